@@ -69,6 +69,14 @@ async backendCatalogSync(force: boolean | null) : Promise<Result<CatalogSyncResu
     else return { status: "error", error: e  as any };
 }
 },
+async backendCatalogSyncCancel(operationId: string | null) : Promise<Result<CatalogSyncCancelResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_sync_cancel", { operationId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async backendCatalogSearch(query: string | null, limit: number | null, offset: number | null) : Promise<Result<CatalogSearchResult, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("backend_catalog_search", { query, limit, offset }) };
@@ -77,9 +85,13 @@ async backendCatalogSearch(query: string | null, limit: number | null, offset: n
     else return { status: "error", error: e  as any };
 }
 },
-async searchYoutubeTrailer(gameName: string) : Promise<Result<string | null, string>> {
+/**
+ * Video id of a game's trailer. With `package_name`, the user's
+ * `trailers.json` override for the package wins over the search.
+ */
+async searchYoutubeTrailer(gameName: string, packageName: string | null) : Promise<Result<string | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("search_youtube_trailer", { gameName }) };
+    return { status: "ok", data: await TAURI_INVOKE("search_youtube_trailer", { gameName, packageName }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -101,6 +113,17 @@ async backendCatalogGameVersions(packageName: string) : Promise<Result<CatalogGa
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Compare two releases of a package; `to_release` defaults to the newest one.
+ */
+async backendCatalogVersionDiff(packageName: string, fromRelease: string, toRelease: string | null) : Promise<Result<ReleaseDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_version_diff", { packageName, fromRelease, toRelease }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async backendCatalogThumbnailPath(packageName: string) : Promise<Result<CatalogThumbnailPath, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("backend_catalog_thumbnail_path", { packageName }) };
@@ -117,6 +140,58 @@ async backendCatalogNote(packageName: string) : Promise<Result<CatalogNote, stri
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Diagnostics for the last parsed game list: schema, rejected rows,
+ * duplicates and unparsable version codes. `None` until a list has been parsed.
+ */
+async backendCatalogParseReport() : Promise<Result<CatalogParseReport | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_parse_report") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The configured catalog sources, the public one first, with how many releases
+ * each currently contributes.
+ */
+async backendCatalogSources() : Promise<Result<CatalogSourceInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_sources") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Where the public config is fetched from and how each source has done this
+ * session.
+ */
+async backendConfigSources() : Promise<Result<ConfigSourceHealth[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_config_sources") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCatalogAnnotations(packageName: string) : Promise<Result<CatalogAnnotations, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_annotations", { packageName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCatalogAnnotationSet(packageName: string, key: string, value: string | null) : Promise<Result<CatalogAnnotations, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_annotation_set", { packageName, key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async backendCatalogLibrary(query: string | null, sortBy: string | null, sortAscending: boolean | null, filter: string | null, limit: number | null, offset: number | null) : Promise<Result<LibraryResult, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("backend_catalog_library", { query, sortBy, sortAscending, filter, limit, offset }) };
@@ -125,6 +200,42 @@ async backendCatalogLibrary(query: string | null, sortBy: string | null, sortAsc
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Write the library view for `query`/`filter` to a JSON or CSV file, chosen
+ * by the extension of `path`. With `all_versions` and no query, every release
+ * is exported instead of the newest release per game.
+ */
+async backendCatalogExport(path: string, query: string | null, sortBy: string | null, sortAscending: boolean | null, filter: string | null, allVersions: boolean | null) : Promise<Result<CatalogExportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_export", { path, query, sortBy, sortAscending, filter, allVersions }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import in-house builds from a JSON or CSV list in the export format. Each
+ * entry needs a `package_name` and a `path` to a folder with an APK or
+ * install.txt; relative paths are resolved against the list's folder.
+ * Imported entries are listed under the `local` source and installed straight
+ * from their folder.
+ */
+async backendCatalogImportLocal(path: string) : Promise<Result<LocalImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_import_local", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCatalogLocalRemove(releaseName: string) : Promise<Result<LocalRemoveResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_catalog_local_remove", { releaseName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async backendFavoritesToggle(packageName: string) : Promise<Result<FavoritesToggleResult, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("backend_favorites_toggle", { packageName }) };
@@ -133,6 +244,144 @@ async backendFavoritesToggle(packageName: string) : Promise<Result<FavoritesTogg
     else return { status: "error", error: e  as any };
 }
 },
+async backendWatchlistGet() : Promise<Result<WatchlistResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_watchlist_get") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Watch or unwatch a package. A new watch starts from the package's current
+ * newest release, so only later releases are reported; `auto_queue` also
+ * updates an existing watch.
+ */
+async backendWatchlistSet(packageName: string, watched: boolean, autoQueue: boolean | null) : Promise<Result<WatchlistResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_watchlist_set", { packageName, watched, autoQueue }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCollectionsList() : Promise<Result<CollectionListResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collections_list") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCollectionCreate(name: string, packageNames: string[] | null) : Promise<Result<GameCollection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_create", { name, packageNames }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCollectionRename(collectionId: string, name: string) : Promise<Result<GameCollection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_rename", { collectionId, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCollectionDelete(collectionId: string) : Promise<Result<CollectionListResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_delete", { collectionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move a collection to `position` in the list.
+ */
+async backendCollectionReorder(collectionId: string, position: number) : Promise<Result<CollectionListResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_reorder", { collectionId, position }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCollectionAddGames(collectionId: string, packageNames: string[]) : Promise<Result<GameCollection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_add_games", { collectionId, packageNames }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async backendCollectionRemoveGames(collectionId: string, packageNames: string[]) : Promise<Result<GameCollection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_remove_games", { collectionId, packageNames }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move a member to `position` within its collection.
+ */
+async backendCollectionMoveGame(collectionId: string, packageName: string, position: number) : Promise<Result<GameCollection, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_move_game", { collectionId, packageName, position }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the given collections, or all of them, to a JSON file that
+ * `backend_collections_import` reads back.
+ */
+async backendCollectionsExport(path: string, collectionIds: string[] | null) : Promise<Result<CollectionExportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collections_export", { path, collectionIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Merge collections from an exported file. Collections are matched by name;
+ * a match gains the packages it is missing.
+ */
+async backendCollectionsImport(path: string) : Promise<Result<CollectionImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collections_import", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Queue the newest release of every member that isn't queued or downloaded yet.
+ */
+async backendCollectionQueue(collectionId: string) : Promise<Result<CollectionQueueResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_queue", { collectionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Install every downloaded member, and every local entry, onto the selected
+ * device one after another. Progress is reported under one operation.
+ */
+async backendCollectionInstall(collectionId: string) : Promise<Result<CollectionInstallResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_collection_install", { collectionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async backendDownloadQueueStatus() : Promise<Result<DownloadQueueStatus, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("backend_download_queue_status") };
@@ -165,6 +414,18 @@ async backendDownloadQueueReorder(packageName: string, position: number) : Promi
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Set the priority of a queued download; higher priorities take the next
+ * free slot first.
+ */
+async backendDownloadSetPriority(packageName: string, priority: number) : Promise<Result<DownloadQueueStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backend_download_set_priority", { packageName, priority }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async backendDownloadStartProcessing() : Promise<Result<DownloadStartResult, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("backend_download_start_processing") };
@@ -685,21 +946,211 @@ export type BackupAppResult = { operation_id: string; package_name: string }
 export type BackupInfo = { package_name: string; backup_path: string; size_bytes: number; created_at: number }
 export type BulkBackupResult = { operation_id: string; app_count: number }
 export type BulkRestoreResult = { operation_id: string; backup_count: number }
+/**
+ * Free-form user annotations for a package, keyed by annotation name
+ */
+export type CatalogAnnotations = { package_name: string; annotations: Partial<{ [key in string]: string }> }
+export type CatalogExportResult = { path: string; 
+/**
+ * `json` or `csv`
+ */
+format: string; count: number }
 /**
  * Full game details (used when fetching single game)
  */
-export type CatalogGameDetail = { game_name: string; release_name: string; package_name: string; version_code: string; version_name: string; size: string; last_updated: string; downloads: string; release_apk_path: string; thumbnail_path: string; thumbnail_exists: boolean; note_path: string; note_excerpt: string; note_exists: boolean }
+export type CatalogGameDetail = { game_name: string; release_name: string; package_name: string; version_code: string; version_name: string; size: string; last_updated: string; downloads: string; release_apk_path: string; thumbnail_path: string; thumbnail_exists: boolean; note_path: string; note_excerpt: string; note_exists: boolean; 
+/**
+ * Warnings found in the release note, e.g. an `install.txt` or a modded build
+ */
+note_warnings: NoteWarning[]; 
+/**
+ * Catalog source the release is downloaded from
+ */
+source: string }
 export type CatalogGameVersions = { games: CatalogSearchGame[] }
 export type CatalogLoadCacheResult = { loaded: boolean; status: CatalogStatus }
-export type CatalogNote = { note: string }
+export type CatalogNote = { note: string; parsed: ReleaseNotes }
+/**
+ * What happened while parsing a `VRP-GameList.txt`, so rows that were dropped
+ * or read with the wrong column layout are visible instead of silently lost.
+ */
+export type CatalogParseReport = { 
+/**
+ * Unix seconds when the list was parsed
+ */
+parsed_at: number; 
+/**
+ * Schema named by the header, or the one most rows looked like
+ */
+schema: CatalogSchema | null; 
+/**
+ * Whether `schema` came from the header rather than the rows
+ */
+schema_from_header: boolean; header: string[]; total_rows: number; accepted_rows: number; modern_rows: number; legacy_rows: number; 
+/**
+ * Rows whose values looked like the other schema than the header names
+ */
+schema_mismatches: number; rejected_count: number; rejected: RejectedRow[]; 
+/**
+ * Rows repeating a release name seen earlier in the list
+ */
+duplicate_keys: number; 
+/**
+ * Rejected rows whose version code was not an integer
+ */
+invalid_version_codes: number }
+/**
+ * Column layout of a `VRP-GameList.txt`.
+ */
+export type CatalogSchema = 
+/**
+ * `name;release;package;version;last_updated;size;downloads[;...]`
+ */
+"modern" | 
+/**
+ * `name;release;package;version;apk_path;version_name;downloads;size;last_updated`
+ */
+"legacy"
 /**
  * A game in catalog search results (condensed view)
  */
 export type CatalogSearchGame = { package_name: string; release_name: string; game_name: string; version_code: string; version_name: string; size: string; last_updated: string; downloads: string }
 export type CatalogSearchResult = { games: CatalogSearchGame[]; total: number; offset: number; limit: number; query: string }
-export type CatalogStatus = { synced: boolean; source: string; game_count: number; has_config: boolean; config_base_uri?: string | null; sync_error?: string | null; thumbnails_dir: string; notes_dir: string; cache_dir: string; cache_age_hours?: number | null; cache_stale?: boolean | null; sync_in_progress: boolean }
-export type CatalogSyncResult = { synced: boolean; status: CatalogStatus }
+/**
+ * An additional catalog source merged with the public one.
+ */
+export type CatalogSource = { name: string; kind: CatalogSourceKind; 
+/**
+ * Base URI for remote sources, folder path for local ones
+ */
+base_uri: string; 
+/**
+ * Password of the source's `meta.7z`. Only set by settings updates: it is
+ * moved into the source secrets before the settings are saved.
+ */
+password: string; 
+/**
+ * Key of the password in the source secrets, empty without one
+ */
+password_ref: string; 
+/**
+ * rclone remote name; defaults to `name`
+ */
+remote_name: string; 
+/**
+ * Game list inside the metadata; defaults to `VRP-GameList.txt`
+ */
+list_file: string; 
+/**
+ * When several sources list the same release, the highest priority wins.
+ * The public source has priority 0.
+ */
+priority: number; enabled: boolean }
+export type CatalogSourceInfo = { name: string; kind: CatalogSourceKind; remote_name: string; list_file: string; priority: number; release_count: number }
+export type CatalogSourceKind = 
+/**
+ * An HTTP mirror laid out like the public one: `meta.7z` plus one folder per release hash
+ */
+"remote" | 
+/**
+ * A local folder holding the list file and one folder per release name
+ */
+"local"
+export type CatalogStatus = { synced: boolean; source: string; game_count: number; has_config: boolean; config_base_uri?: string | null; sync_error?: string | null; thumbnails_dir: string; notes_dir: string; cache_dir: string; cache_age_hours?: number | null; cache_stale?: boolean | null; sync_in_progress: boolean; 
+/**
+ * Operation id of the sync that is running, for `backend_catalog_sync_cancel`
+ */
+sync_operation_id?: string | null }
+export type CatalogSyncCancelResult = { 
+/**
+ * False when no matching sync was running, or it already committed the
+ * new catalog and will finish
+ */
+cancelled: boolean; operation_id?: string | null }
+export type CatalogSyncResult = { 
+/**
+ * False when another sync was already running
+ */
+synced: boolean; status: CatalogStatus; 
+/**
+ * Diagnostics for the game list that is currently loaded
+ */
+parse_report?: CatalogParseReport | null; 
+/**
+ * Thumbnail and note changes, when this sync refreshed them
+ */
+media?: MediaSyncReport | null; 
+/**
+ * Absent when the cache was fresh and no sync ran, the running sync's id
+ * when one was already in progress
+ */
+operation_id?: string | null; summary?: CatalogSyncSummary | null; 
+/**
+ * New releases of watched packages found by this sync
+ */
+watch_alerts: WatchAlert[] }
+/**
+ * How a catalog sync operation ended and how long each phase took.
+ */
+export type CatalogSyncSummary = { operation_id: string; outcome: SyncOutcome; 
+/**
+ * Phases that ran, in order; the last one is where a failed sync stopped
+ */
+phases: PhaseTiming[]; total_ms: number; 
+/**
+ * Bytes of `meta.7z` transferred, 0 when it was already up to date
+ */
+bytes_downloaded: number; 
+/**
+ * The remote `meta.7z` matched the local copy, so download and extraction
+ * were skipped
+ */
+metadata_unchanged: boolean; error?: string | null }
 export type CatalogThumbnailPath = { thumbnail_path: string; thumbnail_exists: boolean }
+export type CollectionExportResult = { path: string; count: number }
+export type CollectionImportResult = { created: number; 
+/**
+ * Existing collections, matched by name, that gained packages
+ */
+merged: number; collections: GameCollection[] }
+export type CollectionInstallResult = { 
+/**
+ * Operation of the whole batch; members are installed one after another
+ */
+operation_id: string; collection_id: string; packages: string[]; skipped: CollectionSkippedGame[] }
+export type CollectionListResult = { collections: GameCollection[] }
+export type CollectionQueueResult = { collection_id: string; queued: string[]; skipped: CollectionSkippedGame[]; queue_length: number }
+/**
+ * A member left out of a bulk action, with a reason such as
+ * `not_in_catalog`, `already_queued` or `not_downloaded`.
+ */
+export type CollectionSkippedGame = { package_name: string; reason: string }
+/**
+ * Where a config source was configured; the environment overrides settings,
+ * and either replaces the built-in URLs.
+ */
+export type ConfigOrigin = "default" | "settings" | "environment"
+/**
+ * How fetching the public config from one source has gone this session.
+ */
+export type ConfigSourceHealth = { 
+/**
+ * URL or file path
+ */
+location: string; kind: ConfigSourceKind; origin: ConfigOrigin; 
+/**
+ * Unix seconds of the last fetch that returned a valid config
+ */
+last_success_at?: number | null; last_error?: string | null; last_error_at?: number | null; 
+/**
+ * Duration of the last attempt
+ */
+latency_ms?: number | null; consecutive_failures: number }
+export type ConfigSourceKind = "url" | 
+/**
+ * A local `vrp-public.json`, e.g. for air-gapped use
+ */
+"file"
 export type CrashDismissResult = { dismissed: boolean }
 export type CrashInfo = { has_crash: boolean; crash_id: string | null; timestamp: number | null; error_message: string | null }
 export type CrashReportResult = { reported: boolean; report_id: string | null }
@@ -719,16 +1170,77 @@ export type DownloadCancelResult = { cancelled: boolean }
 export type DownloadCheckLocalResult = { package_name: string; has_local_files: boolean; local_size_bytes: number }
 export type DownloadDeleteResult = { deleted: boolean; freed_bytes: number }
 export type DownloadListLocalResult = { files: LocalDownloadFile[]; count: number }
-export type DownloadLocation = { download_dir: string; path: string; exists: boolean; free_bytes: number; file_count: number }
+export type DownloadLocation = { download_dir: string; path: string; exists: boolean; free_bytes: number; 
+/**
+ * Space the pending downloads still need, extraction included
+ */
+queued_bytes: number; file_count: number }
 export type DownloadOpenFolderResult = { opened: boolean }
-export type DownloadQueueAddResult = { added: boolean; retried: boolean; package_name: string; queue_length: number; reason?: string | null }
-export type DownloadQueueItem = { package_name: string; release_name: string; game_name?: string | null; status: string; progress_percent: number; speed: string; eta: string; bytes_transferred: number; total_bytes: number; retry_count?: number | null; error?: string | null }
+export type DownloadQueueAddResult = { added: boolean; retried: boolean; package_name: string; queue_length: number; reason?: string | null; 
+/**
+ * Why the game wasn't added, or what to watch out for when it was
+ */
+warning?: string | null }
+export type DownloadQueueItem = { package_name: string; release_name: string; game_name?: string | null; status: string; progress_percent: number; speed: string; eta: string; bytes_transferred: number; total_bytes: number; 
+/**
+ * Higher priorities download first
+ */
+priority: number; 
+/**
+ * Download slot, from 0, while downloading
+ */
+slot?: number | null; 
+/**
+ * Retries made after the first attempt
+ */
+retry_count?: number | null; 
+/**
+ * RFC 3339 time a failed download is retried at
+ */
+next_retry_at?: string | null; 
+/**
+ * Classified cause of the last failure, e.g. "timeout" or "disk_full"
+ */
+failure_reason?: string | null; 
+/**
+ * Whether the last failure may go away on a retry
+ */
+failure_transient?: boolean | null; error?: string | null }
 export type DownloadQueueRemoveResult = { removed: boolean; package_name: string }
-export type DownloadQueueStatus = { queue: DownloadQueueItem[]; queued_count: number; total_count: number; processing: boolean; active_download?: DownloadQueueItem | null }
+export type DownloadQueueStatus = { queue: DownloadQueueItem[]; queued_count: number; total_count: number; processing: boolean; 
+/**
+ * First running download, kept for callers that show a single one
+ */
+active_download?: DownloadQueueItem | null; 
+/**
+ * Every running download, in slot order
+ */
+active_downloads: DownloadQueueItem[]; 
+/**
+ * Downloads run at once
+ */
+slots: number }
 export type DownloadRetryResult = { retried: boolean }
 export type DownloadStartResult = { started: boolean }
 export type FavoritesToggleResult = { package_name: string; is_favorite: boolean; favorites: string[] }
-export type Game = { game_name: string; release_name: string; package_name: string; version_code: string; release_apk_path: string; version_name: string; downloads: string; size: string; last_updated: string; thumbnail_path: string; thumbnail_exists: boolean; note_path: string; note_excerpt: string; note_exists: boolean; popularity_rank: number; is_new: boolean }
+export type Game = { game_name: string; release_name: string; package_name: string; version_code: string; release_apk_path: string; version_name: string; downloads: string; size: string; last_updated: string; thumbnail_path: string; thumbnail_exists: boolean; note_path: string; note_excerpt: string; note_exists: boolean; popularity_rank: number; is_new: boolean; 
+/**
+ * Catalog source the release came from, see `models::config::CatalogSource`
+ */
+source?: string; 
+/**
+ * From release name markers, the release note and the user tag file,
+ * see `models::tags`
+ */
+tags?: string[] }
+/**
+ * A named, user-ordered list of packages, e.g. "Demo day headsets".
+ */
+export type GameCollection = { id: string; name: string; 
+/**
+ * Package names in the order the user arranged them
+ */
+packages: string[] }
 export type InstallCancelResult = { cancelled: boolean }
 export type InstallGameResult = { operation_id: string; package_name: string }
 export type InstallLocalResult = { success: boolean; message: string }
@@ -736,13 +1248,41 @@ export type InstallStatus = { is_installing: boolean; current_package: string |
 export type InstalledApp = { package_name: string; app_name: string; version_code: string; version_name: string; is_system_app: boolean; install_time: number | null; last_update_time: number | null; in_catalog: boolean; game_name: string | null; catalog_version_code: string | null; installed_version_code: string | null; size: string | null; update_available: boolean }
 export type InstalledAppsResult = { apps: InstalledApp[]; count: number; has_updates: boolean }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * Counts over every game matching the query and filter, before paging
+ */
+export type LibraryFacets = { 
+/**
+ * Most common first
+ */
+tags: TagFacet[]; sizes: SizeFacet[]; favorites: number; downloaded: number; 
+/**
+ * `None` when no device is connected
+ */
+installed: number | null }
 /**
  * A game in the library view (with UI-specific fields)
  */
-export type LibraryGame = { package_name: string; release_name: string; game_name: string; size: string; last_updated: string; version_code: string; downloads: string; is_favorite: boolean; is_new: boolean; popularity_rank: number; is_downloaded: boolean }
-export type LibraryResult = { games: LibraryGame[]; total: number; offset: number; limit: number; query: string; sort_by: string; sort_ascending: boolean; filter: string; favorites_count: number }
+export type LibraryGame = { package_name: string; release_name: string; game_name: string; size: string; last_updated: string; version_code: string; downloads: string; is_favorite: boolean; is_new: boolean; popularity_rank: number; 
+/**
+ * Downloads and rank movement across recent syncs, `None` before the
+ * first sync that recorded the package
+ */
+trend: PopularityTrend | null; is_downloaded: boolean; source: string; tags: string[] }
+export type LibraryResult = { games: LibraryGame[]; total: number; offset: number; limit: number; query: string; sort_by: string; sort_ascending: boolean; filter: string; favorites_count: number; 
+/**
+ * Tags the query requires, from its `tag:` clauses
+ */
+tags: string[]; facets: LibraryFacets }
 export type ListBackupsResult = { backups: BackupInfo[] }
 export type LocalDownloadFile = { name: string; package_name: string; size_bytes: number; modified_at: number | null }
+export type LocalImportRejection = { 
+/**
+ * 0-based position of the entry in the imported list
+ */
+index: number; release_name: string; reason: string }
+export type LocalImportResult = { imported: number; rejected: LocalImportRejection[] }
+export type LocalRemoveResult = { removed: boolean; release_name: string }
 export type LogEntriesResult = { entries: LogEntry[] }
 export type LogEntry = { timestamp: number; level: string; message: string; source: string }
 export type LogExportResult = { exported: boolean; path: string | null }
@@ -750,9 +1290,57 @@ export type LogResetResult = { reset: boolean }
 export type LogUploadPayload = { payload: string }
 export type MediaFile = { name: string; path: string; size_bytes: number; mime_type: string }
 export type MediaListResult = { files: MediaFile[]; total_size: number }
+export type MediaSyncCounts = { added: number; updated: number; removed: number; unchanged: number }
+/**
+ * What a sync changed in the thumbnail and note caches.
+ */
+export type MediaSyncReport = { thumbnails: MediaSyncCounts; notes: MediaSyncCounts }
 export type MediaTransferResult = { transferred: boolean; bytes_transferred: number; destination_path: string }
-export type NewAppInfo = { package_name: string; app_name: string; install_date: number }
-export type NewAppsDiscoveryResult = { new_apps: NewAppInfo[]; count: number }
+export type NewAppInfo = { package_name: string; app_name: string; 
+/**
+ * Unix seconds of the sync that first saw the package (or its new version)
+ */
+install_date: number; 
+/**
+ * "added" or "updated"
+ */
+change: string; version_code: string; previous_version_code?: string | null }
+export type NewAppsDiscoveryResult = { new_apps: NewAppInfo[]; count: number; 
+/**
+ * Packages dropped from the catalog by the most recent change
+ */
+removed: string[]; 
+/**
+ * Unix seconds of the sync the most recent change is compared against
+ */
+since?: number | null }
+export type NoteSection = { 
+/**
+ * Empty for text before the first heading
+ */
+title: string; lines: string[]; bullets: string[] }
+export type NoteWarning = { kind: NoteWarningKind; 
+/**
+ * The note line the warning was found on
+ */
+text: string }
+export type NoteWarningKind = 
+/**
+ * The release ships an `install.txt` with extra install steps
+ */
+"install_script" | 
+/**
+ * Modded or patched build
+ */
+"mod" | 
+/**
+ * Something else the release needs, e.g. "Requires OBB on first launch"
+ */
+"requirement" | 
+/**
+ * A line flagged with "warning", "important", "caution" or "attention"
+ */
+"caution"
 export type OfflineModeSetResult = { enabled: boolean }
 export type OfflineModeStatus = { enabled: boolean; last_sync_at: number | null; cache_valid: boolean }
 export type OperationKeepAwake = { enabled: boolean; interval_seconds: number; ticks_sent: number; last_sent_at: number | null }
@@ -766,17 +1354,182 @@ export type OperationStatus = { operation_id: string; operation: string; state:
 export type PerformanceProfile = { profile_name: string; cpu_limit_percent: number; memory_limit_mb: number; priority: string }
 export type PerformanceProfileResult = { profile: PerformanceProfile }
 export type PerformanceProfileSetResult = { saved: boolean }
+export type PhaseTiming = { phase: SyncPhase; duration_ms: number }
+/**
+ * How a package's downloads and rank moved over the last 7 and 30 days.
+ * 
+ * When the history doesn't reach back a full window yet, the window is
+ * measured from the oldest sample instead; `tracked_days` says how far back
+ * that is.
+ */
+export type PopularityTrend = { downloads: number; rank: number; 
+/**
+ * Downloads gained in the last 7 days
+ */
+growth_7d: number | null; growth_30d: number | null; 
+/**
+ * Places climbed in the last 7 days, negative when the package dropped
+ */
+rank_change_7d: number | null; rank_change_30d: number | null; tracked_days: number }
 export type PrivacyStatus = { uuid: string; temp_dir_size_bytes: number; can_cleanup: boolean }
 export type PrivacyTempCleanupResult = { cleaned: boolean; freed_bytes: number }
 export type PrivacyUuidRotateResult = { rotated: boolean; new_uuid: string }
-export type PublicConfig = { base_uri: string; password: string }
+export type PublicConfig = { base_uri: string; 
+/**
+ * Wiped from memory when dropped, including in clones
+ */
+password: string }
+/**
+ * A download queue entry saved in the settings, so the queue survives a
+ * restart. The release is looked up in the catalog again on restore; the
+ * names and source are enough to download it if the catalog lacks it.
+ */
+export type QueuedDownload = { package_name: string; 
+/**
+ * Empty for entries saved by older versions, which kept only the package
+ */
+release_name: string; game_name: string; 
+/**
+ * Catalog source the release came from
+ */
+source: string; 
+/**
+ * Queue status, e.g. "queued" or "failed"
+ */
+status: string; error: string; retry_count: number; priority: number; progress_percent: number; bytes_transferred: number; total_bytes: number }
+export type RejectedRow = { 
+/**
+ * 1-based line number in the game list, header included
+ */
+line: number; reason: RowRejection; message: string; content: string }
+/**
+ * What changes between two releases of one package, `from` to `to`.
+ */
+export type ReleaseDiff = { package_name: string; from_release: string; to_release: string; 
+/**
+ * Where `to` ranks against `from`
+ */
+ordering: VersionOrdering; from_version_code: number; to_version_code: number; from_version_name: string; to_version_name: string; size_delta_bytes: number; 
+/**
+ * e.g. `+12.5 MB`, `-3 MB` or `0 MB`
+ */
+size_delta: string; 
+/**
+ * Days from `from`'s release date to `to`'s, when both are known
+ */
+days_between?: number | null; game_name_changed: boolean; 
+/**
+ * Words of `to`'s release name missing from `from`'s, e.g. `+MR`
+ */
+release_words_added: string[]; release_words_removed: string[] }
+/**
+ * A release note from the metadata archive, split into its parts.
+ * 
+ * Notes are free-form text written by different uploaders, so this is a best
+ * effort: headings are `# Title`, `[Title]`, `== Title ==`, a short line
+ * ending in a colon or a short all-caps line; bullets start with `-`, `*`,
+ * `•`, `+` or `1.`/`1)`.
+ */
+export type ReleaseNotes = { 
+/**
+ * Plain lines before the first heading, joined with spaces
+ */
+summary: string; sections: NoteSection[]; 
+/**
+ * Bullets of sections titled like a changelog ("Changes", "What's new", ...)
+ */
+changelog: string[]; warnings: NoteWarning[]; links: string[] }
 export type RestoreAppResult = { operation_id: string; package_name: string }
-export type Settings = { download_dir: string; delete_after_install: boolean; bandwidth_limit_mbps: number; ip_address: string; wireless_adb: boolean; favorited_games: string[]; use_gallery_view: boolean; sort_column: number; sort_ascending: boolean; queued_games: string[]; keep_awake_during_long_ops: boolean; keep_awake_interval_seconds: number; first_run: boolean; offline_mode: boolean; window_width: number; window_height: number; window_x: number; window_y: number; window_maximized: boolean; diagnostics_uuid: string; known_catalog_packages: string[]; performance_refresh_rate_hz: number; performance_cpu_level: number; performance_gpu_level: number; performance_texture_size: number }
+/**
+ * Why a catalog row could not be turned into a [`Game`].
+ */
+export type RowRejection = "too_few_fields" | "missing_package_name" | "invalid_version_code"
+export type Settings = { download_dir: string; delete_after_install: boolean; bandwidth_limit_mbps: number; 
+/**
+ * Downloads run at once, from 1 to `MAX_DOWNLOAD_SLOTS`
+ */
+concurrent_downloads: number; 
+/**
+ * Retries of a download that failed for a transient reason, 0 for none
+ */
+download_max_retries: number; ip_address: string; wireless_adb: boolean; favorited_games: string[]; 
+/**
+ * Named collections in the order the user arranged them
+ */
+collections: GameCollection[]; 
+/**
+ * Packages to report new releases of after each catalog sync
+ */
+watched_packages: WatchedPackage[]; use_gallery_view: boolean; sort_column: number; sort_ascending: boolean; 
+/**
+ * The download queue as of the last change, restored on startup
+ */
+queued_games: QueuedDownload[]; keep_awake_during_long_ops: boolean; keep_awake_interval_seconds: number; first_run: boolean; offline_mode: boolean; 
+/**
+ * URL or local file path of a `vrp-public.json` to use instead of the
+ * built-in URLs; the `VETERAN_CONFIG_SOURCE` environment variable wins
+ */
+config_source: string; 
+/**
+ * Hours a synced catalog is used as is before the remote is checked for a
+ * new `meta.7z`; 0 checks on every sync
+ */
+catalog_cache_ttl_hours: number; 
+/**
+ * Hours a cached catalog is still accepted in offline mode; 0 accepts any age
+ */
+catalog_max_staleness_hours: number; window_width: number; window_height: number; window_x: number; window_y: number; window_maximized: boolean; diagnostics_uuid: string; known_catalog_packages: string[]; new_app_window_days: number; 
+/**
+ * Mirrors and local folders merged into the catalog alongside the public source
+ */
+catalog_sources: CatalogSource[]; performance_refresh_rate_hz: number; performance_cpu_level: number; performance_gpu_level: number; performance_texture_size: number }
 export type SettingsResponse = { download_dir: string; auto_install: boolean; auto_backup: boolean; backup_dir: string; theme: string; language: string; enable_notifications: boolean; concurrent_downloads: number; favorited_games: string[]; wireless_auto_reconnect: boolean }
 export type SettingsUpdateResponse = { updated: boolean; settings: SettingsResponse }
+/**
+ * Size ranges the library counts games in, for its filter chips.
+ */
+export type SizeBucket = "small" | "medium" | "large" | "huge" | "unknown"
+export type SizeFacet = { bucket: SizeBucket; label: string; 
+/**
+ * Query clauses that select the bucket, empty for unknown sizes
+ */
+query: string; count: number }
+export type SyncOutcome = "succeeded" | "failed" | "cancelled"
+/**
+ * Steps of a catalog sync, in the order they run.
+ */
+export type SyncPhase = "config_fetch" | "remote_check" | "metadata_download" | "extraction" | "parse" | "media_copy"
+export type TagFacet = { tag: string; count: number }
 export type UninstallResult = { uninstalled: boolean; package_name: string; message: string | null }
 export type UpdateAppResult = { operation_id: string; package_name: string }
 export type UpdateInfo = { package_name: string; current_version: string; available_version: string; update_size: string }
+export type VersionOrdering = "older" | "same" | "newer"
+/**
+ * A sync found a new release of a watched package.
+ */
+export type WatchAlert = { package_name: string; game_name: string; 
+/**
+ * Absent when the package wasn't listed before
+ */
+from_release?: string | null; to_release: string; from_version_code?: number | null; to_version_code: number; from_size_bytes?: number | null; to_size_bytes: number; 
+/**
+ * Whether the release was queued for download
+ */
+queued: boolean }
+/**
+ * A package watched for new releases, installed or not.
+ */
+export type WatchedPackage = { package_name: string; 
+/**
+ * Queue a new release for download as soon as a sync finds it
+ */
+auto_queue: boolean; 
+/**
+ * Release with the highest version code seen so far, empty until the
+ * catalog lists the package
+ */
+release_name: string; version_code: number; size_bytes: number }
+export type WatchlistResult = { watched: WatchedPackage[] }
 export type WindowState = { width: number; height: number; x: number; y: number; maximized: boolean }
 export type WindowStateResult = { state: WindowState }
 export type WindowStateSetResult = { saved: boolean }
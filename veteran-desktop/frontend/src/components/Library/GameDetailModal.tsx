@@ -49,7 +49,7 @@ export const GameDetailModal: React.FC<GameDetailModalProps> = ({
           setThumbnailSrc(api.convertFileSrc(path));
         }
 
-        const videoId = await api.searchYoutubeTrailer(game.game_name);
+        const videoId = await api.searchYoutubeTrailer(game.game_name, game.package_name);
         setTrailerId(videoId);
       } catch (e) {
         console.error('Failed to fetch trailer:', e);
@@ -124,6 +124,7 @@ export default function LibraryView() {
             >
               <option value="name">Sort: Name</option>
               <option value="popularity">Sort: Popularity</option>
+              <option value="trending">Sort: Trending</option>
               <option value="date">Sort: Date</option>
               <option value="size">Sort: Size</option>
             </select>
@@ -139,7 +140,7 @@ export default function LibraryView() {
       </div>
 
       <div className={styles['library-filters']}>
-        {['all', 'favorites', 'new', 'popular'].map(f => (
+        {['all', 'favorites', 'new', 'popular', 'local'].map(f => (
             <button 
                 key={f}
                 className={`${styles['filter-chip']}${filter === f ? ` ${styles['active']}` : ''}`} 
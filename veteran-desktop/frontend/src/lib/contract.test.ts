@@ -0,0 +1,32 @@
+// @ts-nocheck
+import { describe, expect, it } from "vitest";
+import { assertLibraryResult } from "./contract";
+import { assertLibraryResult as assertIpcLibraryResult } from "../../ipc-contract.mjs";
+
+function libraryPayload(filter: string) {
+  return {
+    games: [{ is_favorite: false, is_new: false, popularity_rank: 1 }],
+    total: 1,
+    offset: 0,
+    limit: 50,
+    query: "",
+    sort_by: "name",
+    sort_ascending: true,
+    filter,
+    favorites_count: 0,
+  };
+}
+
+describe.each([
+  ["contract.ts", assertLibraryResult],
+  ["ipc-contract.mjs", assertIpcLibraryResult],
+])("%s assertLibraryResult", (_, assert) => {
+  it.each(["all", "favorites", "new", "popular", "local"])("accepts the %s filter", (filter) => {
+    const payload = libraryPayload(filter);
+    expect(assert(payload)).toBe(payload);
+  });
+
+  it("rejects unknown filters", () => {
+    expect(() => assert(libraryPayload("installed"))).toThrow(/library\.filter/);
+  });
+});
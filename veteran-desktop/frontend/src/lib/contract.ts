@@ -503,8 +503,8 @@ export function assertCatalogSearchResult(payload: any): CatalogSearchResult {
   return payload;
 }
 
-const LIBRARY_SORT_COLUMNS = new Set(["name", "popularity", "date", "size"]);
-const LIBRARY_FILTERS = new Set(["all", "favorites", "new", "popular"]);
+const LIBRARY_SORT_COLUMNS = new Set(["name", "popularity", "trending", "date", "size", "relevance"]);
+const LIBRARY_FILTERS = new Set(["all", "favorites", "new", "popular", "local"]);
 
 export function assertLibraryResult(payload: any): LibraryResult {
   if (!isObject(payload)) {
@@ -540,13 +540,13 @@ export function assertLibraryResult(payload: any): LibraryResult {
     throw new Error("library.query must be a string.");
   }
   if (typeof payload.sort_by !== "string" || !LIBRARY_SORT_COLUMNS.has(payload.sort_by)) {
-    throw new Error("library.sort_by must be one of name/popularity/date/size.");
+    throw new Error("library.sort_by must be one of name/popularity/trending/date/size/relevance.");
   }
   if (typeof payload.sort_ascending !== "boolean") {
     throw new Error("library.sort_ascending must be a boolean.");
   }
   if (typeof payload.filter !== "string" || !LIBRARY_FILTERS.has(payload.filter)) {
-    throw new Error("library.filter must be one of all/favorites/new/popular.");
+    throw new Error("library.filter must be one of all/favorites/new/popular/local.");
   }
   if (!Number.isInteger(payload.favorites_count) || payload.favorites_count < 0) {
     throw new Error("library.favorites_count must be a non-negative integer.");
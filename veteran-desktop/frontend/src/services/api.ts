@@ -43,8 +43,8 @@ export const api = {
     return assertCatalogStatusSnapshot(payload);
   },
 
-  searchYoutubeTrailer: async (gameName: string) => {
-    const result = await commands.searchYoutubeTrailer(gameName);
+  searchYoutubeTrailer: async (gameName: string, packageName: string | null = null) => {
+    const result = await commands.searchYoutubeTrailer(gameName, packageName);
     if (result.status === "error") throw new Error(result.error);
     return result.data;
   },
@@ -1,19 +1,33 @@
-use std::collections::HashMap;
-use regex::Regex;
-use crate::models::game::Game;
+use std::collections::{HashMap, HashSet};
+use crate::models::collection::{self, CollectionError, CollectionFile, GameCollection};
+use crate::models::catalog_report::{CatalogParseReport, PopularityTrend, SyncOutcome, SyncPhase};
+use crate::models::config::{CatalogSource, CatalogSourceKind, ConfigSourceHealth, DEFAULT_LIST_FILE, PRIMARY_SOURCE};
+use crate::models::game::{parse_version_code, Game};
+use crate::models::release_notes::ReleaseNotes;
+use crate::models::size::SizeBucket;
+use crate::models::version::ReleaseDiff;
+use crate::models::watchlist::{check_watchlist, WatchAlert, WatchedPackage};
 use crate::models::responses::*;
 use crate::services::adb::AdbService;
 use serde_json::Value;
-use crate::services::catalog::CatalogService;
+use crate::services::catalog::{CatalogService, SourceList};
+use crate::services::catalog_diff::CatalogDiff;
+use crate::services::catalog_sync::{cache_decision, CacheDecision, SyncHandle, SyncOperation, CANCELLED_MESSAGE};
+use crate::services::catalog_export::{read_records, write_records, CatalogRecord, ExportFormat};
 use crate::services::config::ConfigService;
-use crate::services::download::{DownloadService, DownloadStatus};
+use crate::services::download::{downloaded_hashes, DownloadItem, DownloadService, DownloadStatus};
+use crate::services::download_retry::RetryPolicy;
 use crate::services::extract::ExtractService;
 use crate::services::install::InstallService;
+use crate::services::popularity::compare_trending;
+use crate::services::query::{CatalogQuery, FilterContext};
 use crate::services::rclone::RcloneService;
 use crate::services::settings::SettingsService;
+use crate::services::trailer::TrailerService;
 use serde_json::json;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{State, Wry};
 use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
@@ -30,8 +44,13 @@ pub struct AppState {
     pub rclone: Arc<RcloneService>,
     install: InstallService,
     selected_serial: Arc<RwLock<Option<String>>>,
-    pub youtube_cache: Arc<Mutex<HashMap<String, Option<String>>>>,
+    /// Packages installed per device serial, so library queries don't run
+    /// `pm list packages` each time. Cleared when an install, uninstall or
+    /// device change may have made it stale.
+    installed_cache: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    trailers: Arc<TrailerService>,
     events: Arc<Mutex<Vec<Value>>>,
+    catalog_sync: Arc<Mutex<Option<SyncHandle>>>,
 }
 
 impl AppState {
@@ -44,6 +63,9 @@ impl AppState {
             .unwrap_or_else(|| PathBuf::from(".veteran").join("cache"));
         
         let config_service = ConfigService::new(Some(cache_dir.clone()));
+        if let Err(e) = config_service.set_configured_source(&settings.config_source) {
+            crate::logger::log(&format!("[CONFIG] Failed to set the config source: {}", e));
+        }
 
         let download_dir = PathBuf::from(&settings.download_dir);
         let rclone = Arc::new(RcloneService::new(Some(crate::services::binary_paths::rclone().to_string_lossy().to_string())));
@@ -52,13 +74,21 @@ impl AppState {
         if let Ok(cached_config) = config_service.load_from_cache() {
             rclone.set_public_config(&cached_config);
         }
+        rclone.set_catalog_sources(&settings.catalog_sources);
         
         let download = DownloadService::new_with_arc(rclone.clone(), download_dir, settings.bandwidth_limit_mbps);
+        download.set_max_slots(settings.concurrent_downloads.max(1) as usize);
+        download.set_retry_policy(RetryPolicy {
+            max_retries: settings.download_max_retries.max(0) as u32,
+            ..RetryPolicy::default()
+        });
         let adb = AdbService::new();
         let install = InstallService::new(adb.clone());
+        let trailers = Arc::new(TrailerService::new(&cache_dir));
         
         // Create catalog service and load cache on startup
         let mut catalog_service = CatalogService::with_cache_dir(cache_dir);
+        catalog_service.set_new_window_days(settings.new_app_window_days.max(0) as u32);
         let _ = catalog_service.load_from_cache();
 
         Self {
@@ -70,8 +100,10 @@ impl AppState {
             rclone,
             install,
             selected_serial: Arc::new(RwLock::new(None)),
-            youtube_cache: Arc::new(Mutex::new(HashMap::new())),
+            installed_cache: Arc::new(Mutex::new(HashMap::new())),
+            trailers,
             events: Arc::new(Mutex::new(Vec::new())),
+            catalog_sync: Arc::new(Mutex::new(None)),
         }
     }
     
@@ -86,6 +118,9 @@ impl AppState {
             .unwrap_or_else(|| PathBuf::from(".veteran").join("cache"));
 
         let config_service = ConfigService::new(Some(cache_dir.clone()));
+        if let Err(e) = config_service.set_configured_source(&settings.config_source) {
+            crate::logger::log(&format!("[CONFIG] Failed to set the config source: {}", e));
+        }
 
         let download_dir = PathBuf::from(&settings.download_dir);
         let rclone = Arc::new(RcloneService::new(Some(crate::services::binary_paths::rclone().to_string_lossy().to_string())));
@@ -94,13 +129,21 @@ impl AppState {
         if let Ok(cached_config) = config_service.load_from_cache() {
             rclone.set_public_config(&cached_config);
         }
+        rclone.set_catalog_sources(&settings.catalog_sources);
         
         let download = DownloadService::new_with_arc(rclone.clone(), download_dir, settings.bandwidth_limit_mbps);
+        download.set_max_slots(settings.concurrent_downloads.max(1) as usize);
+        download.set_retry_policy(RetryPolicy {
+            max_retries: settings.download_max_retries.max(0) as u32,
+            ..RetryPolicy::default()
+        });
         let adb = AdbService::new();
         let install = InstallService::new(adb.clone());
+        let trailers = Arc::new(TrailerService::new(&cache_dir));
 
         // Load catalog from cache immediately for snappy startup
         let mut catalog_service = CatalogService::with_cache_dir(cache_dir);
+        catalog_service.set_new_window_days(settings.new_app_window_days.max(0) as u32);
         let _ = catalog_service.load_from_cache();
 
         Self {
@@ -112,8 +155,10 @@ impl AppState {
             rclone,
             install,
             selected_serial: Arc::new(RwLock::new(None)),
-            youtube_cache: Arc::new(Mutex::new(HashMap::new())),
+            installed_cache: Arc::new(Mutex::new(HashMap::new())),
+            trailers,
             events: Arc::new(Mutex::new(Vec::new())),
+            catalog_sync: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -148,6 +193,7 @@ impl AppState {
         let download = DownloadService::new_with_arc(rclone.clone(), download_dir, 0.0);
         let adb = AdbService::new();
         let install = InstallService::new(adb.clone());
+        let trailers = Arc::new(TrailerService::new(&cache_dir));
 
         Self {
             settings: settings_service,
@@ -158,8 +204,10 @@ impl AppState {
             rclone,
             install,
             selected_serial: Arc::new(RwLock::new(None)),
-            youtube_cache: Arc::new(Mutex::new(HashMap::new())),
+            installed_cache: Arc::new(Mutex::new(HashMap::new())),
+            trailers,
             events: Arc::new(Mutex::new(Vec::new())),
+            catalog_sync: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -180,50 +228,23 @@ impl AppState {
         message: &str,
         progress_percent: f64,
     ) {
-        let mut events = self.events.lock().await;
-        let now = Utc::now().timestamp() as f64;
-        
-        let terminal = state == "succeeded" || state == "failed" || state == "cancelled" || state == "completed";
-        let actual_state = if state == "completed" { "succeeded" } else { state };
-
-        events.push(json!({
-            "schema_version": 1,
-            "kind": "event",
-            "event": event_name,
-            "timestamp": now,
-            "message": message,
-            "operation": {
-                "operation_id": operation_id,
-                "operation": operation_kind,
-                "state": actual_state,
-                "state_version": 1,
-                "state_history": [
-                    {
-                        "version": 1,
-                        "state": actual_state,
-                        "entered_at": now,
-                        "reason": message
-                    }
-                ],
-                "progress": {
-                    "percent": progress_percent,
-                    "completed_steps": 0,
-                    "total_steps": 100
-                },
-                "cancel_requested": false,
-                "cancel_requested_at": Value::Null,
-                "terminal": terminal,
-                "terminal_at": if terminal { json!(now) } else { Value::Null },
-                "keep_awake": {
-                    "enabled": false,
-                    "interval_seconds": 30,
-                    "ticks_sent": 0,
-                    "last_sent_at": Value::Null
-                }
-            },
-            "error": Value::Null,
-            "extra": {}
-        }));
+        let event = operation_event(event_name, operation_id, operation_kind, state, message, progress_percent);
+        self.events.lock().await.push(event);
+    }
+
+    /// Push a one-off notice that is finished as soon as it is sent, with its
+    /// details under `extra`.
+    pub async fn push_notice_event(
+        &self,
+        event_name: &str,
+        operation_id: &str,
+        operation_kind: &str,
+        message: &str,
+        extra: Value,
+    ) {
+        let mut event = operation_event(event_name, operation_id, operation_kind, "succeeded", message, 100.0);
+        event["extra"] = extra;
+        self.events.lock().await.push(event);
     }
 
     pub async fn push_event(&self, event_name: &str, message: &str, progress_percent: f64) {
@@ -236,64 +257,266 @@ impl AppState {
     pub async fn get_events(&self) -> Vec<Value> {
         self.events.lock().await.clone()
     }
+
+    /// Put back the download queue saved on the last run, returning how many
+    /// downloads are waiting to run.
+    pub async fn restore_download_queue(&self) -> usize {
+        let saved = self.settings.get_settings().await.queued_games;
+        if saved.is_empty() {
+            return 0;
+        }
+        let download = self.download.lock().await;
+        let restored = {
+            let catalog = self.catalog.read().await;
+            download
+                .restore(&saved, |package_name, release_name| {
+                    if release_name.is_empty() {
+                        catalog.get_game_by_package(package_name).cloned()
+                    } else {
+                        catalog.get_game_by_package_and_release(package_name, release_name).cloned()
+                    }
+                })
+                .await
+        };
+        let waiting = download
+            .queue()
+            .await
+            .iter()
+            .filter(|item| item.status == DownloadStatus::Queued)
+            .count();
+        drop(download);
+        crate::logger::log(&format!(
+            "[DOWNLOAD] Restored {} of {} saved queue entries, {} waiting",
+            restored,
+            saved.len(),
+            waiting
+        ));
+        // Forget the entries that couldn't be restored
+        save_download_queue(self).await;
+        waiting
+    }
 }
 
-fn map_download_status(status: DownloadStatus) -> &'static str {
-    match status {
-        DownloadStatus::Queued => "queued",
-        DownloadStatus::Downloading => "downloading",
-        DownloadStatus::Paused => "paused",
-        DownloadStatus::Completed => "completed",
-        DownloadStatus::Failed => "failed",
-        DownloadStatus::Cancelled => "cancelled",
+/// Queue progress is saved at most this often; status changes are saved
+/// right away.
+const DOWNLOAD_QUEUE_SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Save the download queue to the settings so it survives a restart.
+async fn save_download_queue(state: &AppState) {
+    let queued = state.download.lock().await.snapshot().await;
+    // Only the queue changes, so settings saved meanwhile by a sync or the
+    // user are kept; nothing is written while the queue is unchanged
+    let saved = state
+        .settings
+        .update_with(|settings| {
+            settings.queued_games = queued;
+            Ok(())
+        })
+        .await;
+    if let Err(e) = saved {
+        crate::logger::log(&format!("[DOWNLOAD] Failed to save the download queue: {}", e));
     }
 }
 
+/// An event of the shape the frontend polls for with `poll_backend_events`.
+fn operation_event(
+    event_name: &str,
+    operation_id: &str,
+    operation_kind: &str,
+    state: &str,
+    message: &str,
+    progress_percent: f64,
+) -> Value {
+    let now = Utc::now().timestamp() as f64;
+    let terminal = state == "succeeded" || state == "failed" || state == "cancelled" || state == "completed";
+    let actual_state = if state == "completed" { "succeeded" } else { state };
+
+    json!({
+        "schema_version": 1,
+        "kind": "event",
+        "event": event_name,
+        "timestamp": now,
+        "message": message,
+        "operation": {
+            "operation_id": operation_id,
+            "operation": operation_kind,
+            "state": actual_state,
+            "state_version": 1,
+            "state_history": [
+                {
+                    "version": 1,
+                    "state": actual_state,
+                    "entered_at": now,
+                    "reason": message
+                }
+            ],
+            "progress": {
+                "percent": progress_percent,
+                "completed_steps": 0,
+                "total_steps": 100
+            },
+            "cancel_requested": false,
+            "cancel_requested_at": Value::Null,
+            "terminal": terminal,
+            "terminal_at": if terminal { json!(now) } else { Value::Null },
+            "keep_awake": {
+                "enabled": false,
+                "interval_seconds": 30,
+                "ticks_sent": 0,
+                "last_sent_at": Value::Null
+            }
+        },
+        "error": Value::Null,
+        "extra": {}
+    })
+}
+
 async fn selected_serial(state: &AppState) -> Option<String> {
     state.selected_serial.read().await.clone()
 }
 
-fn paginate(games: Vec<Game>, limit: u32, offset: u32) -> (Vec<Game>, usize) {
-    let total = games.len();
-    let page = games
-        .into_iter()
-        .skip(offset as usize)
-        .take(limit as usize)
-        .collect::<Vec<_>>();
-    (page, total)
+/// Packages installed on the selected device, from the cache when possible.
+async fn installed_packages(state: &AppState) -> Result<HashSet<String>, String> {
+    let serial = selected_serial(state).await;
+    let key = serial.clone().unwrap_or_default();
+    if let Some(installed) = state.installed_cache.lock().await.get(&key) {
+        return Ok(installed.clone());
+    }
+    let output = state
+        .adb
+        .shell("pm list packages --show-versioncode", serial.as_deref())
+        .await
+        .map_err(|e| format!("no connected device ({e})"))?;
+    let installed: HashSet<String> = AdbService::parse_packages_with_versions_output(&output.stdout)
+        .into_keys()
+        .collect();
+    state.installed_cache.lock().await.insert(key, installed.clone());
+    Ok(installed)
 }
 
-/// Parse a size string like "1.5 GB" or "500 MB" to a numeric value in MB
-fn parse_size_mb(size_str: &str) -> f64 {
-    let size_str = size_str.trim().to_lowercase();
-    
-    // Handle "Unknown" or empty
-    if size_str.is_empty() || size_str == "unknown" {
-        return 0.0;
+/// Forget the cached installed packages after something may have changed them.
+async fn invalidate_installed_packages(state: &AppState) {
+    state.installed_cache.lock().await.clear();
+}
+
+async fn filter_context(state: &AppState, parsed: &CatalogQuery, favorites: &[String]) -> FilterContext {
+    let installed = if parsed.needs_installed() {
+        // Without a device nothing is installed; the other filters still apply
+        Some(installed_packages(state).await.unwrap_or_else(|e| {
+            crate::logger::log(&format!("[CATALOG] installed: filter matches nothing: {}", e));
+            HashSet::new()
+        }))
+    } else {
+        None
+    };
+    FilterContext {
+        favorites: favorites.iter().cloned().collect(),
+        installed,
+        ..Default::default()
     }
-    
-    // Check for GB
-    if let Some(idx) = size_str.find("gb") {
-        let num_part = &size_str[..idx].trim();
-        if let Ok(gb) = num_part.parse::<f64>() {
-            return gb * 1024.0;
-        }
+}
+
+/// Search, filter and sort the catalog the way the library view shows it.
+fn library_games<'a>(
+    catalog: &'a CatalogService,
+    parsed: &CatalogQuery,
+    context: &mut FilterContext,
+    filter: &str,
+    sort_by: &str,
+    sort_ascending: bool,
+) -> Vec<&'a Game> {
+    for term in parsed.note_terms() {
+        context
+            .notes
+            .insert(term.to_string(), catalog.note_matches(term));
     }
-    
-    // Check for MB
-    if let Some(idx) = size_str.find("mb") {
-        let num_part = &size_str[..idx].trim();
-        if let Ok(mb) = num_part.parse::<f64>() {
-            return mb;
+    let mut games = catalog.search_refs(&parsed.text);
+
+    match filter {
+        "favorites" => games.retain(|g| context.favorites.contains(&g.package_name)),
+        "new" => games.retain(|g| g.is_new),
+        "local" => games.retain(|g| g.is_local()),
+        _ => {}
+    }
+    if parsed.has_clauses() {
+        games.retain(|g| parsed.matches(g, context));
+    }
+
+    match sort_by {
+        // Keep the order produced by the search index (best match first)
+        "relevance" => {}
+        "date" => games.sort_by_key(|g| g.last_updated),
+        "size" => games.sort_by_key(|g| g.size_bytes),
+        "popularity" => games.sort_by(|a, b| {
+            // Sort by popularity_rank (lower rank = more popular)
+            // Unranked games (rank 0 or negative) go to the end
+            let rank_a = if a.popularity_rank > 0 { a.popularity_rank } else { i32::MAX };
+            let rank_b = if b.popularity_rank > 0 { b.popularity_rank } else { i32::MAX };
+            rank_a.cmp(&rank_b)
+        }),
+        "trending" => games.sort_by(|a, b| {
+            compare_trending(catalog.trend(&a.package_name), catalog.trend(&b.package_name))
+        }),
+        _ => games.sort_by(|a, b| a.game_name.cmp(&b.game_name)),
+    }
+
+    if !sort_ascending && sort_by != "relevance" {
+        games.reverse();
+    }
+    games
+}
+
+/// Tag, size, favorite and installed counts over the library results.
+/// `downloaded` is left for the caller, which holds the download service.
+fn library_facets(games: &[&Game], context: &FilterContext) -> LibraryFacets {
+    let mut tag_counts: HashMap<&str, u32> = HashMap::new();
+    let mut size_counts: HashMap<SizeBucket, u32> = HashMap::new();
+    for game in games {
+        for tag in &game.tags {
+            *tag_counts.entry(tag).or_insert(0) += 1;
         }
+        *size_counts.entry(SizeBucket::of(game.size_bytes)).or_insert(0) += 1;
     }
-    
-    // Try parsing as raw number (assume MB)
-    if let Ok(mb) = size_str.parse::<f64>() {
-        return mb;
+
+    let mut tags: Vec<TagFacet> = tag_counts
+        .into_iter()
+        .map(|(tag, count)| TagFacet { tag: tag.to_string(), count })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+
+    LibraryFacets {
+        tags,
+        sizes: SizeBucket::ALL
+            .iter()
+            .map(|&bucket| SizeFacet {
+                bucket,
+                label: bucket.label().to_string(),
+                query: bucket.query().to_string(),
+                count: size_counts.get(&bucket).copied().unwrap_or(0),
+            })
+            .collect(),
+        favorites: games
+            .iter()
+            .filter(|game| context.favorites.contains(&game.package_name))
+            .count() as u32,
+        downloaded: 0,
+        installed: context.installed.as_ref().map(|installed| {
+            games
+                .iter()
+                .filter(|game| installed.contains(&game.package_name))
+                .count() as u32
+        }),
     }
-    
-    0.0
+}
+
+fn paginate<T>(items: Vec<T>, limit: u32, offset: u32) -> (Vec<T>, usize) {
+    let total = items.len();
+    let page = items
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect::<Vec<_>>();
+    (page, total)
 }
 
 // --- Readiness & Lifecycle ---
@@ -335,7 +558,7 @@ pub async fn backend_get_settings(state: State<'_, AppState>) -> Result<Settings
         theme: "dark".to_string(),
         language: "en".to_string(),
         enable_notifications: true,
-        concurrent_downloads: settings.keep_awake_interval_seconds as u32,
+        concurrent_downloads: settings.concurrent_downloads.max(1) as u32,
         favorited_games: settings.favorited_games,
         wireless_auto_reconnect: settings.wireless_adb,
     })
@@ -352,6 +575,19 @@ pub async fn backend_update_settings(
         .patch_settings(patch)
         .await
         .map_err(|err| err.to_string())?;
+    let settings = state.settings.get_settings().await;
+    state.rclone.set_catalog_sources(&settings.catalog_sources);
+    state
+        .config
+        .set_configured_source(&settings.config_source)
+        .map_err(|err| err.to_string())?;
+    let download = state.download.lock().await;
+    download.set_max_slots(settings.concurrent_downloads.max(1) as usize);
+    download.set_retry_policy(RetryPolicy {
+        max_retries: settings.download_max_retries.max(0) as u32,
+        ..download.retry_policy()
+    });
+    drop(download);
     backend_get_settings(state).await
 }
 
@@ -360,6 +596,12 @@ pub async fn backend_update_settings(
 #[tauri::command]
 #[specta]
 pub async fn backend_catalog_status(state: State<'_, AppState>) -> Result<CatalogStatus, String> {
+    let sync_operation_id = state
+        .catalog_sync
+        .lock()
+        .await
+        .as_ref()
+        .map(|handle| handle.operation_id.clone());
     let catalog = state.catalog.read().await;
     let game_count = catalog.games().len() as u32;
     let has_games = game_count > 0;
@@ -406,14 +648,17 @@ pub async fn backend_catalog_status(state: State<'_, AppState>) -> Result<Catalo
         cache_age_hours,
         cache_stale,
         sync_in_progress: catalog.is_syncing(),
+        sync_operation_id,
     })
 }
 
 #[tauri::command]
 #[specta]
 pub async fn backend_catalog_load_cache(state: State<'_, AppState>) -> Result<CatalogLoadCacheResult, String> {
+    let window_days = state.settings.get_settings().await.new_app_window_days;
     let loaded = {
         let mut catalog = state.catalog.write().await;
+        catalog.set_new_window_days(window_days.max(0) as u32);
         catalog.load_from_cache()
     };
 
@@ -430,25 +675,72 @@ pub async fn backend_catalog_sync(
     let force = force.unwrap_or(false);
     crate::logger::log(&format!("[CATALOG] Sync triggered (force={})", force));
 
-    // Check cache validity (4 hour rule) unless forced
-    if !force {
-        let cache_age = state.catalog.read().await.get_cache_age();
-        if let Some(age) = cache_age {
-            crate::logger::log(&format!("[CATALOG] Cache age: {:.2} hours", age));
-            if age < 4.0 {
-                crate::logger::log("[CATALOG] Cache is fresh (< 4h). Skipping network sync.");
-                // Cache is fresh enough. Reload it to ensure we have the latest content from disk
-                // (though usually it's already in memory, this handles external updates)
-                let _ = backend_catalog_load_cache(state.clone()).await?;
-                let status = backend_catalog_status(state).await?;
-                return Ok(CatalogSyncResult {
-                    synced: true,
-                    status,
-                });
-            }
-        } else {
-            crate::logger::log("[CATALOG] No cache file found.");
+    // Use the cached list while it is younger than the TTL, or in offline mode
+    // while it is within the max staleness
+    let settings = state.settings.get_settings().await;
+    let cache_age = state.catalog.read().await.get_cache_age();
+    match cache_age {
+        Some(age) => crate::logger::log(&format!("[CATALOG] Cache age: {:.2} hours", age)),
+        None => crate::logger::log("[CATALOG] No cache file found."),
+    }
+    match cache_decision(cache_age, &settings, force) {
+        CacheDecision::UseCache => {
+            crate::logger::log(&format!(
+                "[CATALOG] Using cached catalog (offline={}, ttl={}h). Skipping network sync.",
+                settings.offline_mode, settings.catalog_cache_ttl_hours
+            ));
+            // Cache is fresh enough. Reload it to ensure we have the latest content from disk
+            // (though usually it's already in memory, this handles external updates)
+            let _ = backend_catalog_load_cache(state.clone()).await?;
+            let parse_report = state.catalog.read().await.parse_report().cloned();
+            let status = backend_catalog_status(state).await?;
+            return Ok(CatalogSyncResult {
+                synced: true,
+                status,
+                parse_report,
+                media: None,
+                operation_id: None,
+                summary: None,
+                watch_alerts: Vec::new(),
+            });
+        }
+        CacheDecision::TooStale => {
+            let reason = match cache_age {
+                Some(age) => format!(
+                    "the cached catalog is {:.1} hours old, older than the {} hour limit",
+                    age, settings.catalog_max_staleness_hours
+                ),
+                None => "there is no cached catalog".to_string(),
+            };
+            crate::logger::log(&format!("[CATALOG] Offline mode: {}", reason));
+            return Err(format!("Offline mode is on and {}", reason));
+        }
+        CacheDecision::CheckRemote => {}
+    }
+
+    let operation = SyncOperation::new();
+    let operation_id = operation.id().to_string();
+    {
+        let mut active = state.catalog_sync.lock().await;
+        if let Some(running) = active.as_ref() {
+            crate::logger::log(&format!(
+                "[CATALOG] Sync {} is already running, not starting another",
+                running.operation_id
+            ));
+            let operation_id = Some(running.operation_id.clone());
+            drop(active);
+            let status = backend_catalog_status(state).await?;
+            return Ok(CatalogSyncResult {
+                synced: false,
+                status,
+                parse_report: None,
+                media: None,
+                operation_id,
+                summary: None,
+                watch_alerts: Vec::new(),
+            });
         }
+        *active = Some(operation.handle());
     }
 
     // Set sync_in_progress flag
@@ -456,274 +748,630 @@ pub async fn backend_catalog_sync(
         let mut catalog = state.catalog.write().await;
         catalog.set_syncing(true);
     }
+    state
+        .push_operation_event("catalog_sync.started", &operation_id, "catalog_sync", "running", "Catalog sync started", 0.0)
+        .await;
     
-    crate::logger::log("[CATALOG] Starting background sync process...");
+    crate::logger::log(&format!("[CATALOG] Starting background sync process (operation {})...", operation_id));
     // Perform sync in spawn_blocking to avoid blocking the async runtime
-    let result = tokio::task::spawn_blocking({
+    let joined = tokio::task::spawn_blocking({
         let state = state.inner().clone();
         move || {
+            let mut operation = operation;
             let runtime = tokio::runtime::Handle::current();
-            runtime.block_on(async {
-                // 1. Fetch config
-                crate::logger::log("[CATALOG] Fetching config...");
-                let config = state.config.fetch_config().await.map_err(|e| e.to_string())?;
-                crate::logger::log(&format!("[CATALOG] Config base_uri: {}", config.base_uri));
-                crate::logger::log(&format!("[CATALOG] Config password length: {}", config.password.len()));
-                
-                // 2. Update rclone config
-                state.rclone.set_public_config(&config);
-                
-                // 3. Sync metadata
-                let cache_dir = state.catalog.read().await.cache_dir().to_path_buf();
-                let meta_download_dir = cache_dir.join("meta_download");
-                let _ = tokio::fs::create_dir_all(&meta_download_dir).await;
-                
-                let meta_archive = meta_download_dir.join("meta.7z");
-                let extract_dir = cache_dir.join("meta_extracted");
-                let mut game_list_path = extract_dir.join("VRP-GameList.txt");
-
-                // Check modtime before sync to see if we need to extract
-                let initial_modtime = if meta_archive.exists() {
-                    std::fs::metadata(&meta_archive)
-                        .and_then(|m| m.modified())
-                        .ok()
-                } else {
-                    None
-                };
-
-                crate::logger::log(&format!("[CATALOG] Cache dir: {}", cache_dir.display()));
-                crate::logger::log(&format!("[CATALOG] Meta download dir: {}", meta_download_dir.display()));
-                crate::logger::log(&format!("[CATALOG] Meta archive path: {}", meta_archive.display()));
-                crate::logger::log(&format!("[CATALOG] Meta archive exists before sync: {}", meta_archive.exists()));
-                
-                crate::logger::log("[CATALOG] Running rclone sync for meta.7z...");
-                let result = state.rclone.sync_metadata(&meta_download_dir).await.map_err(|e| e.to_string())?;
-                crate::logger::log(&format!("[CATALOG] Sync result: success={}, stdout={}", result.success(), result.stdout));
-                if !result.success() {
-                    crate::logger::log(&format!("[CATALOG] Metadata sync failed: {}", result.stderr));
-                    return Err(format!("Metadata sync failed: {}", result.stderr));
-                }
-                
-                // Check if file was actually downloaded
-                crate::logger::log(&format!("[CATALOG] Meta archive exists after sync: {}", meta_archive.exists()));
-                
-                // List all files in download dir to debug
-                if let Ok(entries) = std::fs::read_dir(&meta_download_dir) {
-                    let files: Vec<String> = entries
-                        .filter_map(|e| e.ok())
-                        .map(|e| e.file_name().to_string_lossy().to_string())
-                        .collect();
-                    crate::logger::log(&format!("[CATALOG] Files in meta_download: {:?}", files));
-                }
-                
-                if meta_archive.exists() {
-                    if let Ok(metadata) = std::fs::metadata(&meta_archive) {
-                        crate::logger::log(&format!("[CATALOG] Meta archive size: {} bytes", metadata.len()));
-                    }
-                }
-                
-                // Check if we need to extract:
-                // 1. If meta.7z changed (new download)
-                // 2. If VRP-GameList.txt is missing (first run or deleted)
-                let new_modtime = std::fs::metadata(&meta_archive)
-                    .and_then(|m| m.modified())
-                    .ok();
-                
-                let should_extract = initial_modtime != new_modtime || !game_list_path.exists();
-
-                if should_extract {
-                    crate::logger::log("[CATALOG] Extracting meta.7z...");
-                    let _ = tokio::fs::create_dir_all(&extract_dir).await;
-                    
-                    let archive_path = meta_archive.clone();
-                    let extract_path = extract_dir.clone();
-                    let password = config.password.clone();
-                    
-                    tokio::task::spawn_blocking(move || {
-                        ExtractService::extract_7z(&archive_path, &extract_path, Some(&password))
-                            .map_err(|e| e.to_string())
-                    }).await.map_err(|e| e.to_string())??;
-                } else {
-                    crate::logger::log("[CATALOG] meta.7z unchanged. Skipping extraction.");
-                }
-
-                // 4.5 Ensure thumbnails and notes are present in the main cache
-                // This runs even if we skip extraction, ensuring we recover if the cache was partially cleared
-                let extracted_meta = extract_dir.join(".meta");
-                if extracted_meta.exists() {
-                    let extracted_thumbnails = extracted_meta.join("thumbnails");
-                    let extracted_notes = extracted_meta.join("notes");
-                    
-                    let target_thumbnails = cache_dir.join("thumbnails");
-                    let target_notes = cache_dir.join("notes");
-                    
-                    let _ = tokio::fs::create_dir_all(&target_thumbnails).await;
-                    let _ = tokio::fs::create_dir_all(&target_notes).await;
-                    
-                    crate::logger::log("[CATALOG] Syncing thumbnails and notes to cache...");
-                    
-                    // Copy thumbnails
-                    if let Ok(mut entries) = tokio::fs::read_dir(&extracted_thumbnails).await {
-                        let mut count = 0;
-                        while let Ok(Some(entry)) = entries.next_entry().await {
-                            let dest = target_thumbnails.join(entry.file_name());
-                            if !dest.exists() {
-                                let _ = tokio::fs::copy(entry.path(), dest).await;
-                                count += 1;
-                            }
-                        }
-                        if count > 0 {
-                            crate::logger::log(&format!("[CATALOG] Copied {} new thumbnails", count));
-                        }
-                    }
-                    
-                    // Copy notes
-                    if let Ok(mut entries) = tokio::fs::read_dir(&extracted_notes).await {
-                        let mut count = 0;
-                        while let Ok(Some(entry)) = entries.next_entry().await {
-                            let dest = target_notes.join(entry.file_name());
-                            if !dest.exists() {
-                                let _ = tokio::fs::copy(entry.path(), dest).await;
-                                count += 1;
-                            }
-                        }
-                        if count > 0 {
-                            crate::logger::log(&format!("[CATALOG] Copied {} new notes", count));
-                        }
-                    }
-                }
-                
-                // 5. Load into catalog
-                if !game_list_path.exists() {
-                    game_list_path = extract_dir.join(".meta").join("VRP-GameList.txt");
-                }
-                
-                if game_list_path.exists() {
-                    crate::logger::log("[CATALOG] Parsing game list file...");
-                    let mut catalog = state.catalog.write().await;
-                    catalog.parse_game_list_file(&game_list_path).map_err(|e| e.to_string())?;
-                    
-                    // Cache it for offline use and update timestamp
-                    let cached_path = cache_dir.join("VRP-GameList.txt");
-                    let _ = tokio::fs::copy(&game_list_path, &cached_path).await;
-                    
-                    if let Ok(file) = std::fs::OpenOptions::new().write(true).open(&cached_path) {
-                        let _ = file.set_modified(std::time::SystemTime::now());
-                    }
-                }
-                
-                crate::logger::log("[CATALOG] Sync completed successfully.");
-                Ok::<(), String>(())
-            })
+            let result = runtime.block_on(run_catalog_sync(&state, &mut operation));
+            (operation, result)
         }
-    }).await.map_err(|e| e.to_string())?;
+    }).await;
     
     // Clear sync_in_progress flag
     {
         let mut catalog = state.catalog.write().await;
         catalog.set_syncing(false);
     }
+    {
+        let mut active = state.catalog_sync.lock().await;
+        if active.as_ref().is_some_and(|handle| handle.operation_id == operation_id) {
+            *active = None;
+        }
+    }
+    let (operation, result) = joined.map_err(|e| e.to_string())?;
+
+    let summary = operation.finish(result.clone().err());
+    crate::logger::log(&format!("[CATALOG] {}", summary.summary()));
+    let (event_name, event_state, percent) = match summary.outcome {
+        SyncOutcome::Succeeded => ("catalog_sync.completed", "succeeded", 100.0),
+        SyncOutcome::Failed => ("catalog_sync.failed", "failed", 0.0),
+        SyncOutcome::Cancelled => ("catalog_sync.cancelled", "cancelled", 0.0),
+    };
+    state
+        .push_operation_event(event_name, &operation_id, "catalog_sync", event_state, &summary.summary(), percent)
+        .await;
     
     match result {
-        Ok(()) => {
+        Ok(diff) => {
+            let watch_alerts = match diff {
+                Some(diff) => check_watched_packages(&state, &operation_id, &diff).await,
+                None => Vec::new(),
+            };
+            let (parse_report, media) = {
+                let catalog = state.catalog.read().await;
+                (catalog.parse_report().cloned(), catalog.media_sync_report())
+            };
             let status = backend_catalog_status(state).await?;
-            Ok(CatalogSyncResult { synced: true, status })
-        }
-        Err(e) => {
-            let mut catalog = state.catalog.write().await;
-            catalog.set_syncing(false);
-            Err(e)
+            Ok(CatalogSyncResult {
+                synced: true,
+                status,
+                parse_report,
+                media,
+                operation_id: Some(operation_id),
+                summary: Some(summary),
+                watch_alerts,
+            })
         }
+        Err(e) => Err(e),
     }
 }
 
-#[tauri::command]
-#[specta]
-pub async fn backend_catalog_search(
-    state: State<'_, AppState>,
-    query: Option<String>,
-    limit: Option<u32>,
-    offset: Option<u32>,
-) -> Result<CatalogSearchResult, String> {
-    let query = query.unwrap_or_default();
-    let limit = limit.unwrap_or(100);
-    let offset = offset.unwrap_or(0);
+/// Compare watched packages with what the sync changed, pushing a
+/// `watchlist.release` event for each new release and queueing it when the
+/// watch asks for that.
+async fn check_watched_packages(state: &AppState, operation_id: &str, diff: &CatalogDiff) -> Vec<WatchAlert> {
+    let (mut alerts, releases, auto_queued) = {
+        let catalog = state.catalog.read().await;
+        let checked = state
+            .settings
+            .update_with(|settings| {
+                let alerts = check_watchlist(&mut settings.watched_packages, diff, |package| {
+                    catalog.get_game_by_package(package)
+                });
+                let auto_queued: HashSet<String> = settings
+                    .watched_packages
+                    .iter()
+                    .filter(|watch| watch.auto_queue)
+                    .map(|watch| watch.package_name.clone())
+                    .collect();
+                Ok((alerts, auto_queued))
+            })
+            .await;
+        let (alerts, auto_queued) = match checked {
+            Ok(checked) => checked,
+            Err(e) => {
+                // Unsaved releases are reported again by the next sync
+                crate::logger::log(&format!("[WATCHLIST] Failed to save watched releases: {}", e));
+                return Vec::new();
+            }
+        };
+        let releases: Vec<Option<Game>> = alerts
+            .iter()
+            .map(|alert| {
+                catalog
+                    .get_game_by_package_and_release(&alert.package_name, &alert.to_release)
+                    .cloned()
+            })
+            .collect();
+        (alerts, releases, auto_queued)
+    };
 
-    let games = state.catalog.read().await.search(&query);
-    let (games, total) = paginate(games, limit, offset);
+    let download = state.download.lock().await;
+    for (alert, release) in alerts.iter_mut().zip(releases) {
+        let auto_queue = auto_queued.contains(&alert.package_name);
+        if let Some(game) = release.filter(|game| auto_queue && !game.is_local()) {
+            if check_queue_space(&download, &game).await.is_ok() {
+                alert.queued = download.add_to_queue(game).await;
+            }
+        }
+    }
+    drop(download);
+    if alerts.iter().any(|alert| alert.queued) {
+        save_download_queue(state).await;
+    }
 
-    let games: Vec<CatalogSearchGame> = games
-        .into_iter()
-        .map(|game| CatalogSearchGame {
-            package_name: game.package_name,
-            release_name: game.release_name,
-            game_name: game.game_name,
-            version_code: game.version_code,
-            version_name: game.version_name,
-            size: game.size,
-            last_updated: game.last_updated,
-            downloads: game.downloads,
-        })
-        .collect();
+    for alert in &alerts {
+        let message = alert.message();
+        crate::logger::log(&format!("[WATCHLIST] {} (queued={})", message, alert.queued));
+        state
+            .push_notice_event(
+                "watchlist.release",
+                operation_id,
+                "watchlist",
+                &message,
+                serde_json::to_value(alert).unwrap_or_default(),
+            )
+            .await;
+    }
+    alerts
+}
 
-    Ok(CatalogSearchResult {
-        games,
-        total: total as u32,
-        offset,
-        limit,
-        query,
-    })
+/// Start `phase` of the sync and tell the frontend about it.
+async fn enter_sync_phase(state: &AppState, operation: &mut SyncOperation, phase: SyncPhase) -> Result<(), String> {
+    operation.enter(phase)?;
+    crate::logger::log(&format!("[CATALOG] {}...", phase.label()));
+    state
+        .push_operation_event(
+            "catalog_sync.phase",
+            operation.id(),
+            "catalog_sync",
+            "running",
+            phase.label(),
+            phase.progress_span().0,
+        )
+        .await;
+    Ok(())
 }
 
-#[tauri::command]
-#[specta]
-pub async fn search_youtube_trailer(
-    state: State<'_, AppState>,
-    game_name: String,
-) -> Result<Option<String>, String> {
-    // Check cache
-    {
-        let cache = state.youtube_cache.lock().await;
-        if let Some(video_id) = cache.get(&game_name) {
-            return Ok(video_id.clone());
+/// Forward rclone transfer stats for `meta.7z` as progress events within the
+/// metadata download phase. Resolves to the bytes transferred once the sender
+/// is dropped.
+fn forward_metadata_progress(
+    state: &AppState,
+    operation_id: &str,
+) -> (
+    tokio::sync::mpsc::UnboundedSender<crate::services::rclone::DownloadProgress>,
+    tokio::task::JoinHandle<u64>,
+) {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<crate::services::rclone::DownloadProgress>();
+    let state = state.clone();
+    let operation_id = operation_id.to_string();
+    let task = tokio::spawn(async move {
+        let (start, end) = SyncPhase::MetadataDownload.progress_span();
+        let mut bytes = 0;
+        while let Some(progress) = receiver.recv().await {
+            bytes = progress.bytes_transferred.max(0) as u64;
+            let message = format!(
+                "{}: {:.0}% ({}, ETA {})",
+                SyncPhase::MetadataDownload.label(),
+                progress.percent,
+                progress.speed_display(),
+                progress.eta
+            );
+            let percent = start + (end - start) * progress.percent.clamp(0.0, 100.0) / 100.0;
+            state
+                .push_operation_event("catalog_sync.progress", &operation_id, "catalog_sync", "running", &message, percent)
+                .await;
         }
-    }
+        bytes
+    });
+    (sender, task)
+}
+
+/// Copy the public `meta.7z` into `meta_download_dir`, forwarding transfer
+/// progress as operation events. Returns whether the archive changed on disk.
+async fn download_primary_metadata(
+    state: &AppState,
+    operation: &mut SyncOperation,
+    meta_download_dir: &std::path::Path,
+) -> Result<bool, String> {
+    let _ = tokio::fs::create_dir_all(meta_download_dir).await;
+    let meta_archive = meta_download_dir.join("meta.7z");
+
+    // Check modtime before sync to see if we need to extract
+    let initial_modtime = if meta_archive.exists() {
+        std::fs::metadata(&meta_archive)
+            .and_then(|m| m.modified())
+            .ok()
+    } else {
+        None
+    };
 
-    // Search YouTube
-    let query = format!("{} VR trailer", game_name);
-    let encoded_query = url::form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>();
-    let url = format!("https://www.youtube.com/results?search_query={}", encoded_query);
+    crate::logger::log(&format!("[CATALOG] Meta download dir: {}", meta_download_dir.display()));
+    crate::logger::log(&format!("[CATALOG] Meta archive path: {}", meta_archive.display()));
+    crate::logger::log(&format!("[CATALOG] Meta archive exists before sync: {}", meta_archive.exists()));
     
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| e.to_string())?;
+    crate::logger::log("[CATALOG] Running rclone sync for meta.7z...");
+    let (progress_sender, progress_task) = forward_metadata_progress(state, operation.id());
+    let result = state.rclone.sync_metadata(meta_download_dir, Some(progress_sender)).await;
+    if let Ok(bytes) = progress_task.await {
+        operation.record_bytes(bytes);
+    }
+    let result = result.map_err(|e| e.to_string())?;
+    crate::logger::log(&format!("[CATALOG] Sync result: success={}, stdout={}", result.success(), result.stdout));
+    if operation.is_cancelled() {
+        return Err(CANCELLED_MESSAGE.to_string());
+    }
+    if !result.success() {
+        crate::logger::log(&format!("[CATALOG] Metadata sync failed: {}", result.stderr));
+        return Err(format!("Metadata sync failed: {}", result.stderr));
+    }
+    
+    // Check if file was actually downloaded
+    crate::logger::log(&format!("[CATALOG] Meta archive exists after sync: {}", meta_archive.exists()));
+    
+    // List all files in download dir to debug
+    if let Ok(entries) = std::fs::read_dir(meta_download_dir) {
+        let files: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        crate::logger::log(&format!("[CATALOG] Files in meta_download: {:?}", files));
+    }
+    
+    if meta_archive.exists() {
+        if let Ok(metadata) = std::fs::metadata(&meta_archive) {
+            crate::logger::log(&format!("[CATALOG] Meta archive size: {} bytes", metadata.len()));
+        }
+    }
 
-    let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
-    let text = response.text().await.map_err(|e| e.to_string())?;
+    let new_modtime = std::fs::metadata(&meta_archive)
+        .and_then(|m| m.modified())
+        .ok();
+    Ok(initial_modtime != new_modtime)
+}
 
-    // Parse video ID
-    let re = Regex::new(r"watch\?v=([a-zA-Z0-9_-]{11})").map_err(|e| e.to_string())?;
-    let video_id = re.captures(&text)
-        .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str().to_string());
+/// Returns what changed in the catalog, `None` when no game list was parsed.
+async fn run_catalog_sync(state: &AppState, operation: &mut SyncOperation) -> Result<Option<CatalogDiff>, String> {
+    // 1. Fetch config
+    enter_sync_phase(state, operation, SyncPhase::ConfigFetch).await?;
+    let config = state.config.fetch_config().await.map_err(|e| e.to_string())?;
+    crate::logger::log(&format!("[CATALOG] Config base_uri: {}", config.base_uri));
+    crate::logger::log(&format!("[CATALOG] Config password length: {}", config.password.len()));
+    
+    // 2. Update rclone config
+    state.rclone.set_public_config(&config);
+
+    // 2.5 Compare the remote meta.7z with the local copy. A failed stat is only a
+    // warning, as the file might still be accessible
+    enter_sync_phase(state, operation, SyncPhase::RemoteCheck).await?;
+    let cache_dir = state.catalog.read().await.cache_dir().to_path_buf();
+    let meta_download_dir = cache_dir.join("meta_download");
+    let meta_archive = meta_download_dir.join("meta.7z");
+    let extract_dir = cache_dir.join("meta_extracted");
+    let extracted_meta = extract_dir.join(".meta");
+    let mut game_list_path = extract_dir.join("VRP-GameList.txt");
+    let game_list_present = game_list_path.exists() || extracted_meta.join("VRP-GameList.txt").exists();
+    crate::logger::log(&format!("[CATALOG] Cache dir: {}", cache_dir.display()));
+    let metadata_changed = match state.rclone.stat_source_metadata(PRIMARY_SOURCE).await {
+        Ok(Some(stat)) => !(stat.matches_file(&meta_archive) && game_list_present),
+        Ok(None) => {
+            crate::logger::log("[CATALOG] WARNING: meta.7z not found on the remote");
+            true
+        }
+        Err(e) => {
+            crate::logger::log(&format!("[CATALOG] WARNING: Could not stat remote meta.7z: {}", e));
+            true
+        }
+    };
+    
+    // 3. Sync metadata, or report that it's up to date without entering the
+    // download phase
+    let archive_changed = if metadata_changed {
+        enter_sync_phase(state, operation, SyncPhase::MetadataDownload).await?;
+        download_primary_metadata(state, operation, &meta_download_dir).await?
+    } else {
+        crate::logger::log("[CATALOG] Remote meta.7z unchanged. Skipping download and extraction.");
+        operation.mark_metadata_unchanged();
+        state
+            .push_operation_event(
+                "catalog_sync.progress",
+                operation.id(),
+                "catalog_sync",
+                "running",
+                "Metadata up to date, skipping download",
+                SyncPhase::Extraction.progress_span().1,
+            )
+            .await;
+        false
+    };
 
-    // Update cache
-    {
-        let mut cache = state.youtube_cache.lock().await;
-        cache.insert(game_name, video_id.clone());
+    // 3.5 Fetch the lists of any additional sources
+    let source_lists = fetch_source_lists(state, &cache_dir).await;
+    
+    // 4. Check if we need to extract:
+    // 1. If meta.7z changed (new download)
+    // 2. If VRP-GameList.txt is missing (first run or deleted)
+    if metadata_changed {
+        enter_sync_phase(state, operation, SyncPhase::Extraction).await?;
+        if archive_changed || !game_list_present {
+            crate::logger::log("[CATALOG] Extracting meta.7z...");
+            let _ = tokio::fs::create_dir_all(&extract_dir).await;
+            
+            let archive_path = meta_archive.clone();
+            let extract_path = extract_dir.clone();
+            let password = config.password.clone();
+            
+            tokio::task::spawn_blocking(move || {
+                ExtractService::extract_7z(&archive_path, &extract_path, Some(&password))
+                    .map_err(|e| e.to_string())
+            }).await.map_err(|e| e.to_string())??;
+        } else {
+            crate::logger::log("[CATALOG] meta.7z unchanged. Skipping extraction.");
+        }
     }
 
-    Ok(video_id)
-}
+    // 5. Load into catalog
+    enter_sync_phase(state, operation, SyncPhase::Parse).await?;
+    // Parsing replaces the stored catalog, so the remaining phases run to the end
+    operation.commit()?;
+    if !game_list_path.exists() {
+        game_list_path = extracted_meta.join("VRP-GameList.txt");
+    }
+    
+    let mut sync_diff = None;
+    if game_list_path.exists() {
+        crate::logger::log("[CATALOG] Parsing game list file...");
+        let settings = state.settings.get_settings().await;
+        let (known_packages, diff) = {
+            let mut catalog = state.catalog.write().await;
+            catalog.set_new_window_days(settings.new_app_window_days.max(0) as u32);
+            catalog
+                .parse_game_list_files(&game_list_path, &source_lists)
+                .map_err(|e| e.to_string())?;
+            if let Some(report) = catalog.parse_report() {
+                crate::logger::log(&format!("[CATALOG] Parse report: {}", report.summary()));
+            }
 
-#[tauri::command]
-#[specta]
-pub async fn backend_catalog_game_detail(
-    state: State<'_, AppState>,
-    package_name: String,
-) -> Result<CatalogGameDetail, String> {
+            // 6. Diff against the previous sync
+            let diff = catalog
+                .record_snapshot(&settings.known_catalog_packages)
+                .map_err(|e| e.to_string())?;
+            crate::logger::log(&format!(
+                "[CATALOG] Changes since last sync: {} added, {} removed, {} updated",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.updated.len()
+            ));
+            let known_packages: Vec<String> = catalog.snapshot().packages.keys().cloned().collect();
+            (known_packages, diff)
+        };
+        sync_diff = Some(diff);
+
+        if settings.known_catalog_packages != known_packages {
+            let saved = state
+                .settings
+                .update_with(|settings| {
+                    settings.known_catalog_packages = known_packages;
+                    Ok(())
+                })
+                .await;
+            if let Err(e) = saved {
+                crate::logger::log(&format!("[CATALOG] Failed to save known packages: {}", e));
+            }
+        }
+        
+        // Cache it for offline use and update timestamp
+        let cached_path = cache_dir.join("VRP-GameList.txt");
+        let _ = tokio::fs::copy(&game_list_path, &cached_path).await;
+        
+        if let Ok(file) = std::fs::OpenOptions::new().write(true).open(&cached_path) {
+            let _ = file.set_modified(std::time::SystemTime::now());
+        }
+
+        // 7. Sync thumbnails and notes into the main cache. This runs even if
+        // extraction was skipped, so a partially cleared cache is recovered
+        enter_sync_phase(state, operation, SyncPhase::MediaCopy).await?;
+        let mut catalog = state.catalog.write().await;
+        let media = if extracted_meta.exists() {
+            catalog.sync_media(&extracted_meta).map(|report| {
+                crate::logger::log(&format!("[CATALOG] Media sync: {}", report.summary()));
+            })
+        } else {
+            catalog.refresh_media()
+        };
+        if let Err(e) = media {
+            crate::logger::log(&format!("[CATALOG] Failed to sync media: {}", e));
+        }
+    }
+    
+    crate::logger::log("[CATALOG] Sync completed successfully.");
+    Ok(sync_diff)
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_sync_cancel(
+    state: State<'_, AppState>,
+    operation_id: Option<String>,
+) -> Result<CatalogSyncCancelResult, String> {
+    let handle = state.catalog_sync.lock().await.clone();
+    let handle = match handle {
+        Some(handle) if operation_id.as_ref().is_none_or(|id| *id == handle.operation_id) => handle,
+        _ => {
+            return Ok(CatalogSyncCancelResult {
+                cancelled: false,
+                operation_id,
+            })
+        }
+    };
+
+    if !handle.cancel() {
+        crate::logger::log(&format!(
+            "[CATALOG] Sync {} already committed the new catalog, letting it finish",
+            handle.operation_id
+        ));
+        return Ok(CatalogSyncCancelResult {
+            cancelled: false,
+            operation_id: Some(handle.operation_id),
+        });
+    }
+    crate::logger::log(&format!("[CATALOG] Cancelling sync {}", handle.operation_id));
+    state.rclone.stop_metadata_sync().await.map_err(|e| e.to_string())?;
+    Ok(CatalogSyncCancelResult {
+        cancelled: true,
+        operation_id: Some(handle.operation_id),
+    })
+}
+
+/// Fetch the game lists of the additional catalog sources. A source that can't be
+/// reached falls back to the list cached by its last successful sync.
+async fn fetch_source_lists(state: &AppState, cache_dir: &std::path::Path) -> Vec<SourceList> {
+    let mut lists = Vec::new();
+    for source in state.rclone.catalog_sources() {
+        let source_dir = cache_dir.join("sources").join(&source.name);
+        let cached_list = source_dir.join(source.list_file());
+        if let Err(e) = fetch_source_list(state, &source, &source_dir, &cached_list).await {
+            crate::logger::log(&format!("[CATALOG] Source '{}' sync failed: {}", source.name, e));
+        }
+
+        match tokio::fs::read_to_string(&cached_list).await {
+            Ok(content) => lists.push(SourceList {
+                source: source.name.clone(),
+                priority: source.priority,
+                content,
+            }),
+            Err(_) => crate::logger::log(&format!(
+                "[CATALOG] Source '{}' has no game list yet, skipping",
+                source.name
+            )),
+        }
+    }
+    lists
+}
+
+async fn fetch_source_list(
+    state: &AppState,
+    source: &CatalogSource,
+    source_dir: &std::path::Path,
+    cached_list: &std::path::Path,
+) -> Result<(), String> {
+    tokio::fs::create_dir_all(source_dir).await.map_err(|e| e.to_string())?;
+    let list_path = match source.kind {
+        CatalogSourceKind::Local => PathBuf::from(&source.base_uri).join(source.list_file()),
+        CatalogSourceKind::Remote => {
+            let result = state
+                .rclone
+                .sync_source_metadata(&source.name, source_dir, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !result.success() {
+                return Err(format!("Metadata sync failed: {}", result.stderr));
+            }
+
+            let archive_path = source_dir.join("meta.7z");
+            let extract_dir = source_dir.join("meta_extracted");
+            let extract_path = extract_dir.clone();
+            let password = state
+                .settings
+                .source_password(source)
+                .map_err(|e| e.to_string())?;
+            tokio::task::spawn_blocking(move || {
+                ExtractService::extract_7z(&archive_path, &extract_path, password.as_deref().map(String::as_str))
+                    .map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+
+            let extracted = extract_dir.join(source.list_file());
+            if extracted.exists() {
+                extracted
+            } else {
+                extract_dir.join(".meta").join(source.list_file())
+            }
+        }
+    };
+
+    tokio::fs::copy(&list_path, cached_list)
+        .await
+        .map_err(|e| format!("failed to copy {}: {}", list_path.display(), e))?;
+    Ok(())
+}
+
+/// The configured catalog sources, the public one first, with how many releases
+/// each currently contributes.
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_sources(
+    state: State<'_, AppState>,
+) -> Result<Vec<CatalogSourceInfo>, String> {
+    let counts = state.catalog.read().await.release_counts_by_source();
+    let count = |name: &str| counts.get(name).copied().unwrap_or(0) as u32;
+
+    let mut sources = vec![CatalogSourceInfo {
+        name: PRIMARY_SOURCE.to_string(),
+        kind: CatalogSourceKind::Remote,
+        remote_name: PRIMARY_SOURCE.to_string(),
+        list_file: DEFAULT_LIST_FILE.to_string(),
+        priority: 0,
+        release_count: count(PRIMARY_SOURCE),
+    }];
+    sources.extend(
+        state
+            .rclone
+            .catalog_sources()
+            .into_iter()
+            .map(|source| CatalogSourceInfo {
+                release_count: count(&source.name),
+                remote_name: source.remote_name().to_string(),
+                list_file: source.list_file().to_string(),
+                name: source.name,
+                kind: source.kind,
+                priority: source.priority,
+            }),
+    );
+    Ok(sources)
+}
+
+/// Where the public config is fetched from and how each source has done this
+/// session.
+#[tauri::command]
+#[specta]
+pub async fn backend_config_sources(
+    state: State<'_, AppState>,
+) -> Result<Vec<ConfigSourceHealth>, String> {
+    state.config.source_health().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_search(
+    state: State<'_, AppState>,
+    query: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<CatalogSearchResult, String> {
+    let query = query.unwrap_or_default();
+    let limit = limit.unwrap_or(100);
+    let offset = offset.unwrap_or(0);
+
+    let (games, total) = {
+        let catalog = state.catalog.read().await;
+        let (page, total) = paginate(catalog.search_refs(&query), limit, offset);
+        let games: Vec<CatalogSearchGame> = page
+            .into_iter()
+            .map(|game| CatalogSearchGame {
+                package_name: game.package_name.clone(),
+                release_name: game.release_name.clone(),
+                game_name: game.game_name.clone(),
+                version_code: game.version_code.to_string(),
+                version_name: game.version_name.clone(),
+                size: game.size_label(),
+                last_updated: game.last_updated_label(),
+                downloads: game.downloads_label(),
+            })
+            .collect();
+        (games, total)
+    };
+
+    Ok(CatalogSearchResult {
+        games,
+        total: total as u32,
+        offset,
+        limit,
+        query,
+    })
+}
+
+/// Video id of a game's trailer. With `package_name`, the user's
+/// `trailers.json` override for the package wins over the search.
+#[tauri::command]
+#[specta]
+pub async fn search_youtube_trailer(
+    state: State<'_, AppState>,
+    game_name: String,
+    package_name: Option<String>,
+) -> Result<Option<String>, String> {
+    state
+        .trailers
+        .lookup(&game_name, package_name.as_deref())
+        .await
+        .map_err(|e| format!("{e:#}"))
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_game_detail(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<CatalogGameDetail, String> {
     let game = state
         .catalog
         .read()
@@ -736,27 +1384,25 @@ pub async fn backend_catalog_game_detail(
             let catalog = state.catalog.read().await;
             let thumbnail_path = catalog.thumbnails_dir().join(format!("{}.jpg", game.package_name));
             let note_path = catalog.notes_dir().join(format!("{}.txt", game.package_name));
-            let note = if note_path.exists() {
-                std::fs::read_to_string(&note_path).unwrap_or_default()
-            } else {
-                String::new()
-            };
+            let notes = catalog.release_notes(&game.package_name).unwrap_or_default();
             
             Ok(CatalogGameDetail {
+                version_code: game.version_code.to_string(),
+                size: game.size_label(),
+                last_updated: game.last_updated_label(),
+                downloads: game.downloads_label(),
                 game_name: game.game_name,
                 release_name: game.release_name,
                 package_name: game.package_name,
-                version_code: game.version_code,
                 version_name: game.version_name,
-                size: game.size,
-                last_updated: game.last_updated,
-                downloads: game.downloads,
                 release_apk_path: game.release_apk_path,
                 thumbnail_path: thumbnail_path.display().to_string(),
                 thumbnail_exists: thumbnail_path.exists(),
                 note_path: note_path.display().to_string(),
-                note_excerpt: note.chars().take(200).collect(),
+                note_excerpt: notes.excerpt(200),
                 note_exists: note_path.exists(),
+                note_warnings: notes.warnings,
+                source: game.source,
             })
         }
         None => Err(format!("Game not found for package: {package_name}")),
@@ -765,167 +1411,828 @@ pub async fn backend_catalog_game_detail(
 
 #[tauri::command]
 #[specta]
-pub async fn backend_catalog_game_versions(
+pub async fn backend_catalog_game_versions(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<CatalogGameVersions, String> {
+    let games = state.catalog.read().await.get_games_by_package(&package_name);
+    let games: Vec<CatalogSearchGame> = games
+        .into_iter()
+        .map(|game| CatalogSearchGame {
+            version_code: game.version_code.to_string(),
+            size: game.size_label(),
+            last_updated: game.last_updated_label(),
+            downloads: game.downloads_label(),
+            package_name: game.package_name,
+            release_name: game.release_name,
+            game_name: game.game_name,
+            version_name: game.version_name,
+        })
+        .collect();
+    Ok(CatalogGameVersions { games })
+}
+
+/// Compare two releases of a package; `to_release` defaults to the newest one.
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_version_diff(
+    state: State<'_, AppState>,
+    package_name: String,
+    from_release: String,
+    to_release: Option<String>,
+) -> Result<ReleaseDiff, String> {
+    let catalog = state.catalog.read().await;
+    let find = |release_name: &str| {
+        catalog
+            .get_game_by_package_and_release(&package_name, release_name)
+            .ok_or_else(|| format!("Release not found: {} ({})", release_name, package_name))
+    };
+    let from = find(&from_release)?;
+    let newest;
+    let to = match to_release.as_deref() {
+        Some(release_name) => find(release_name)?,
+        None => {
+            newest = catalog
+                .get_games_by_package(&package_name)
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("Package not found: {}", package_name))?;
+            &newest
+        }
+    };
+    Ok(ReleaseDiff::between(from, to))
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_thumbnail_path(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<CatalogThumbnailPath, String> {
+    let catalog = state.catalog.read().await;
+    let thumbnail_path = catalog.thumbnails_dir().join(format!("{package_name}.jpg"));
+    Ok(CatalogThumbnailPath {
+        thumbnail_path: thumbnail_path.display().to_string(),
+        thumbnail_exists: thumbnail_path.exists(),
+    })
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_note(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<CatalogNote, String> {
+    let note = state
+        .catalog
+        .read()
+        .await
+        .note_text(&package_name)
+        .unwrap_or_default();
+    let parsed = ReleaseNotes::parse(&note);
+    Ok(CatalogNote { note, parsed })
+}
+
+/// Diagnostics for the last parsed game list: schema, rejected rows,
+/// duplicates and unparsable version codes. `None` until a list has been parsed.
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_parse_report(
+    state: State<'_, AppState>,
+) -> Result<Option<CatalogParseReport>, String> {
+    Ok(state.catalog.read().await.parse_report().cloned())
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_annotations(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<CatalogAnnotations, String> {
+    let annotations = state
+        .catalog
+        .read()
+        .await
+        .annotations(&package_name)
+        .map_err(|e| e.to_string())?;
+    Ok(CatalogAnnotations {
+        package_name,
+        annotations,
+    })
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_annotation_set(
+    state: State<'_, AppState>,
+    package_name: String,
+    key: String,
+    value: Option<String>,
+) -> Result<CatalogAnnotations, String> {
+    state
+        .catalog
+        .read()
+        .await
+        .set_annotation(&package_name, &key, value.as_deref())
+        .map_err(|e| e.to_string())?;
+    backend_catalog_annotations(state, package_name).await
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_library(
+    state: State<'_, AppState>,
+    query: Option<String>,
+    sort_by: Option<String>,
+    sort_ascending: Option<bool>,
+    filter: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<LibraryResult, String> {
+    let query = query.unwrap_or_default();
+    let limit = limit.unwrap_or(100);
+    let offset = offset.unwrap_or(0);
+    let sort_by = sort_by.unwrap_or_else(|| "name".to_string());
+    let sort_ascending = sort_ascending.unwrap_or(true);
+    let filter = filter.unwrap_or_else(|| "all".to_string());
+
+    let parsed = CatalogQuery::parse(&query).map_err(|e| format!("Invalid query: {e}"))?;
+    let favorites = state.settings.get_settings().await.favorited_games;
+    let mut context = filter_context(&state, &parsed, &favorites).await;
+    // The installed facet is best effort when the query doesn't need the device
+    if context.installed.is_none() && selected_serial(&state).await.is_some() {
+        context.installed = installed_packages(&state).await.ok();
+    }
+
+    let (page, total, mut facets, listed_releases) = {
+        let catalog = state.catalog.read().await;
+        let games = library_games(&catalog, &parsed, &mut context, &filter, &sort_by, sort_ascending);
+        let mut facets = library_facets(&games, &context);
+        // Imported entries are already on disk
+        let listed_releases: Vec<String> = games
+            .iter()
+            .filter(|game| !game.is_local())
+            .map(|game| game.release_name.clone())
+            .collect();
+        facets.downloaded = (games.len() - listed_releases.len()) as u32;
+
+        let (page, total) = paginate(games, limit, offset);
+        let page = page
+            .into_iter()
+            .map(|game| (game.clone(), catalog.trend(&game.package_name).copied()))
+            .collect::<Vec<(Game, Option<PopularityTrend>)>>();
+        (page, total, facets, listed_releases)
+    };
+
+    // One listing of the download folder rather than a lookup per result
+    let download_dir = state.download.lock().await.download_dir().to_path_buf();
+    let downloaded = downloaded_hashes(&download_dir);
+    facets.downloaded += listed_releases
+        .iter()
+        .filter(|release_name| downloaded.contains(&CatalogService::game_name_to_hash(release_name)))
+        .count() as u32;
+
+    let mut paged = Vec::new();
+    for (game, trend) in &page {
+        // Imported entries are already on disk
+        let is_downloaded = game.is_local()
+            || downloaded.contains(&CatalogService::game_name_to_hash(&game.release_name));
+        paged.push(LibraryGame {
+            package_name: game.package_name.clone(),
+            release_name: game.release_name.clone(),
+            game_name: game.game_name.clone(),
+            size: game.size_label(),
+            last_updated: game.last_updated_label(),
+            version_code: game.version_code.to_string(),
+            downloads: game.downloads_label(),
+            is_favorite: favorites.iter().any(|pkg| pkg == &game.package_name),
+            is_new: game.is_new,
+            popularity_rank: game.popularity_rank,
+            trend: *trend,
+            is_downloaded,
+            source: game.source.clone(),
+            tags: game.tags.clone(),
+        });
+    }
+
+    Ok(LibraryResult {
+        games: paged,
+        total: total as u32,
+        offset,
+        limit,
+        query,
+        sort_by,
+        sort_ascending,
+        filter,
+        favorites_count: favorites.len() as u32,
+        tags: parsed.required_tags().into_iter().map(str::to_string).collect(),
+        facets,
+    })
+}
+
+/// Write the library view for `query`/`filter` to a JSON or CSV file, chosen
+/// by the extension of `path`. With `all_versions` and no query, every release
+/// is exported instead of the newest release per game.
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_export(
+    state: State<'_, AppState>,
+    path: String,
+    query: Option<String>,
+    sort_by: Option<String>,
+    sort_ascending: Option<bool>,
+    filter: Option<String>,
+    all_versions: Option<bool>,
+) -> Result<CatalogExportResult, String> {
+    let path = PathBuf::from(path);
+    let format = ExportFormat::from_path(&path).map_err(|e| e.to_string())?;
+    let query = query.unwrap_or_default();
+    let sort_by = sort_by.unwrap_or_else(|| "name".to_string());
+    let filter = filter.unwrap_or_else(|| "all".to_string());
+
+    let parsed = CatalogQuery::parse(&query).map_err(|e| format!("Invalid query: {e}"))?;
+    let favorites = state.settings.get_settings().await.favorited_games;
+    let mut context = filter_context(&state, &parsed, &favorites).await;
+
+    let records: Vec<CatalogRecord> = {
+        let catalog = state.catalog.read().await;
+        let games = if all_versions.unwrap_or(false) && query.trim().is_empty() {
+            catalog.releases().iter().collect()
+        } else {
+            library_games(
+                &catalog,
+                &parsed,
+                &mut context,
+                &filter,
+                &sort_by,
+                sort_ascending.unwrap_or(true),
+            )
+        };
+        games
+            .into_iter()
+            .map(|game| CatalogRecord::from_game(game, catalog.local_game_dir(&game.release_name)))
+            .collect()
+    };
+
+    write_records(&path, format, &records).map_err(|e| format!("{e:#}"))?;
+    crate::logger::log(&format!(
+        "[CATALOG] Exported {} releases to {}",
+        records.len(),
+        path.display()
+    ));
+    Ok(CatalogExportResult {
+        path: path.display().to_string(),
+        format: format.as_str().to_string(),
+        count: records.len() as u32,
+    })
+}
+
+/// Import in-house builds from a JSON or CSV list in the export format. Each
+/// entry needs a `package_name` and a `path` to a folder with an APK or
+/// install.txt; relative paths are resolved against the list's folder.
+/// Imported entries are listed under the `local` source and installed straight
+/// from their folder.
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_import_local(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<LocalImportResult, String> {
+    let path = PathBuf::from(path);
+    let records = read_records(&path).map_err(|e| format!("{e:#}"))?;
+    let base_dir = path.parent().map(PathBuf::from).unwrap_or_default();
+
+    let mut catalog = state.catalog.write().await;
+    let mut entries = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, record) in records.into_iter().enumerate() {
+        let release_name = record.release_name.clone();
+        let entry = record.into_local_entry(&base_dir).and_then(|entry| {
+            if catalog.lists_release(&entry.game.release_name) {
+                Err(format!(
+                    "release name '{}' is already listed by a catalog source",
+                    entry.game.release_name
+                ))
+            } else {
+                Ok(entry)
+            }
+        });
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(reason) => rejected.push(LocalImportRejection {
+                index: index as u32,
+                release_name,
+                reason,
+            }),
+        }
+    }
+
+    let imported = catalog
+        .add_local_entries(entries)
+        .map_err(|e| e.to_string())?;
+    crate::logger::log(&format!(
+        "[CATALOG] Imported {} local entries from {} ({} rejected)",
+        imported,
+        path.display(),
+        rejected.len()
+    ));
+    Ok(LocalImportResult {
+        imported: imported as u32,
+        rejected,
+    })
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_catalog_local_remove(
+    state: State<'_, AppState>,
+    release_name: String,
+) -> Result<LocalRemoveResult, String> {
+    let removed = state
+        .catalog
+        .write()
+        .await
+        .remove_local_entry(&release_name)
+        .map_err(|e| e.to_string())?;
+    Ok(LocalRemoveResult {
+        removed,
+        release_name,
+    })
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_favorites_toggle(
+    state: State<'_, AppState>,
+    package_name: String,
+) -> Result<FavoritesToggleResult, String> {
+    let (exists, favorites) = state
+        .settings
+        .update_with(|settings| {
+            let exists = settings.favorited_games.iter().any(|pkg| pkg == &package_name);
+            if exists {
+                settings.favorited_games.retain(|pkg| pkg != &package_name);
+            } else {
+                settings.favorited_games.push(package_name.clone());
+            }
+            Ok((exists, settings.favorited_games.clone()))
+        })
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(FavoritesToggleResult {
+        package_name,
+        is_favorite: !exists,
+        favorites,
+    })
+}
+
+// --- Watchlist ---
+
+#[tauri::command]
+#[specta]
+pub async fn backend_watchlist_get(state: State<'_, AppState>) -> Result<WatchlistResult, String> {
+    Ok(WatchlistResult {
+        watched: state.settings.get_settings().await.watched_packages,
+    })
+}
+
+/// Watch or unwatch a package. A new watch starts from the package's current
+/// newest release, so only later releases are reported; `auto_queue` also
+/// updates an existing watch.
+#[tauri::command]
+#[specta]
+pub async fn backend_watchlist_set(
+    state: State<'_, AppState>,
+    package_name: String,
+    watched: bool,
+    auto_queue: Option<bool>,
+) -> Result<WatchlistResult, String> {
+    let catalog = state.catalog.read().await;
+    let watched = state
+        .settings
+        .update_with(|settings| {
+            let existing = settings
+                .watched_packages
+                .iter_mut()
+                .find(|watch| watch.package_name == package_name);
+
+            match (existing, watched) {
+                (Some(watch), true) => {
+                    if let Some(auto_queue) = auto_queue {
+                        watch.auto_queue = auto_queue;
+                    }
+                }
+                (None, true) => {
+                    let watch = WatchedPackage::new(
+                        &package_name,
+                        auto_queue.unwrap_or(false),
+                        catalog.get_game_by_package(&package_name),
+                    );
+                    settings.watched_packages.push(watch);
+                }
+                (_, false) => settings
+                    .watched_packages
+                    .retain(|watch| watch.package_name != package_name),
+            }
+            Ok(settings.watched_packages.clone())
+        })
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(WatchlistResult { watched })
+}
+
+// --- Collections ---
+
+/// Apply `change` to the saved collections and save them again.
+async fn update_collections<T>(
+    state: &AppState,
+    change: impl FnOnce(&mut Vec<GameCollection>) -> Result<T, CollectionError>,
+) -> Result<T, String> {
+    state
+        .settings
+        .update_with(|settings| Ok(change(&mut settings.collections)?))
+        .await
+        .map_err(|err| err.to_string())
+}
+
+async fn find_collection(state: &AppState, collection_id: &str) -> Result<GameCollection, String> {
+    state
+        .settings
+        .get_settings()
+        .await
+        .collections
+        .into_iter()
+        .find(|collection| collection.id == collection_id)
+        .ok_or_else(|| CollectionError::NotFound(collection_id.to_string()).to_string())
+}
+
+/// The catalog game of every member, in collection order; `None` for members
+/// the catalog doesn't list.
+async fn collection_games(state: &AppState, collection: &GameCollection) -> Vec<(String, Option<Game>)> {
+    let catalog = state.catalog.read().await;
+    collection
+        .packages
+        .iter()
+        .map(|package| (package.clone(), catalog.get_game_by_package(package).cloned()))
+        .collect()
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_collections_list(state: State<'_, AppState>) -> Result<CollectionListResult, String> {
+    Ok(CollectionListResult {
+        collections: state.settings.get_settings().await.collections,
+    })
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_collection_create(
+    state: State<'_, AppState>,
+    name: String,
+    package_names: Option<Vec<String>>,
+) -> Result<GameCollection, String> {
+    update_collections(&state, |collections| {
+        collection::create(collections, &name, &package_names.unwrap_or_default())
+    })
+    .await
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_collection_rename(
+    state: State<'_, AppState>,
+    collection_id: String,
+    name: String,
+) -> Result<GameCollection, String> {
+    update_collections(&state, |collections| {
+        collection::rename(collections, &collection_id, &name)
+    })
+    .await
+}
+
+#[tauri::command]
+#[specta]
+pub async fn backend_collection_delete(
     state: State<'_, AppState>,
-    package_name: String,
-) -> Result<CatalogGameVersions, String> {
-    let games = state.catalog.read().await.get_games_by_package(&package_name);
-    let games: Vec<CatalogSearchGame> = games
-        .into_iter()
-        .map(|game| CatalogSearchGame {
-            package_name: game.package_name,
-            release_name: game.release_name,
-            game_name: game.game_name,
-            version_code: game.version_code,
-            version_name: game.version_name,
-            size: game.size,
-            last_updated: game.last_updated,
-            downloads: game.downloads,
-        })
-        .collect();
-    Ok(CatalogGameVersions { games })
+    collection_id: String,
+) -> Result<CollectionListResult, String> {
+    update_collections(&state, |collections| {
+        collection::delete(collections, &collection_id)
+    })
+    .await?;
+    backend_collections_list(state).await
 }
 
+/// Move a collection to `position` in the list.
 #[tauri::command]
 #[specta]
-pub async fn backend_catalog_thumbnail_path(
+pub async fn backend_collection_reorder(
     state: State<'_, AppState>,
-    package_name: String,
-) -> Result<CatalogThumbnailPath, String> {
-    let catalog = state.catalog.read().await;
-    let thumbnail_path = catalog.thumbnails_dir().join(format!("{package_name}.jpg"));
-    Ok(CatalogThumbnailPath {
-        thumbnail_path: thumbnail_path.display().to_string(),
-        thumbnail_exists: thumbnail_path.exists(),
+    collection_id: String,
+    position: u32,
+) -> Result<CollectionListResult, String> {
+    update_collections(&state, |collections| {
+        collection::move_collection(collections, &collection_id, position as usize)
     })
+    .await?;
+    backend_collections_list(state).await
 }
 
 #[tauri::command]
 #[specta]
-pub async fn backend_catalog_note(
+pub async fn backend_collection_add_games(
     state: State<'_, AppState>,
-    package_name: String,
-) -> Result<CatalogNote, String> {
-    let catalog = state.catalog.read().await;
-    let path = catalog.notes_dir().join(format!("{package_name}.txt"));
-    let note = if path.exists() {
-        std::fs::read_to_string(&path).unwrap_or_default()
-    } else {
-        String::new()
-    };
-    Ok(CatalogNote { note })
+    collection_id: String,
+    package_names: Vec<String>,
+) -> Result<GameCollection, String> {
+    update_collections(&state, |collections| {
+        collection::add_packages(collections, &collection_id, &package_names)
+    })
+    .await
 }
 
 #[tauri::command]
 #[specta]
-pub async fn backend_catalog_library(
+pub async fn backend_collection_remove_games(
     state: State<'_, AppState>,
-    query: Option<String>,
-    sort_by: Option<String>,
-    sort_ascending: Option<bool>,
-    filter: Option<String>,
-    limit: Option<u32>,
-    offset: Option<u32>,
-) -> Result<LibraryResult, String> {
-    let query = query.unwrap_or_default();
-    let limit = limit.unwrap_or(100);
-    let offset = offset.unwrap_or(0);
-    let sort_by = sort_by.unwrap_or_else(|| "name".to_string());
-    let sort_ascending = sort_ascending.unwrap_or(true);
-    let filter = filter.unwrap_or_else(|| "all".to_string());
+    collection_id: String,
+    package_names: Vec<String>,
+) -> Result<GameCollection, String> {
+    update_collections(&state, |collections| {
+        collection::remove_packages(collections, &collection_id, &package_names)
+    })
+    .await
+}
 
-    let favorites = state.settings.get_settings().await.favorited_games;
-    let mut games = state.catalog.read().await.search(&query);
+/// Move a member to `position` within its collection.
+#[tauri::command]
+#[specta]
+pub async fn backend_collection_move_game(
+    state: State<'_, AppState>,
+    collection_id: String,
+    package_name: String,
+    position: u32,
+) -> Result<GameCollection, String> {
+    update_collections(&state, |collections| {
+        collection::move_package(collections, &collection_id, &package_name, position as usize)
+    })
+    .await
+}
 
-    if filter == "favorites" {
-        games.retain(|g| favorites.iter().any(|pkg| pkg == &g.package_name));
-    }
+/// Write the given collections, or all of them, to a JSON file that
+/// `backend_collections_import` reads back.
+#[tauri::command]
+#[specta]
+pub async fn backend_collections_export(
+    state: State<'_, AppState>,
+    path: String,
+    collection_ids: Option<Vec<String>>,
+) -> Result<CollectionExportResult, String> {
+    let collections = state.settings.get_settings().await.collections;
+    let file = collection::export(&collections, collection_ids.as_deref()).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    crate::logger::log(&format!(
+        "[COLLECTIONS] Exported {} collections to {}",
+        file.collections.len(),
+        path
+    ));
+    Ok(CollectionExportResult {
+        path,
+        count: file.collections.len() as u32,
+    })
+}
 
-    match sort_by.as_str() {
-        "date" => games.sort_by(|a, b| a.last_updated.cmp(&b.last_updated)),
-        "size" => games.sort_by(|a, b| {
-            // Parse size strings like "1.5 GB" or "500 MB" to numeric MB values
-            let size_a = parse_size_mb(&a.size);
-            let size_b = parse_size_mb(&b.size);
-            size_a.partial_cmp(&size_b).unwrap_or(std::cmp::Ordering::Equal)
-        }),
-        "popularity" => games.sort_by(|a, b| {
-            // Sort by popularity_rank (lower rank = more popular)
-            // Unranked games (rank 0 or negative) go to the end
-            let rank_a = if a.popularity_rank > 0 { a.popularity_rank } else { i32::MAX };
-            let rank_b = if b.popularity_rank > 0 { b.popularity_rank } else { i32::MAX };
-            rank_a.cmp(&rank_b)
-        }),
-        _ => games.sort_by(|a, b| a.game_name.cmp(&b.game_name)),
-    }
+/// Merge collections from an exported file. Collections are matched by name;
+/// a match gains the packages it is missing.
+#[tauri::command]
+#[specta]
+pub async fn backend_collections_import(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<CollectionImportResult, String> {
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let file: CollectionFile =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid collection file {path}: {e}"))?;
+    let summary = update_collections(&state, |collections| collection::import(collections, &file)).await?;
+    crate::logger::log(&format!(
+        "[COLLECTIONS] Imported {} from {}: {} created, {} merged",
+        file.collections.len(),
+        path,
+        summary.created,
+        summary.merged
+    ));
+    Ok(CollectionImportResult {
+        created: summary.created as u32,
+        merged: summary.merged as u32,
+        collections: state.settings.get_settings().await.collections,
+    })
+}
 
-    if !sort_ascending {
-        games.reverse();
-    }
+/// Queue the newest release of every member that isn't queued or downloaded yet.
+#[tauri::command]
+#[specta]
+pub async fn backend_collection_queue(
+    state: State<'_, AppState>,
+    collection_id: String,
+) -> Result<CollectionQueueResult, String> {
+    let collection = find_collection(&state, &collection_id).await?;
+    let games = collection_games(&state, &collection).await;
 
-    let total = games.len();
-    let mut paged = Vec::new();
-    let start = offset as usize;
-    let end = (start + limit as usize).min(games.len());
-    
-    if start < games.len() {
-        let download = state.download.lock().await;
-        for game in &games[start..end] {
-            let is_downloaded = download.is_downloaded(game).await;
-            paged.push(LibraryGame {
-                package_name: game.package_name.clone(),
-                release_name: game.release_name.clone(),
-                game_name: game.game_name.clone(),
-                size: game.size.clone(),
-                last_updated: game.last_updated.clone(),
-                version_code: game.version_code.clone(),
-                downloads: game.downloads.clone(),
-                is_favorite: favorites.iter().any(|pkg| pkg == &game.package_name),
-                is_new: game.is_new,
-                popularity_rank: game.popularity_rank,
-                is_downloaded,
-            });
+    let download = state.download.lock().await;
+    let mut queued = Vec::new();
+    let mut skipped = Vec::new();
+    for (package_name, game) in games {
+        let reason = match game {
+            None => Some("not_in_catalog"),
+            Some(game) if game.is_local() => Some("local_entry"),
+            Some(game) => {
+                let already_queued = download
+                    .queue()
+                    .await
+                    .iter()
+                    .any(|item| item.game.package_name == package_name);
+                if already_queued {
+                    Some("already_queued")
+                } else if download.is_release_downloaded(&game.release_name) {
+                    Some("already_downloaded")
+                } else if check_queue_space(&download, &game).await.is_err() {
+                    Some("insufficient_space")
+                } else if download.add_to_queue(game).await {
+                    None
+                } else {
+                    Some("not_added")
+                }
+            }
+        };
+        match reason {
+            Some(reason) => skipped.push(CollectionSkippedGame {
+                package_name,
+                reason: reason.to_string(),
+            }),
+            None => queued.push(package_name),
         }
     }
-
-    Ok(LibraryResult {
-        games: paged,
-        total: total as u32,
-        offset,
-        limit,
-        query,
-        sort_by,
-        sort_ascending,
-        filter,
-        favorites_count: favorites.len() as u32,
+    let queue_length = download.queue().await.len() as u32;
+    drop(download);
+    save_download_queue(&state).await;
+
+    crate::logger::log(&format!(
+        "[COLLECTIONS] Queued {} of {} games from '{}'",
+        queued.len(),
+        collection.packages.len(),
+        collection.name
+    ));
+    Ok(CollectionQueueResult {
+        collection_id,
+        queued,
+        skipped,
+        queue_length,
     })
 }
 
+enum InstallSource {
+    /// Folder of an imported local entry
+    GameDir(PathBuf),
+    /// Download folder holding the release archive
+    Archive(PathBuf),
+}
+
+/// Install every downloaded member, and every local entry, onto the selected
+/// device one after another. Progress is reported under one operation.
 #[tauri::command]
 #[specta]
-pub async fn backend_favorites_toggle(
+pub async fn backend_collection_install(
     state: State<'_, AppState>,
-    package_name: String,
-) -> Result<FavoritesToggleResult, String> {
-    let mut settings = state.settings.get_settings().await;
-    let exists = settings.favorited_games.iter().any(|pkg| pkg == &package_name);
+    collection_id: String,
+) -> Result<CollectionInstallResult, String> {
+    let collection = find_collection(&state, &collection_id).await?;
+    let serial = selected_serial(&state)
+        .await
+        .ok_or_else(|| "No device selected. Connect and select a headset to install to.".to_string())?;
+    let games = collection_games(&state, &collection).await;
 
-    if exists {
-        settings.favorited_games.retain(|pkg| pkg != &package_name);
-    } else {
-        settings.favorited_games.push(package_name.clone());
+    // (package, release, where to install from) of each member to install
+    let mut targets = Vec::new();
+    let mut skipped = Vec::new();
+    {
+        let catalog = state.catalog.read().await;
+        let download = state.download.lock().await;
+        for (package_name, game) in games {
+            let reason = match game {
+                None => Some("not_in_catalog"),
+                Some(game) if game.is_local() => match catalog.local_game_dir(&game.release_name) {
+                    Some(dir) => {
+                        targets.push((package_name.clone(), game.release_name.clone(), InstallSource::GameDir(PathBuf::from(dir))));
+                        None
+                    }
+                    None => Some("no_local_folder"),
+                },
+                Some(game) if download.is_release_downloaded(&game.release_name) => {
+                    let hash_dir = download.get_download_dir(&game);
+                    targets.push((package_name.clone(), game.release_name, InstallSource::Archive(hash_dir)));
+                    None
+                }
+                Some(_) => Some("not_downloaded"),
+            };
+            if let Some(reason) = reason {
+                skipped.push(CollectionSkippedGame {
+                    package_name,
+                    reason: reason.to_string(),
+                });
+            }
+        }
     }
 
-    state
-        .settings
-        .update_settings(settings.clone())
-        .await
-        .map_err(|err| err.to_string())?;
+    if targets.is_empty() {
+        if skipped.is_empty() {
+            return Err(format!("Collection '{}' has no games to install", collection.name));
+        }
+        let reasons: Vec<String> = skipped
+            .iter()
+            .map(|game| format!("{} ({})", game.package_name, game.reason))
+            .collect();
+        return Err(format!(
+            "Nothing to install from collection '{}': {}",
+            collection.name,
+            reasons.join(", ")
+        ));
+    }
 
-    Ok(FavoritesToggleResult {
-        package_name,
-        is_favorite: !exists,
-        favorites: settings.favorited_games,
+    let operation_id = Uuid::new_v4().to_string();
+    let packages: Vec<String> = targets.iter().map(|(package, ..)| package.clone()).collect();
+    let install_service = state.install.clone();
+    let app_state = state.inner().clone();
+    let op_id = operation_id.clone();
+
+    tokio::spawn(async move {
+        crate::logger::log(&format!(
+            "[INSTALL] Installing {} games from collection '{}'",
+            targets.len(),
+            collection.name
+        ));
+        let password = app_state.config.fetch_config().await.ok().map(|c| c.password.clone());
+        let total = targets.len().max(1) as f64;
+        let mut failed = Vec::new();
+
+        for (index, (package_name, release_name, source)) in targets.into_iter().enumerate() {
+            let percent = index as f64 / total * 100.0;
+            app_state
+                .push_operation_event(
+                    "install.progress",
+                    &op_id,
+                    "install",
+                    "running",
+                    &format!("Installing {release_name} ({}/{})", index + 1, total as usize),
+                    percent,
+                )
+                .await;
+
+            if !install_service.try_start_install(&package_name).await {
+                failed.push(format!("{package_name}: already installing"));
+                continue;
+            }
+            let result = match source {
+                InstallSource::GameDir(game_dir) => {
+                    install_service
+                        .install_from_game_dir(&game_dir, &package_name, Some(serial.as_str()), password.clone(), None)
+                        .await
+                }
+                InstallSource::Archive(hash_dir) => {
+                    install_service
+                        .install_game(&hash_dir, &package_name, &release_name, Some(serial.as_str()), password.clone(), None)
+                        .await
+                }
+            };
+            install_service.finish_install(&package_name).await;
+
+            match result {
+                Ok(res) if res.success => {}
+                Ok(res) => failed.push(format!("{package_name}: {}", res.message)),
+                Err(e) => failed.push(format!("{package_name}: {e}")),
+            }
+        }
+
+        invalidate_installed_packages(&app_state).await;
+        let (state, msg) = if failed.is_empty() {
+            ("succeeded", "Collection installed".to_string())
+        } else {
+            ("failed", format!("{} installs failed: {}", failed.len(), failed.join("; ")))
+        };
+        crate::logger::log(&format!("[INSTALL] Collection '{}': {}", collection.name, msg));
+        app_state
+            .push_operation_event("install.completed", &op_id, "install", state, &msg, 100.0)
+            .await;
+    });
+
+    Ok(CollectionInstallResult {
+        operation_id,
+        collection_id,
+        packages,
+        skipped,
     })
 }
 
@@ -939,42 +2246,18 @@ pub async fn backend_download_queue_status(state: State<'_, AppState>) -> Result
     let total_count = queue_items.len() as u32;
     let queued_count = queue_items.iter().filter(|i| i.status == DownloadStatus::Queued).count() as u32;
 
-    let queue: Vec<DownloadQueueItem> = queue_items
-        .iter()
-        .map(|item| DownloadQueueItem {
-            package_name: item.game.package_name.clone(),
-            release_name: item.game.release_name.clone(),
-            game_name: Some(item.game.game_name.clone()),
-            status: map_download_status(item.status).to_string(),
-            progress_percent: item.progress.percent,
-            speed: item.progress.speed.clone(),
-            eta: item.progress.eta.clone(),
-            bytes_transferred: item.progress.bytes_transferred as f64,
-            total_bytes: item.progress.total_bytes as f64,
-            retry_count: None,
-            error: if item.error.is_empty() { None } else { Some(item.error.clone()) },
-        })
-        .collect();
+    let queue: Vec<DownloadQueueItem> = queue_items.iter().map(download_queue_item).collect();
 
-    // Find the active download (currently downloading)
-    let active_download = queue_items
+    // Running downloads, in slot order
+    let mut running: Vec<_> = queue_items
         .iter()
-        .find(|item| item.status == DownloadStatus::Downloading)
-        .map(|item| DownloadQueueItem {
-            package_name: item.game.package_name.clone(),
-            release_name: item.game.release_name.clone(),
-            game_name: Some(item.game.game_name.clone()),
-            status: map_download_status(item.status).to_string(),
-            progress_percent: item.progress.percent,
-            speed: item.progress.speed.clone(),
-            eta: item.progress.eta.clone(),
-            bytes_transferred: item.progress.bytes_transferred as f64,
-            total_bytes: item.progress.total_bytes as f64,
-            retry_count: None,
-            error: if item.error.is_empty() { None } else { Some(item.error.clone()) },
-        });
+        .filter(|item| item.status.is_active())
+        .collect();
+    running.sort_by_key(|item| item.slot);
+    let active_downloads: Vec<DownloadQueueItem> = running.into_iter().map(download_queue_item).collect();
 
     let processing = download.is_processing().await;
+    let slots = download.max_slots() as u32;
     drop(download);
 
     Ok(DownloadQueueStatus {
@@ -982,10 +2265,33 @@ pub async fn backend_download_queue_status(state: State<'_, AppState>) -> Result
         queued_count,
         total_count,
         processing,
-        active_download,
+        active_download: active_downloads.first().cloned(),
+        active_downloads,
+        slots,
     })
 }
 
+fn download_queue_item(item: &DownloadItem) -> DownloadQueueItem {
+    DownloadQueueItem {
+        package_name: item.game.package_name.clone(),
+        release_name: item.game.release_name.clone(),
+        game_name: Some(item.game.game_name.clone()),
+        status: item.status.as_str().to_string(),
+        progress_percent: item.progress.percent,
+        speed: item.progress.speed.clone(),
+        eta: item.progress.eta.clone(),
+        bytes_transferred: item.progress.bytes_transferred as f64,
+        total_bytes: item.progress.total_bytes as f64,
+        priority: item.priority,
+        slot: item.slot.map(|slot| slot as u32),
+        retry_count: Some(item.retry_count),
+        next_retry_at: item.next_retry_at.map(|at| at.to_rfc3339()),
+        failure_reason: item.failure.map(|failure| failure.as_str().to_string()),
+        failure_transient: item.failure.map(|failure| failure.is_transient()),
+        error: if item.error.is_empty() { None } else { Some(item.error.clone()) },
+    }
+}
+
 #[tauri::command]
 #[specta]
 pub async fn backend_download_queue_add(
@@ -1008,6 +2314,12 @@ pub async fn backend_download_queue_add(
     let Some(game) = maybe_game else {
         return Err(format!("No catalog game found for package: {package_name}"));
     };
+    if game.is_local() {
+        return Err(format!(
+            "{} is a local entry; install it directly",
+            game.release_name
+        ));
+    }
 
     let download = state.download.lock().await;
     
@@ -1026,12 +2338,27 @@ pub async fn backend_download_queue_add(
             package_name,
             queue_length,
             reason: Some("already_queued".to_string()),
+            warning: None,
         });
     }
-    
+
+    let warning = match check_queue_space(&download, &game).await {
+        Ok(warning) => warning,
+        Err(message) => {
+            return Ok(DownloadQueueAddResult {
+                added: false,
+                retried: false,
+                package_name,
+                queue_length: queue_items.len() as u32,
+                reason: Some("insufficient_space".to_string()),
+                warning: Some(message),
+            });
+        }
+    };
     let added = download.add_to_queue(game).await;
     let queue_length = download.queue().await.len() as u32;
     drop(download);
+    save_download_queue(&state).await;
     
     Ok(DownloadQueueAddResult {
         added,
@@ -1039,9 +2366,32 @@ pub async fn backend_download_queue_add(
         package_name,
         queue_length,
         reason: None,
+        warning,
     })
 }
 
+/// Check `game` against the free space of the download drive before queueing
+/// it: an error when it can't fit even on its own, a warning when it only
+/// fits if some pending downloads don't go ahead.
+async fn check_queue_space(download: &DownloadService, game: &Game) -> Result<Option<String>, String> {
+    let check = match download.space_to_queue(game).await {
+        Ok(check) => check,
+        Err(e) => {
+            crate::logger::log(&format!("[DOWNLOAD] Could not read free space, queueing anyway: {}", e));
+            return Ok(None);
+        }
+    };
+    if !check.fits_alone() {
+        crate::logger::log(&format!("[DOWNLOAD] Not queueing {}: {}", game.package_name, check.message()));
+        return Err(check.message());
+    }
+    if !check.fits() {
+        crate::logger::log(&format!("[DOWNLOAD] Queueing {} anyway: {}", game.package_name, check.message()));
+        return Ok(Some(check.message()));
+    }
+    Ok(None)
+}
+
 #[tauri::command]
 #[specta]
 pub async fn backend_download_queue_remove(
@@ -1051,6 +2401,7 @@ pub async fn backend_download_queue_remove(
     let download = state.download.lock().await;
     let _removed = download.remove_from_queue(&package_name).await;
     drop(download);
+    save_download_queue(&state).await;
     backend_download_queue_status(state).await
 }
 
@@ -1064,6 +2415,23 @@ pub async fn backend_download_queue_reorder(
     let download = state.download.lock().await;
     let _moved = download.reorder_queue(&package_name, position as usize).await;
     drop(download);
+    save_download_queue(&state).await;
+    backend_download_queue_status(state).await
+}
+
+/// Set the priority of a queued download; higher priorities take the next
+/// free slot first.
+#[tauri::command]
+#[specta]
+pub async fn backend_download_set_priority(
+    state: State<'_, AppState>,
+    package_name: String,
+    priority: i32,
+) -> Result<DownloadQueueStatus, String> {
+    let download = state.download.lock().await;
+    let _updated = download.set_priority(&package_name, priority).await;
+    drop(download);
+    save_download_queue(&state).await;
     backend_download_queue_status(state).await
 }
 
@@ -1074,14 +2442,48 @@ pub async fn backend_download_start_processing(state: State<'_, AppState>) -> Re
     let download_clone = download.clone();
     let app_state = state.inner().clone();
     drop(download);
+    let last_saved = std::sync::Mutex::new(Instant::now());
 
     tokio::spawn(async move {
         let _ = download_clone
             .process_queue_with_callback(move |item| {
                 let app_state = app_state.clone();
+                let save = !item.status.is_active() || {
+                    let mut last_saved = last_saved.lock().unwrap();
+                    let due = last_saved.elapsed() >= DOWNLOAD_QUEUE_SAVE_INTERVAL;
+                    if due {
+                        *last_saved = Instant::now();
+                    }
+                    due
+                };
                 async move {
+                    if save {
+                        save_download_queue(&app_state).await;
+                    }
+                    if let (DownloadStatus::Queued, Some(retry_at)) = (item.status, item.next_retry_at) {
+                        let reason = item.failure.map(|failure| failure.as_str()).unwrap_or("unknown");
+                        let message = format!(
+                            "{}: {} failure, retry {} at {}",
+                            item.game.game_name,
+                            reason,
+                            item.retry_count,
+                            retry_at.to_rfc3339()
+                        );
+                        app_state
+                            .push_operation_event(
+                                "download.retrying",
+                                &item.operation_id,
+                                "download",
+                                "running",
+                                &message,
+                                item.progress.percent,
+                            )
+                            .await;
+                        return;
+                    }
                     let event_name = match item.status {
                         DownloadStatus::Downloading => "download.progress",
+                        DownloadStatus::Verifying => "download.verifying",
                         DownloadStatus::Paused => "download.paused",
                         DownloadStatus::Completed => "download.completed",
                         DownloadStatus::Failed => "download.failed",
@@ -1089,7 +2491,7 @@ pub async fn backend_download_start_processing(state: State<'_, AppState>) -> Re
                         _ => return,
                     };
                     let state = match item.status {
-                        DownloadStatus::Downloading => "running",
+                        DownloadStatus::Downloading | DownloadStatus::Verifying => "running",
                         DownloadStatus::Paused => "paused",
                         DownloadStatus::Completed => "succeeded",
                         DownloadStatus::Failed => "failed",
@@ -1122,33 +2524,36 @@ pub async fn backend_download_cancel(
     let download = state.download.lock().await;
     
     // If a package name is provided, remove it from the queue regardless of status.
-    // If it's currently downloading, it will be cancelled as well.
+    // If it's currently downloading, its slot is cancelled as well.
     if let Some(pkg) = package_name {
-        // Find if it is the current active download
-        let is_active = download.queue().await.iter().any(|i| i.game.package_name == pkg && i.status == DownloadStatus::Downloading);
-        
-        if is_active {
-            let _ = download.cancel_current().await;
-        }
-        
+        download.cancel_item(&pkg).await;
+
         // Remove from queue completely so it doesn't linger in UI
         let cancelled = download.remove_from_queue(&pkg).await;
+        drop(download);
+        save_download_queue(&state).await;
         return Ok(DownloadCancelResult { cancelled });
     }
 
-    // Fallback: cancel current active download if no package specified
+    // Fallback: cancel every running download if no package specified
+    let running: Vec<String> = download
+        .queue()
+        .await
+        .iter()
+        .filter(|i| i.status.is_active())
+        .map(|i| i.game.package_name.clone())
+        .collect();
     let cancelled = download
         .cancel_current()
         .await
         .map_err(|err| err.to_string())?;
-    
-    // Also remove the cancelled item from queue
-    if cancelled {
-        let queue = download.queue().await;
-        if let Some(item) = queue.iter().find(|i| i.status == DownloadStatus::Cancelled) {
-            download.remove_from_queue(&item.game.package_name).await;
-        }
+
+    // Also remove the cancelled items from queue
+    for pkg in running {
+        download.remove_from_queue(&pkg).await;
     }
+    drop(download);
+    save_download_queue(&state).await;
 
     Ok(DownloadCancelResult { cancelled })
 }
@@ -1170,6 +2575,7 @@ pub async fn backend_download_retry(
             package_name,
             queue_length: result.queue_length,
             reason: None,
+            warning: result.warning,
         })
     } else {
         Ok(result)
@@ -1204,7 +2610,12 @@ pub async fn backend_download_resume_item(
     state: State<'_, AppState>,
     package_name: String,
 ) -> Result<bool, String> {
-    state.download.lock().await.resume_item(&package_name).await.map_err(|e| e.to_string())
+    let resumed = state.download.lock().await.resume_item(&package_name).await.map_err(|e| e.to_string())?;
+    if resumed {
+        save_download_queue(&state).await;
+        backend_download_start_processing(state).await?;
+    }
+    Ok(resumed)
 }
 
 #[tauri::command]
@@ -1233,11 +2644,18 @@ pub async fn backend_download_location_get(state: State<'_, AppState>) -> Result
         0
     };
 
+    let free_bytes = crate::services::disk_space::available_space(&download_dir).unwrap_or_else(|e| {
+        crate::logger::log(&format!("[DOWNLOAD] Could not read free space of {}: {}", download_dir.display(), e));
+        0
+    });
+    let queued_bytes = state.download.lock().await.queued_space().await;
+
     Ok(DownloadLocation {
         download_dir: download_dir.display().to_string(),
         path: download_dir.display().to_string(),
         exists,
-        free_bytes: 100.0 * 1024.0 * 1024.0 * 1024.0, // Mock 100GB
+        free_bytes: free_bytes as f64,
+        queued_bytes: queued_bytes as f64,
         file_count,
     })
 }
@@ -1407,7 +2825,16 @@ pub async fn backend_install_game(
     let Some(game) = maybe_game else {
         return Err(format!("No catalog game found for package: {package_name}"));
     };
-
+    // Imported entries are installed from their own folder, nothing is downloaded
+    let local_dir = if game.is_local() {
+        let dir = catalog
+            .local_game_dir(&game.release_name)
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("No folder recorded for {}", game.release_name))?;
+        Some(dir)
+    } else {
+        None
+    };
 
     let hash_dir = state.download.lock().await.get_download_dir(&game);
     let serial = selected_serial(&state).await;
@@ -1450,21 +2877,31 @@ pub async fn backend_install_game(
             pkg_name_clone, release_name_clone
         ));
 
-        let password = app_state.config.fetch_config().await.ok().map(|c| c.password);
+        let password = app_state.config.fetch_config().await.ok().map(|c| c.password.clone());
 
-        let result = install_service
-            .install_game(
-                &hash_dir,
-                &pkg_name_clone,
-                &release_name_clone,
-                serial.as_deref(),
-                password,
-                Some(tx),
-            )
-            .await;
+        let result = match local_dir {
+            Some(game_dir) => {
+                install_service
+                    .install_from_game_dir(&game_dir, &pkg_name_clone, serial.as_deref(), password, Some(tx))
+                    .await
+            }
+            None => {
+                install_service
+                    .install_game(
+                        &hash_dir,
+                        &pkg_name_clone,
+                        &release_name_clone,
+                        serial.as_deref(),
+                        password,
+                        Some(tx),
+                    )
+                    .await
+            }
+        };
 
         // Always release the install lock
         install_service.finish_install(&pkg_name_clone).await;
+        invalidate_installed_packages(&app_state).await;
 
         match result {
             Ok(res) => {
@@ -1527,6 +2964,7 @@ pub async fn backend_uninstall_game(
         )
         .await
         .map_err(|err| err.to_string())?;
+    invalidate_installed_packages(&state).await;
     Ok(UninstallResult {
         uninstalled: result.success,
         package_name,
@@ -1540,6 +2978,11 @@ pub async fn backend_installed_apps(state: State<'_, AppState>) -> Result<Instal
     let serial = selected_serial(&state).await;
     let output = state.adb.shell("pm list packages --show-versioncode", serial.as_deref()).await.map_err(|e| e.to_string())?;
     let packages = AdbService::parse_packages_with_versions_output(&output.stdout);
+    state
+        .installed_cache
+        .lock()
+        .await
+        .insert(serial.unwrap_or_default(), packages.keys().cloned().collect());
 
     let catalog = state.catalog.read().await;
     let mut apps: Vec<InstalledApp> = packages
@@ -1548,17 +2991,15 @@ pub async fn backend_installed_apps(state: State<'_, AppState>) -> Result<Instal
             let catalog_game = catalog.get_game_by_package(&pkg);
             let in_catalog = catalog_game.is_some();
             let game_name = catalog_game.map(|g| g.game_name.clone());
-            let catalog_version_code = catalog_game.map(|g| g.version_code.clone());
-            let size = catalog_game.map(|g| g.size.clone());
-
-            let mut update_available = false;
-            if let (Some(installed_ver), Some(catalog_ver)) = (version.as_ref(), catalog_version_code.as_ref()) {
-                let inst: i64 = installed_ver.parse().unwrap_or(0);
-                let cat: i64 = catalog_ver.parse().unwrap_or(0);
-                if cat > inst {
-                    update_available = true;
+            let catalog_version_code = catalog_game.map(|g| g.version_code);
+            let size = catalog_game.map(|g| g.size_label());
+
+            let update_available = match (version.as_deref(), catalog_version_code) {
+                (Some(installed_ver), Some(catalog_ver)) => {
+                    parse_version_code(installed_ver).is_some_and(|installed| catalog_ver > installed)
                 }
-            }
+                _ => false,
+            };
 
             InstalledApp {
                 package_name: pkg.clone(),
@@ -1570,7 +3011,7 @@ pub async fn backend_installed_apps(state: State<'_, AppState>) -> Result<Instal
                 last_update_time: None,
                 in_catalog,
                 game_name,
-                catalog_version_code,
+                catalog_version_code: catalog_version_code.map(|code| code.to_string()),
                 installed_version_code: version,
                 size,
                 update_available,
@@ -1613,6 +3054,7 @@ pub async fn backend_installed_app_version(
 pub async fn backend_install_local(state: State<'_, AppState>, path: String) -> Result<InstallLocalResult, String> {
     let serial = selected_serial(&state).await;
     let result = state.adb.install_apk(&path, serial.as_deref()).await.map_err(|e| e.to_string())?;
+    invalidate_installed_packages(&state).await;
     Ok(InstallLocalResult {
         success: result.success(),
         message: result.output(),
@@ -1633,6 +3075,13 @@ pub async fn backend_device_state(state: State<'_, AppState>) -> Result<DeviceSt
         *state.selected_serial.write().await = Some(auto_serial.clone());
         selected = Some(auto_serial);
     }
+    // Drop the installed packages of devices that went away, they may have
+    // changed by the time they come back
+    state
+        .installed_cache
+        .lock()
+        .await
+        .retain(|serial, _| devices.iter().any(|device| &device.serial == serial));
 
     let devices_payload: Vec<DeviceInfo> = devices
         .iter()
@@ -1718,6 +3167,7 @@ pub async fn backend_select_device(
     serial: String,
 ) -> Result<DeviceState, String> {
     *state.selected_serial.write().await = Some(serial);
+    invalidate_installed_packages(&state).await;
     backend_device_state(state).await
 }
 
@@ -1725,6 +3175,7 @@ pub async fn backend_select_device(
 #[specta]
 pub async fn backend_clear_device_selection(state: State<'_, AppState>) -> Result<DeviceState, String> {
     *state.selected_serial.write().await = None;
+    invalidate_installed_packages(&state).await;
     backend_device_state(state).await
 }
 
@@ -1741,6 +3192,7 @@ pub async fn backend_wireless_connect(
         .connect_wireless(&endpoint)
         .await
         .map_err(|err| err.to_string())?;
+    invalidate_installed_packages(&state).await;
 
     if save_endpoint.unwrap_or(false) {
         let patch = json!({
@@ -1777,6 +3229,7 @@ pub async fn backend_wireless_disconnect(
         .disconnect_wireless(endpoint.as_deref())
         .await
         .map_err(|err| err.to_string())?;
+    invalidate_installed_packages(&state).await;
     Ok(WirelessDisconnectResult {
         disconnected: result.success(),
         message: result.output(),
@@ -1794,6 +3247,7 @@ pub async fn backend_wireless_reconnect(state: State<'_, AppState>) -> Result<Wi
             .connect_wireless(&endpoint)
             .await
             .map_err(|err| err.to_string())?;
+        invalidate_installed_packages(&state).await;
         Ok(WirelessReconnectResult {
             reconnected: result.success(),
             endpoint: Some(endpoint),
@@ -2285,6 +3739,8 @@ pub async fn backend_adb_console_execute(
         .shell(&command, serial.as_deref())
         .await
         .map_err(|e| e.to_string())?;
+    // Console commands can install or remove packages
+    invalidate_installed_packages(&state).await;
     let success = output.success();
     Ok(AdbConsoleExecuteResult {
         success,
@@ -2304,11 +3760,56 @@ pub async fn backend_adb_console_history(
 #[tauri::command]
 #[specta]
 pub async fn backend_new_apps_discovery(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> Result<NewAppsDiscoveryResult, String> {
+    let catalog = state.catalog.read().await;
+    let snapshot = catalog.snapshot();
+    let now = Utc::now().timestamp();
+
+    // Everything still inside the "new" window, plus version bumps from the last
+    // change that are inside it too
+    let mut new_apps: Vec<NewAppInfo> = snapshot
+        .packages
+        .iter()
+        .filter(|(package, _)| snapshot.is_new(package, catalog.new_window_days(), now))
+        .map(|(package, entry)| NewAppInfo {
+            package_name: package.clone(),
+            app_name: entry.game_name.clone(),
+            install_date: entry.first_seen as f64,
+            change: "added".to_string(),
+            version_code: entry.version_code.to_string(),
+            previous_version_code: None,
+        })
+        .collect();
+
+    for bump in &snapshot.last_diff.updated {
+        if !snapshot.is_recently_updated(&bump.package_name, catalog.new_window_days(), now) {
+            continue;
+        }
+        if let Some(entry) = snapshot.packages.get(&bump.package_name) {
+            new_apps.push(NewAppInfo {
+                package_name: bump.package_name.clone(),
+                app_name: entry.game_name.clone(),
+                install_date: entry.updated_at as f64,
+                change: "updated".to_string(),
+                version_code: bump.to.to_string(),
+                previous_version_code: Some(bump.from.to_string()),
+            });
+        }
+    }
+
+    new_apps.sort_by(|a, b| {
+        b.install_date
+            .partial_cmp(&a.install_date)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.app_name.cmp(&b.app_name))
+    });
+
     Ok(NewAppsDiscoveryResult {
-        new_apps: vec![],
-        count: 0,
+        count: new_apps.len() as u32,
+        new_apps,
+        removed: snapshot.last_diff.removed.clone(),
+        since: snapshot.last_diff.previous_taken_at.map(|t| t as f64),
     })
 }
 
@@ -2429,18 +3930,43 @@ pub fn register_invoke_handler(builder: tauri::Builder<Wry>) -> tauri::Builder<W
         backend_catalog_status,
         backend_catalog_load_cache,
         backend_catalog_sync,
+        backend_catalog_sync_cancel,
         backend_catalog_search,
         search_youtube_trailer,
         backend_catalog_game_detail,
         backend_catalog_game_versions,
+        backend_catalog_version_diff,
         backend_catalog_thumbnail_path,
         backend_catalog_note,
+        backend_catalog_parse_report,
+        backend_catalog_sources,
+        backend_config_sources,
+        backend_catalog_annotations,
+        backend_catalog_annotation_set,
         backend_catalog_library,
+        backend_catalog_export,
+        backend_catalog_import_local,
+        backend_catalog_local_remove,
         backend_favorites_toggle,
+        backend_watchlist_get,
+        backend_watchlist_set,
+        backend_collections_list,
+        backend_collection_create,
+        backend_collection_rename,
+        backend_collection_delete,
+        backend_collection_reorder,
+        backend_collection_add_games,
+        backend_collection_remove_games,
+        backend_collection_move_game,
+        backend_collections_export,
+        backend_collections_import,
+        backend_collection_queue,
+        backend_collection_install,
         backend_download_queue_status,
         backend_download_queue_add,
         backend_download_queue_remove,
         backend_download_queue_reorder,
+        backend_download_set_priority,
         backend_download_start_processing,
         backend_download_cancel,
         backend_download_retry,
@@ -2515,27 +4041,48 @@ mod tests {
     fn generate_bindings() {
         use crate::models::game::Game;
         use crate::models::settings::Settings;
-        use crate::models::config::PublicConfig;
+        use crate::models::catalog_report::{CatalogSyncSummary, MediaSyncReport};
+        use crate::models::config::{ConfigSourceHealth, PublicConfig};
         use crate::models::responses::*;
 
         tauri_specta::Builder::<tauri::Wry>::new()
             .typ::<Game>()
             .typ::<Settings>()
             .typ::<PublicConfig>()
+            .typ::<ConfigSourceHealth>()
             .typ::<BackendReadyState>()
             .typ::<BackendRecoverResult>()
             .typ::<SettingsResponse>()
             .typ::<SettingsUpdateResponse>()
             .typ::<CatalogStatus>()
             .typ::<CatalogSyncResult>()
+            .typ::<CatalogSyncCancelResult>()
+            .typ::<CatalogSyncSummary>()
             .typ::<CatalogSearchGame>()
             .typ::<CatalogSearchResult>()
             .typ::<CatalogGameDetail>()
             .typ::<CatalogGameVersions>()
+            .typ::<crate::models::version::ReleaseDiff>()
             .typ::<CatalogThumbnailPath>()
             .typ::<CatalogNote>()
+            .typ::<CatalogAnnotations>()
+            .typ::<CatalogParseReport>()
+            .typ::<MediaSyncReport>()
+            .typ::<CatalogSourceInfo>()
             .typ::<LibraryGame>()
             .typ::<LibraryResult>()
+            .typ::<PopularityTrend>()
+            .typ::<LibraryFacets>()
+            .typ::<CatalogExportResult>()
+            .typ::<LocalImportResult>()
+            .typ::<WatchAlert>()
+            .typ::<WatchlistResult>()
+            .typ::<GameCollection>()
+            .typ::<CollectionListResult>()
+            .typ::<CollectionImportResult>()
+            .typ::<CollectionQueueResult>()
+            .typ::<CollectionInstallResult>()
+            .typ::<LocalRemoveResult>()
             .typ::<FavoritesToggleResult>()
             .typ::<DownloadQueueItem>()
             .typ::<DownloadQueueStatus>()
@@ -2622,18 +4169,43 @@ mod tests {
                 backend_catalog_status,
                 backend_catalog_load_cache,
                 backend_catalog_sync,
+                backend_catalog_sync_cancel,
                 backend_catalog_search,
                 search_youtube_trailer,
                 backend_catalog_game_detail,
                 backend_catalog_game_versions,
+                backend_catalog_version_diff,
                 backend_catalog_thumbnail_path,
                 backend_catalog_note,
+                backend_catalog_parse_report,
+                backend_catalog_sources,
+                backend_config_sources,
+                backend_catalog_annotations,
+                backend_catalog_annotation_set,
                 backend_catalog_library,
+                backend_catalog_export,
+                backend_catalog_import_local,
+                backend_catalog_local_remove,
                 backend_favorites_toggle,
+                backend_watchlist_get,
+                backend_watchlist_set,
+                backend_collections_list,
+                backend_collection_create,
+                backend_collection_rename,
+                backend_collection_delete,
+                backend_collection_reorder,
+                backend_collection_add_games,
+                backend_collection_remove_games,
+                backend_collection_move_game,
+                backend_collections_export,
+                backend_collections_import,
+                backend_collection_queue,
+                backend_collection_install,
                 backend_download_queue_status,
                 backend_download_queue_add,
                 backend_download_queue_remove,
                 backend_download_queue_reorder,
+                backend_download_set_priority,
                 backend_download_start_processing,
                 backend_download_cancel,
                 backend_download_retry,
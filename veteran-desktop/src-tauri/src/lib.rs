@@ -20,6 +20,15 @@ pub fn run() {
                 let _ = crate::ipc::commands::backend_catalog_sync(state, Some(false)).await;
             });
 
+            let handle3 = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle3.state::<AppState>();
+                // Restore the download queue of the last run and resume it
+                if state.restore_download_queue().await > 0 {
+                    let _ = crate::ipc::commands::backend_download_start_processing(state).await;
+                }
+            });
+
             let handle2 = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 let state = handle2.state::<AppState>();
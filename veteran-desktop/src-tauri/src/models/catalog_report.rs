@@ -0,0 +1,274 @@
+use crate::models::game::{CatalogSchema, RowRejection};
+use serde::{Deserialize, Serialize};
+
+/// Rejected rows beyond this many are counted but not listed.
+pub const MAX_LISTED_REJECTIONS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct RejectedRow {
+    /// 1-based line number in the game list, header included
+    pub line: u32,
+    pub reason: RowRejection,
+    pub message: String,
+    pub content: String,
+}
+
+/// What happened while parsing a `VRP-GameList.txt`, so rows that were dropped
+/// or read with the wrong column layout are visible instead of silently lost.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, specta::Type)]
+#[serde(default)]
+pub struct CatalogParseReport {
+    /// Unix seconds when the list was parsed
+    #[specta(type = f64)]
+    pub parsed_at: i64,
+    /// Schema named by the header, or the one most rows looked like
+    pub schema: Option<CatalogSchema>,
+    /// Whether `schema` came from the header rather than the rows
+    pub schema_from_header: bool,
+    pub header: Vec<String>,
+    pub total_rows: u32,
+    pub accepted_rows: u32,
+    pub modern_rows: u32,
+    pub legacy_rows: u32,
+    /// Rows whose values looked like the other schema than the header names
+    pub schema_mismatches: u32,
+    pub rejected_count: u32,
+    pub rejected: Vec<RejectedRow>,
+    /// Rows repeating a release name seen earlier in the list
+    pub duplicate_keys: u32,
+    /// Rejected rows whose version code was not an integer
+    pub invalid_version_codes: u32,
+}
+
+impl CatalogParseReport {
+    pub fn has_problems(&self) -> bool {
+        self.rejected_count > 0
+            || self.schema_mismatches > 0
+            || self.duplicate_keys > 0
+    }
+
+    pub fn record_rejection(&mut self, line: u32, reason: RowRejection, content: &str) {
+        self.rejected_count += 1;
+        if reason == RowRejection::InvalidVersionCode {
+            self.invalid_version_codes += 1;
+        }
+        if self.rejected.len() < MAX_LISTED_REJECTIONS {
+            self.rejected.push(RejectedRow {
+                line,
+                reason,
+                message: reason.to_string(),
+                content: content.to_string(),
+            });
+        }
+    }
+
+    pub fn record_schema(&mut self, schema: CatalogSchema) {
+        match schema {
+            CatalogSchema::Modern => self.modern_rows += 1,
+            CatalogSchema::Legacy => self.legacy_rows += 1,
+        }
+    }
+
+    /// Fill in `schema` from the row counts when the header didn't name one.
+    pub fn settle_schema(&mut self) {
+        if self.schema.is_some() || self.modern_rows + self.legacy_rows == 0 {
+            return;
+        }
+        self.schema = Some(if self.modern_rows >= self.legacy_rows {
+            CatalogSchema::Modern
+        } else {
+            CatalogSchema::Legacy
+        });
+    }
+
+    /// One-line summary for the log.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} rows parsed, {} rejected, {} schema mismatches, {} duplicate keys, {} invalid version codes",
+            self.accepted_rows,
+            self.total_rows,
+            self.rejected_count,
+            self.schema_mismatches,
+            self.duplicate_keys,
+            self.invalid_version_codes
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+pub struct MediaSyncCounts {
+    pub added: u32,
+    pub updated: u32,
+    pub removed: u32,
+    pub unchanged: u32,
+}
+
+/// What a sync changed in the thumbnail and note caches.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+pub struct MediaSyncReport {
+    pub thumbnails: MediaSyncCounts,
+    pub notes: MediaSyncCounts,
+}
+
+impl MediaSyncReport {
+    /// One-line summary for the log.
+    pub fn summary(&self) -> String {
+        let counts = |c: &MediaSyncCounts| {
+            format!(
+                "{} added, {} updated, {} removed",
+                c.added, c.updated, c.removed
+            )
+        };
+        format!(
+            "thumbnails {}; notes {}",
+            counts(&self.thumbnails),
+            counts(&self.notes)
+        )
+    }
+}
+
+/// Steps of a catalog sync, in the order they run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    ConfigFetch,
+    RemoteCheck,
+    MetadataDownload,
+    Extraction,
+    Parse,
+    MediaCopy,
+}
+
+impl SyncPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            SyncPhase::ConfigFetch => "Fetching config",
+            SyncPhase::RemoteCheck => "Checking remote",
+            SyncPhase::MetadataDownload => "Downloading metadata",
+            SyncPhase::Extraction => "Extracting metadata",
+            SyncPhase::Parse => "Parsing game list",
+            SyncPhase::MediaCopy => "Copying thumbnails and notes",
+        }
+    }
+
+    /// Share of the overall progress bar, as start and end percent.
+    pub fn progress_span(self) -> (f64, f64) {
+        match self {
+            SyncPhase::ConfigFetch => (0.0, 5.0),
+            SyncPhase::RemoteCheck => (5.0, 10.0),
+            SyncPhase::MetadataDownload => (10.0, 60.0),
+            SyncPhase::Extraction => (60.0, 75.0),
+            SyncPhase::Parse => (75.0, 90.0),
+            SyncPhase::MediaCopy => (90.0, 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOutcome {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+pub struct PhaseTiming {
+    pub phase: SyncPhase,
+    #[specta(type = f64)]
+    pub duration_ms: u64,
+}
+
+/// How a catalog sync operation ended and how long each phase took.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct CatalogSyncSummary {
+    pub operation_id: String,
+    pub outcome: SyncOutcome,
+    /// Phases that ran, in order; the last one is where a failed sync stopped
+    pub phases: Vec<PhaseTiming>,
+    #[specta(type = f64)]
+    pub total_ms: u64,
+    /// Bytes of `meta.7z` transferred, 0 when it was already up to date
+    #[specta(type = f64)]
+    pub bytes_downloaded: u64,
+    /// The remote `meta.7z` matched the local copy, so download and extraction
+    /// were skipped
+    pub metadata_unchanged: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CatalogSyncSummary {
+    /// One-line summary for the log and the final operation event.
+    pub fn summary(&self) -> String {
+        let phases = self
+            .phases
+            .iter()
+            .map(|timing| {
+                format!(
+                    "{} {:.1}s",
+                    timing.phase.label().to_lowercase(),
+                    timing.duration_ms as f64 / 1000.0
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let outcome = match self.outcome {
+            SyncOutcome::Succeeded => "finished",
+            SyncOutcome::Failed => "failed",
+            SyncOutcome::Cancelled => "cancelled",
+        };
+        format!(
+            "Catalog sync {} after {:.1}s ({})",
+            outcome,
+            self.total_ms as f64 / 1000.0,
+            phases
+        )
+    }
+}
+
+/// How a package's downloads and rank moved over the last 7 and 30 days.
+///
+/// When the history doesn't reach back a full window yet, the window is
+/// measured from the oldest sample instead; `tracked_days` says how far back
+/// that is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct PopularityTrend {
+    pub downloads: f64,
+    pub rank: i32,
+    /// Downloads gained in the last 7 days
+    pub growth_7d: Option<f64>,
+    pub growth_30d: Option<f64>,
+    /// Places climbed in the last 7 days, negative when the package dropped
+    pub rank_change_7d: Option<i32>,
+    pub rank_change_30d: Option<i32>,
+    pub tracked_days: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejections_are_capped_but_counted() {
+        let mut report = CatalogParseReport::default();
+        for line in 0..(MAX_LISTED_REJECTIONS as u32 + 5) {
+            report.record_rejection(line + 2, RowRejection::TooFewFields, "a;b");
+        }
+        assert_eq!(report.rejected.len(), MAX_LISTED_REJECTIONS);
+        assert_eq!(report.rejected_count, MAX_LISTED_REJECTIONS as u32 + 5);
+        assert_eq!(report.rejected[0].message, "expected at least 4 fields");
+        assert!(report.has_problems());
+    }
+
+    #[test]
+    fn schema_falls_back_to_row_majority() {
+        let mut report = CatalogParseReport::default();
+        report.record_schema(CatalogSchema::Legacy);
+        report.record_schema(CatalogSchema::Legacy);
+        report.record_schema(CatalogSchema::Modern);
+        report.settle_schema();
+        assert_eq!(report.schema, Some(CatalogSchema::Legacy));
+        assert!(!report.has_problems());
+    }
+}
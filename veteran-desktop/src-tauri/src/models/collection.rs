@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Version written to exported collection files.
+pub const COLLECTION_FILE_VERSION: u32 = 1;
+
+/// A named, user-ordered list of packages, e.g. "Demo day headsets".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct GameCollection {
+    pub id: String,
+    pub name: String,
+    /// Package names in the order the user arranged them
+    pub packages: Vec<String>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CollectionError {
+    #[error("collection name is empty")]
+    EmptyName,
+    #[error("a collection named '{0}' already exists")]
+    DuplicateName(String),
+    #[error("no collection with id '{0}'")]
+    NotFound(String),
+    #[error("package '{package}' is not in collection '{collection}'")]
+    NotAMember { collection: String, package: String },
+    #[error("unsupported collection file version {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// The file written by export and read by import. Collections are matched by
+/// name on import, ids are local to one installation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionFile {
+    pub version: u32,
+    pub collections: Vec<ExportedCollection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedCollection {
+    pub name: String,
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+/// Counts of an import: collections created, and existing collections that
+/// gained packages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub merged: usize,
+}
+
+fn valid_name(
+    collections: &[GameCollection],
+    name: &str,
+    except_id: Option<&str>,
+) -> Result<String, CollectionError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(CollectionError::EmptyName);
+    }
+    let taken = collections.iter().any(|collection| {
+        Some(collection.id.as_str()) != except_id && collection.name.eq_ignore_ascii_case(name)
+    });
+    if taken {
+        return Err(CollectionError::DuplicateName(name.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn find_mut<'a>(
+    collections: &'a mut [GameCollection],
+    id: &str,
+) -> Result<&'a mut GameCollection, CollectionError> {
+    collections
+        .iter_mut()
+        .find(|collection| collection.id == id)
+        .ok_or_else(|| CollectionError::NotFound(id.to_string()))
+}
+
+fn position(collections: &[GameCollection], id: &str) -> Result<usize, CollectionError> {
+    collections
+        .iter()
+        .position(|collection| collection.id == id)
+        .ok_or_else(|| CollectionError::NotFound(id.to_string()))
+}
+
+/// Append the packages not yet in `collection`, keeping their order. Returns
+/// how many were added.
+fn extend_packages<'a>(
+    collection: &mut GameCollection,
+    packages: impl IntoIterator<Item = &'a String>,
+) -> usize {
+    let before = collection.packages.len();
+    for package in packages {
+        let package = package.trim();
+        if !package.is_empty() && !collection.packages.iter().any(|p| p == package) {
+            collection.packages.push(package.to_string());
+        }
+    }
+    collection.packages.len() - before
+}
+
+/// Add a collection at the end of the list.
+pub fn create(
+    collections: &mut Vec<GameCollection>,
+    name: &str,
+    packages: &[String],
+) -> Result<GameCollection, CollectionError> {
+    let mut collection = GameCollection {
+        id: Uuid::new_v4().to_string(),
+        name: valid_name(collections, name, None)?,
+        packages: Vec::new(),
+    };
+    extend_packages(&mut collection, packages);
+    collections.push(collection.clone());
+    Ok(collection)
+}
+
+pub fn rename(
+    collections: &mut [GameCollection],
+    id: &str,
+    name: &str,
+) -> Result<GameCollection, CollectionError> {
+    let name = valid_name(collections, name, Some(id))?;
+    let collection = find_mut(collections, id)?;
+    collection.name = name;
+    Ok(collection.clone())
+}
+
+/// Remove a collection, returning it.
+pub fn delete(
+    collections: &mut Vec<GameCollection>,
+    id: &str,
+) -> Result<GameCollection, CollectionError> {
+    let index = position(collections, id)?;
+    Ok(collections.remove(index))
+}
+
+pub fn add_packages(
+    collections: &mut [GameCollection],
+    id: &str,
+    packages: &[String],
+) -> Result<GameCollection, CollectionError> {
+    let collection = find_mut(collections, id)?;
+    extend_packages(collection, packages);
+    Ok(collection.clone())
+}
+
+pub fn remove_packages(
+    collections: &mut [GameCollection],
+    id: &str,
+    packages: &[String],
+) -> Result<GameCollection, CollectionError> {
+    let collection = find_mut(collections, id)?;
+    collection
+        .packages
+        .retain(|package| !packages.iter().any(|p| p.trim() == package));
+    Ok(collection.clone())
+}
+
+/// Move a collection to `new_position` in the list, clamped to its end.
+pub fn move_collection(
+    collections: &mut Vec<GameCollection>,
+    id: &str,
+    new_position: usize,
+) -> Result<(), CollectionError> {
+    let index = position(collections, id)?;
+    let collection = collections.remove(index);
+    let target = new_position.min(collections.len());
+    collections.insert(target, collection);
+    Ok(())
+}
+
+/// Move a package to `new_position` within its collection, clamped to the end.
+pub fn move_package(
+    collections: &mut [GameCollection],
+    id: &str,
+    package: &str,
+    new_position: usize,
+) -> Result<GameCollection, CollectionError> {
+    let collection = find_mut(collections, id)?;
+    let index = collection
+        .packages
+        .iter()
+        .position(|p| p == package)
+        .ok_or_else(|| CollectionError::NotAMember {
+            collection: collection.name.clone(),
+            package: package.to_string(),
+        })?;
+    let package = collection.packages.remove(index);
+    let target = new_position.min(collection.packages.len());
+    collection.packages.insert(target, package);
+    Ok(collection.clone())
+}
+
+/// The collections with the given ids in list order, or all of them.
+pub fn export(
+    collections: &[GameCollection],
+    ids: Option<&[String]>,
+) -> Result<CollectionFile, CollectionError> {
+    if let Some(ids) = ids {
+        for id in ids {
+            position(collections, id)?;
+        }
+    }
+    Ok(CollectionFile {
+        version: COLLECTION_FILE_VERSION,
+        collections: collections
+            .iter()
+            .filter(|collection| ids.is_none_or(|ids| ids.contains(&collection.id)))
+            .map(|collection| ExportedCollection {
+                name: collection.name.clone(),
+                packages: collection.packages.clone(),
+            })
+            .collect(),
+    })
+}
+
+/// Merge an exported file: a collection whose name matches an existing one
+/// (ignoring case) adds its missing packages to it, others are created at
+/// the end of the list. Nameless collections are skipped.
+pub fn import(
+    collections: &mut Vec<GameCollection>,
+    file: &CollectionFile,
+) -> Result<ImportSummary, CollectionError> {
+    if file.version > COLLECTION_FILE_VERSION {
+        return Err(CollectionError::UnsupportedVersion(file.version));
+    }
+    let mut summary = ImportSummary::default();
+    for imported in &file.collections {
+        let name = imported.name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        match collections
+            .iter_mut()
+            .find(|collection| collection.name.eq_ignore_ascii_case(name))
+        {
+            Some(existing) => {
+                if extend_packages(existing, &imported.packages) > 0 {
+                    summary.merged += 1;
+                }
+            }
+            None => {
+                create(collections, name, &imported.packages)?;
+                summary.created += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn names_are_trimmed_and_unique() {
+        let mut collections = Vec::new();
+        let kids = create(&mut collections, " Kids ", &packages(&["a", "b", "a", " "])).unwrap();
+        assert_eq!(kids.name, "Kids");
+        assert_eq!(kids.packages, packages(&["a", "b"]));
+
+        assert_eq!(
+            create(&mut collections, "kids", &[]),
+            Err(CollectionError::DuplicateName("kids".to_string()))
+        );
+        assert_eq!(
+            create(&mut collections, "  ", &[]),
+            Err(CollectionError::EmptyName)
+        );
+        let fitness = create(&mut collections, "Fitness", &[]).unwrap();
+        assert!(rename(&mut collections, &fitness.id, "KIDS").is_err());
+        // Changing only the case of its own name is fine
+        assert_eq!(
+            rename(&mut collections, &kids.id, "KIDS").unwrap().name,
+            "KIDS"
+        );
+        assert_eq!(
+            delete(&mut collections, "missing"),
+            Err(CollectionError::NotFound("missing".to_string()))
+        );
+        delete(&mut collections, &kids.id).unwrap();
+        assert_eq!(collections, vec![fitness]);
+    }
+
+    #[test]
+    fn members_and_collections_are_reordered() {
+        let mut collections = Vec::new();
+        let demo = create(&mut collections, "Demo day headsets", &[]).unwrap();
+        let kids = create(&mut collections, "Kids", &[]).unwrap();
+        let fitness = create(&mut collections, "Fitness", &[]).unwrap();
+
+        add_packages(&mut collections, &demo.id, &packages(&["a", "b", "c"])).unwrap();
+        add_packages(&mut collections, &demo.id, &packages(&["b", "d"])).unwrap();
+        let demo = remove_packages(&mut collections, &demo.id, &packages(&["c"])).unwrap();
+        assert_eq!(demo.packages, packages(&["a", "b", "d"]));
+
+        let demo = move_package(&mut collections, &demo.id, "d", 0).unwrap();
+        assert_eq!(demo.packages, packages(&["d", "a", "b"]));
+        let demo = move_package(&mut collections, &demo.id, "d", 99).unwrap();
+        assert_eq!(demo.packages, packages(&["a", "b", "d"]));
+        assert!(matches!(
+            move_package(&mut collections, &demo.id, "zzz", 0),
+            Err(CollectionError::NotAMember { .. })
+        ));
+
+        move_collection(&mut collections, &fitness.id, 0).unwrap();
+        let order: Vec<_> = collections.iter().map(|c| c.id.clone()).collect();
+        assert_eq!(order, vec![fitness.id, demo.id, kids.id]);
+    }
+
+    #[test]
+    fn import_merges_exported_collections_by_name() {
+        let mut source = Vec::new();
+        let kids = create(&mut source, "Kids", &packages(&["a", "b"])).unwrap();
+        create(&mut source, "Fitness", &packages(&["f"])).unwrap();
+
+        let file = export(&source, None).unwrap();
+        let json = serde_json::to_string(&file).unwrap();
+        let file: CollectionFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(file.collections.len(), 2);
+        assert_eq!(
+            export(&source, Some(std::slice::from_ref(&kids.id)))
+                .unwrap()
+                .collections[0]
+                .name,
+            "Kids"
+        );
+        assert!(export(&source, Some(&["missing".to_string()])).is_err());
+
+        let mut target = Vec::new();
+        create(&mut target, "kids", &packages(&["b", "c"])).unwrap();
+        let summary = import(&mut target, &file).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                created: 1,
+                merged: 1
+            }
+        );
+        assert_eq!(target[0].packages, packages(&["b", "c", "a"]));
+        assert_eq!(target[1].name, "Fitness");
+
+        // Importing again changes nothing
+        assert_eq!(
+            import(&mut target, &file).unwrap(),
+            ImportSummary::default()
+        );
+
+        let newer = CollectionFile {
+            version: COLLECTION_FILE_VERSION + 1,
+            collections: Vec::new(),
+        };
+        assert_eq!(
+            import(&mut target, &newer),
+            Err(CollectionError::UnsupportedVersion(
+                COLLECTION_FILE_VERSION + 1
+            ))
+        );
+    }
+}
@@ -2,11 +2,117 @@ use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
+use zeroize::Zeroizing;
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, specta::Type)]
 pub struct PublicConfig {
     pub base_uri: String,
+    /// Wiped from memory when dropped, including in clones
+    #[specta(type = String)]
+    pub password: Zeroizing<String>,
+}
+
+/// Name of the source configured from the public `vrp-public.json`; also its rclone remote.
+pub const PRIMARY_SOURCE: &str = "vrp";
+pub const DEFAULT_LIST_FILE: &str = "VRP-GameList.txt";
+/// Source of releases imported from disk, installed without downloading.
+pub const LOCAL_SOURCE: &str = "local";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogSourceKind {
+    /// An HTTP mirror laid out like the public one: `meta.7z` plus one folder per release hash
+    #[default]
+    Remote,
+    /// A local folder holding the list file and one folder per release name
+    Local,
+}
+
+/// An additional catalog source merged with the public one.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(default)]
+pub struct CatalogSource {
+    pub name: String,
+    pub kind: CatalogSourceKind,
+    /// Base URI for remote sources, folder path for local ones
+    pub base_uri: String,
+    /// Password of the source's `meta.7z`. Only set by settings updates: it is
+    /// moved into the source secrets before the settings are saved.
     pub password: String,
+    /// Key of the password in the source secrets, empty without one
+    pub password_ref: String,
+    /// rclone remote name; defaults to `name`
+    pub remote_name: String,
+    /// Game list inside the metadata; defaults to `VRP-GameList.txt`
+    pub list_file: String,
+    /// When several sources list the same release, the highest priority wins.
+    /// The public source has priority 0.
+    pub priority: i32,
+    pub enabled: bool,
+}
+
+impl CatalogSource {
+    pub fn remote_name(&self) -> &str {
+        if self.remote_name.trim().is_empty() {
+            &self.name
+        } else {
+            self.remote_name.trim()
+        }
+    }
+
+    pub fn list_file(&self) -> &str {
+        if self.list_file.trim().is_empty() {
+            DEFAULT_LIST_FILE
+        } else {
+            self.list_file.trim()
+        }
+    }
+
+    /// Why this source can't be used, if it can't.
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("source name is empty".to_string());
+        }
+        if name == PRIMARY_SOURCE || self.remote_name() == PRIMARY_SOURCE {
+            return Err(format!(
+                "'{PRIMARY_SOURCE}' is reserved for the public source"
+            ));
+        }
+        if name == LOCAL_SOURCE {
+            return Err(format!("'{LOCAL_SOURCE}' is reserved for imported entries"));
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "source name '{name}' may only contain letters, digits, '-' and '_'"
+            ));
+        }
+        if self.base_uri.trim().is_empty() {
+            return Err(format!("source '{name}' has no base URI"));
+        }
+        Ok(())
+    }
+}
+
+/// Enabled, valid sources with unique names, in configured order.
+pub fn usable_sources(sources: &[CatalogSource]) -> Vec<CatalogSource> {
+    let mut usable: Vec<CatalogSource> = Vec::new();
+    for source in sources.iter().filter(|source| source.enabled) {
+        if let Err(reason) = source.validate() {
+            crate::logger::log(&format!("[CONFIG] Ignoring catalog source: {}", reason));
+        } else if usable.iter().any(|existing| existing.name == source.name) {
+            crate::logger::log(&format!(
+                "[CONFIG] Ignoring duplicate catalog source '{}'",
+                source.name
+            ));
+        } else {
+            usable.push(source.clone());
+        }
+    }
+    usable
 }
 
 #[derive(Error, Debug)]
@@ -17,14 +123,22 @@ pub enum ConfigError {
     Base64Error(#[from] base64::DecodeError),
     #[error("utf8 error")]
     Utf8Error(#[from] std::string::FromUtf8Error),
+    #[error("config is not a JSON object")]
+    NotAnObject,
+    #[error("missing or empty field '{0}'")]
+    MissingField(&'static str),
+    #[error("field '{0}' is not a string")]
+    WrongType(&'static str),
+    #[error("baseUri '{0}' is not an http(s) URL")]
+    InvalidBaseUri(String),
 }
 
 impl PublicConfig {
     pub fn from_json(data: &Value) -> Result<Self, ConfigError> {
         let raw_password = data.get("password").and_then(|v| v.as_str()).unwrap_or("");
 
-        let decoded_bytes = general_purpose::STANDARD.decode(raw_password)?;
-        let decoded_password = String::from_utf8(decoded_bytes)?;
+        let decoded_bytes = Zeroizing::new(general_purpose::STANDARD.decode(raw_password)?);
+        let decoded_password = Zeroizing::new(String::from_utf8(decoded_bytes.to_vec())?);
 
         let base_uri = data
             .get("baseUri")
@@ -37,6 +151,80 @@ impl PublicConfig {
             password: decoded_password,
         })
     }
+
+    /// Parse a fetched `vrp-public.json`, rejecting anything that isn't a usable
+    /// config so a broken mirror response never replaces a good cached one.
+    pub fn from_json_validated(data: &Value) -> Result<Self, ConfigError> {
+        if !data.is_object() {
+            return Err(ConfigError::NotAnObject);
+        }
+        for field in ["baseUri", "password"] {
+            match data.get(field) {
+                Some(Value::String(_)) => {}
+                Some(_) => return Err(ConfigError::WrongType(field)),
+                None => return Err(ConfigError::MissingField(field)),
+            }
+        }
+        let config = Self::from_json(data)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.base_uri.trim().is_empty() {
+            return Err(ConfigError::MissingField("baseUri"));
+        }
+        let url = url::Url::parse(self.base_uri.trim())
+            .map_err(|_| ConfigError::InvalidBaseUri(self.base_uri.clone()))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(ConfigError::InvalidBaseUri(self.base_uri.clone()));
+        }
+        if self.password.is_empty() {
+            return Err(ConfigError::MissingField("password"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSourceKind {
+    Url,
+    /// A local `vrp-public.json`, e.g. for air-gapped use
+    File,
+}
+
+/// Where a config source was configured; the environment overrides settings,
+/// and either replaces the built-in URLs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigOrigin {
+    Default,
+    Settings,
+    Environment,
+}
+
+/// How fetching the public config from one source has gone this session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct ConfigSourceHealth {
+    /// URL or file path
+    pub location: String,
+    pub kind: ConfigSourceKind,
+    pub origin: ConfigOrigin,
+    /// Unix seconds of the last fetch that returned a valid config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[specta(type = Option<f64>)]
+    pub last_success_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[specta(type = Option<f64>)]
+    pub last_error_at: Option<i64>,
+    /// Duration of the last attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[specta(type = Option<f64>)]
+    pub latency_ms: Option<u64>,
+    pub consecutive_failures: u32,
 }
 
 #[cfg(test)]
@@ -55,10 +243,48 @@ mod tests {
         });
 
         let config = PublicConfig::from_json(&json).unwrap();
-        assert_eq!(config.password, password);
+        assert_eq!(config.password.as_str(), password);
         assert_eq!(config.base_uri, "http://example.com");
     }
 
+    #[test]
+    fn test_usable_sources() {
+        let mirror = CatalogSource {
+            name: "mirror".to_string(),
+            base_uri: "https://mirror.example.com/".to_string(),
+            enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(mirror.remote_name(), "mirror");
+        assert_eq!(mirror.list_file(), DEFAULT_LIST_FILE);
+
+        let sources = vec![
+            mirror.clone(),
+            CatalogSource {
+                name: "vrp".to_string(),
+                ..mirror.clone()
+            },
+            CatalogSource {
+                name: "local".to_string(),
+                ..mirror.clone()
+            },
+            CatalogSource {
+                name: "bad name".to_string(),
+                ..mirror.clone()
+            },
+            CatalogSource {
+                enabled: false,
+                name: "off".to_string(),
+                ..mirror.clone()
+            },
+            CatalogSource {
+                base_uri: "https://other.example.com/".to_string(),
+                ..mirror.clone()
+            },
+        ];
+        assert_eq!(usable_sources(&sources), vec![mirror]);
+    }
+
     #[test]
     fn test_invalid_base64() {
         let json = json!({
@@ -69,6 +295,28 @@ mod tests {
         assert!(PublicConfig::from_json(&json).is_err());
     }
 
+    #[test]
+    fn test_validated_config_rejects_broken_responses() {
+        let encoded = general_purpose::STANDARD.encode("secret_password");
+        let valid = json!({ "baseUri": "https://mirror.example.com/", "password": encoded });
+        assert!(PublicConfig::from_json_validated(&valid).is_ok());
+
+        let broken = [
+            json!([]),
+            json!({ "password": encoded }),
+            json!({ "baseUri": "https://mirror.example.com/", "password": 5 }),
+            json!({ "baseUri": "mirror.example.com", "password": encoded }),
+            json!({ "baseUri": "ftp://mirror.example.com/", "password": encoded }),
+            json!({ "baseUri": "https://mirror.example.com/", "password": "" }),
+        ];
+        for data in broken {
+            assert!(
+                PublicConfig::from_json_validated(&data).is_err(),
+                "accepted {data}"
+            );
+        }
+    }
+
     #[test]
     fn test_missing_fields_defaults() {
         // Base64 of empty string is empty string
@@ -76,7 +324,7 @@ mod tests {
         // Depending on implementation, missing password might fail decode if not handled.
         // My implementation reads empty string if missing, which decodes to empty string.
         let config = PublicConfig::from_json(&json).unwrap();
-        assert_eq!(config.password, "");
+        assert_eq!(config.password.as_str(), "");
         assert_eq!(config.base_uri, "");
     }
 }
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A download queue entry saved in the settings, so the queue survives a
+/// restart. The release is looked up in the catalog again on restore; the
+/// names and source are enough to download it if the catalog lacks it.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(default)]
+pub struct QueuedDownload {
+    pub package_name: String,
+    /// Empty for entries saved by older versions, which kept only the package
+    pub release_name: String,
+    pub game_name: String,
+    /// Catalog source the release came from
+    pub source: String,
+    /// Queue status, e.g. "queued" or "failed"
+    pub status: String,
+    pub error: String,
+    pub retry_count: u32,
+    pub priority: i32,
+    pub progress_percent: f64,
+    #[specta(type = f64)]
+    pub bytes_transferred: i64,
+    #[specta(type = f64)]
+    pub total_bytes: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedEntry {
+    Download(QueuedDownload),
+    Package(String),
+}
+
+/// Read the saved queue, including the bare package names older versions
+/// wrote.
+pub fn deserialize_queue<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<QueuedDownload>, D::Error> {
+    Ok(Vec::<SavedEntry>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            SavedEntry::Download(download) => download,
+            SavedEntry::Package(package_name) => QueuedDownload {
+                package_name,
+                status: "queued".to_string(),
+                ..QueuedDownload::default()
+            },
+        })
+        .collect())
+}
@@ -1,3 +1,6 @@
+use crate::models::config::LOCAL_SOURCE;
+use crate::models::size::{format_megabytes, parse_catalog_size};
+use chrono::NaiveDate;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
@@ -7,12 +10,20 @@ pub struct Game {
     pub game_name: String,
     pub release_name: String,
     pub package_name: String,
-    pub version_code: String,
+    #[serde(with = "wire::version_code")]
+    #[specta(type = String)]
+    pub version_code: i64,
     pub release_apk_path: String,
     pub version_name: String,
-    pub downloads: String,
-    pub size: String,
-    pub last_updated: String,
+    #[serde(with = "wire::downloads")]
+    #[specta(type = String)]
+    pub downloads: f64,
+    #[serde(rename = "size", with = "wire::size")]
+    #[specta(type = String)]
+    pub size_bytes: u64,
+    #[serde(with = "wire::last_updated")]
+    #[specta(type = String)]
+    pub last_updated: Option<NaiveDate>,
     pub thumbnail_path: String,
     pub thumbnail_exists: bool,
     pub note_path: String,
@@ -20,6 +31,13 @@ pub struct Game {
     pub note_exists: bool,
     pub popularity_rank: i32,
     pub is_new: bool,
+    /// Catalog source the release came from, see `models::config::CatalogSource`
+    #[serde(default)]
+    pub source: String,
+    /// From release name markers, the release note and the user tag file,
+    /// see `models::tags`
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 static DATE_PREFIX_PATTERN: OnceLock<Regex> = OnceLock::new();
@@ -27,24 +45,82 @@ static NUMERIC_PATTERN: OnceLock<Regex> = OnceLock::new();
 static SIZE_WITH_UNIT_PATTERN: OnceLock<Regex> = OnceLock::new();
 static VERSION_FROM_RELEASE_PATTERN: OnceLock<Regex> = OnceLock::new();
 
+/// Column layout of a `VRP-GameList.txt`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogSchema {
+    /// `name;release;package;version;last_updated;size;downloads[;...]`
+    Modern,
+    /// `name;release;package;version;apk_path;version_name;downloads;size;last_updated`
+    Legacy,
+}
+
+impl CatalogSchema {
+    /// Recognise the schema from the header line's column names, if possible.
+    pub fn from_header(columns: &[&str]) -> Option<Self> {
+        let column = |index: usize| columns.get(index).map(|c| c.trim().to_lowercase());
+        match column(4) {
+            Some(name) if name.contains("apk") => Some(Self::Legacy),
+            Some(name) if name.contains("updated") => Some(Self::Modern),
+            _ => None,
+        }
+    }
+
+    /// Guess the schema of a single row from the shape of its values.
+    pub fn detect(fields: &[&str]) -> Self {
+        if Game::looks_like_modern_catalog_schema(fields) {
+            Self::Modern
+        } else {
+            Self::Legacy
+        }
+    }
+}
+
+/// Why a catalog row could not be turned into a [`Game`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RowRejection {
+    TooFewFields,
+    MissingPackageName,
+    InvalidVersionCode,
+}
+
+impl std::fmt::Display for RowRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooFewFields => write!(f, "expected at least 4 fields"),
+            Self::MissingPackageName => write!(f, "package name is empty"),
+            Self::InvalidVersionCode => write!(f, "version code is not an integer"),
+        }
+    }
+}
+
 impl Game {
     pub fn from_csv_row(fields: &[&str]) -> Option<Self> {
+        Self::parse_row(fields, CatalogSchema::detect(fields)).ok()
+    }
+
+    /// Parse a row using a known `schema` rather than guessing it from the values.
+    pub fn parse_row(fields: &[&str], schema: CatalogSchema) -> Result<Self, RowRejection> {
         if fields.len() < 4 {
-            return None;
+            return Err(RowRejection::TooFewFields);
         }
 
         let game_name = fields.first().unwrap_or(&"").trim().to_string();
         let release_name = fields.get(1).unwrap_or(&"").trim().to_string();
         let package_name = fields.get(2).unwrap_or(&"").trim().to_string();
-        let version_code = fields.get(3).unwrap_or(&"").trim().to_string();
+        if package_name.is_empty() {
+            return Err(RowRejection::MissingPackageName);
+        }
+        let version_code = parse_version_code(fields[3]).ok_or(RowRejection::InvalidVersionCode)?;
 
-        if Self::looks_like_modern_catalog_schema(fields) {
-            let last_updated = fields.get(4).unwrap_or(&"").trim().to_string();
-            let size = Self::normalize_size_field(fields.get(5).unwrap_or(&"").trim());
-            let downloads = fields.get(6).unwrap_or(&"").trim().to_string();
+        if schema == CatalogSchema::Modern {
+            let last_updated = parse_catalog_date(fields.get(4).unwrap_or(&""));
+            let size_bytes = parse_catalog_size(fields.get(5).unwrap_or(&"")).unwrap_or(0);
+            let downloads = parse_downloads(fields.get(6).unwrap_or(&""));
             let version_name = Self::extract_version_name_from_release(&release_name);
 
-            Some(Game {
+            Ok(Game {
                 game_name,
                 release_name,
                 package_name,
@@ -52,23 +128,23 @@ impl Game {
                 release_apk_path: String::new(),
                 version_name,
                 downloads,
-                size,
+                size_bytes,
                 last_updated,
                 ..Default::default()
             })
         } else {
             // Legacy schema
             // 4: apk_path, 5: version_name, 6: downloads, 7: size, 8: last_updated
-            Some(Game {
+            Ok(Game {
                 game_name,
                 release_name,
                 package_name,
                 version_code,
                 release_apk_path: fields.get(4).unwrap_or(&"").trim().to_string(),
                 version_name: fields.get(5).unwrap_or(&"").trim().to_string(),
-                downloads: fields.get(6).unwrap_or(&"").trim().to_string(),
-                size: fields.get(7).unwrap_or(&"").trim().to_string(),
-                last_updated: fields.get(8).unwrap_or(&"").trim().to_string(),
+                downloads: parse_downloads(fields.get(6).unwrap_or(&"")),
+                size_bytes: parse_catalog_size(fields.get(7).unwrap_or(&"")).unwrap_or(0),
+                last_updated: parse_catalog_date(fields.get(8).unwrap_or(&"")),
                 ..Default::default()
             })
         }
@@ -106,33 +182,142 @@ impl Game {
         String::new()
     }
 
-    fn normalize_size_field(raw_size: &str) -> String {
-        let size_value = raw_size.trim();
-        if size_value.is_empty() {
-            return String::new();
-        }
+    /// Whether the release was imported from disk rather than listed by a source.
+    pub fn is_local(&self) -> bool {
+        self.source == LOCAL_SOURCE
+    }
 
-        let size_unit = SIZE_WITH_UNIT_PATTERN.get_or_init(|| {
-            Regex::new(r"(?i)^\d+(?:\.\d+)?\s*(?:KB|MB|GB|TB|KIB|MIB|GIB|TIB)$").unwrap()
-        });
-        if size_unit.is_match(size_value) {
-            return size_value.to_string();
+    /// Size as the catalog lists it, e.g. `1024 MB`; empty when unknown.
+    pub fn size_label(&self) -> String {
+        format_megabytes(self.size_bytes)
+    }
+
+    pub fn downloads_label(&self) -> String {
+        format_number(self.downloads)
+    }
+
+    /// `YYYY-MM-DD`, or empty when the catalog row had no parsable date.
+    pub fn last_updated_label(&self) -> String {
+        self.last_updated
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Parse a version code, `None` unless it is an integer.
+pub fn parse_version_code(text: &str) -> Option<i64> {
+    text.trim().parse::<i64>().ok()
+}
+
+/// Parse the `YYYY-MM-DD` prefix of a catalog date like `2024-06-01 10:22 UTC`.
+pub fn parse_catalog_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()
+}
+
+pub fn parse_downloads(text: &str) -> f64 {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .unwrap_or(0.0)
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// `Game` keeps the all-string JSON shape it always had, so persisted data and
+/// the generated TypeScript type stay compatible with the typed fields.
+mod wire {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Int(i64),
+        Float(f64),
+        Text(String),
+    }
+
+    pub mod version_code {
+        use super::Raw;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(value)
         }
 
-        let numeric = NUMERIC_PATTERN.get_or_init(|| Regex::new(r"^\d+(?:\.\d+)?$").unwrap());
-        if numeric.is_match(size_value) {
-            let normalized = if size_value.contains('.') {
-                size_value
-                    .trim_end_matches('0')
-                    .trim_end_matches('.')
-                    .to_string()
-            } else {
-                size_value.to_string()
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+            let version_code = match Raw::deserialize(deserializer)? {
+                Raw::Int(value) => Some(value),
+                Raw::Float(value) => (value.fract() == 0.0).then_some(value as i64),
+                Raw::Text(text) => crate::models::game::parse_version_code(&text),
             };
-            return format!("{} MB", normalized);
+            version_code.ok_or_else(|| serde::de::Error::custom("version code is not an integer"))
+        }
+    }
+
+    pub mod downloads {
+        use super::Raw;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&crate::models::game::format_number(*value))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+            Ok(match Raw::deserialize(deserializer)? {
+                Raw::Int(value) => value as f64,
+                Raw::Float(value) => value,
+                Raw::Text(text) => crate::models::game::parse_downloads(&text),
+            })
+        }
+    }
+
+    pub mod size {
+        use super::Raw;
+        use crate::models::size::{format_megabytes, parse_catalog_size};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&format_megabytes(*value))
+        }
+
+        /// Numbers are byte counts; strings are catalog sizes like `1024 MB`.
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+            Ok(match Raw::deserialize(deserializer)? {
+                Raw::Int(value) => value.max(0) as u64,
+                Raw::Float(value) => value.max(0.0) as u64,
+                Raw::Text(text) => parse_catalog_size(&text).unwrap_or(0),
+            })
+        }
+    }
+
+    pub mod last_updated {
+        use chrono::NaiveDate;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            value: &Option<NaiveDate>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(date) => serializer.collect_str(&date.format("%Y-%m-%d")),
+                None => serializer.serialize_str(""),
+            }
         }
 
-        size_value.to_string()
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<NaiveDate>, D::Error> {
+            let text = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+            Ok(crate::models::game::parse_catalog_date(&text))
+        }
     }
 }
 
@@ -149,10 +334,11 @@ mod tests {
         assert_eq!(game.game_name, "My Game");
         assert_eq!(game.release_name, "My Release v1+123");
         assert_eq!(game.package_name, "com.game");
-        assert_eq!(game.version_code, "100");
-        assert_eq!(game.last_updated, "2023-01-01");
-        assert_eq!(game.size, "1024 MB"); // Normalized
-        assert_eq!(game.downloads, "1000");
+        assert_eq!(game.version_code, 100);
+        assert_eq!(game.last_updated, NaiveDate::from_ymd_opt(2023, 1, 1));
+        assert_eq!(game.size_bytes, 1024 * 1024 * 1024);
+        assert_eq!(game.size_label(), "1024 MB"); // Normalized
+        assert_eq!(game.downloads, 1000.0);
         assert_eq!(game.version_name, "123"); // Extracted from release name
     }
 
@@ -165,18 +351,88 @@ mod tests {
         assert_eq!(game.game_name, "Old Game");
         assert_eq!(game.release_apk_path, "/path/to/apk");
         assert_eq!(game.version_name, "1.0");
-        assert_eq!(game.downloads, "500");
-        assert_eq!(game.size, "500 MB");
-        assert_eq!(game.last_updated, "2022-01-01");
+        assert_eq!(game.downloads, 500.0);
+        assert_eq!(game.size_label(), "500 MB");
+        assert_eq!(game.last_updated_label(), "2022-01-01");
     }
 
     #[test]
     fn test_normalize_size() {
-        assert_eq!(Game::normalize_size_field("1024"), "1024 MB");
-        assert_eq!(Game::normalize_size_field("1.5"), "1.5 MB");
-        assert_eq!(Game::normalize_size_field("1.0"), "1 MB");
-        assert_eq!(Game::normalize_size_field("2 GB"), "2 GB");
-        assert_eq!(Game::normalize_size_field(""), "");
+        let size_label = |raw: &str| {
+            let row = format!("G;G v1+1;com.g;1;2023-01-01;{raw};0");
+            let fields: Vec<&str> = row.split(';').collect();
+            Game::from_csv_row(&fields).unwrap().size_label()
+        };
+        assert_eq!(size_label("1024"), "1024 MB");
+        assert_eq!(size_label("1.5"), "1.5 MB");
+        assert_eq!(size_label("1.0"), "1 MB");
+        assert_eq!(size_label("2 GB"), "2048 MB");
+    }
+
+    #[test]
+    fn test_typed_fields_and_wire_format() {
+        let row = "Game;Game v7+1.2;com.game;7;2024-06-01 10:22 UTC;1.5 GB;12.5";
+        let fields: Vec<&str> = row.split(';').collect();
+        let game = Game::from_csv_row(&fields).unwrap();
+        assert_eq!(game.last_updated, NaiveDate::from_ymd_opt(2024, 6, 1));
+
+        // Serialized like the string-typed model so existing consumers keep working
+        let json = serde_json::to_value(&game).unwrap();
+        assert_eq!(json["version_code"], "7");
+        assert_eq!(json["size"], "1536 MB");
+        assert_eq!(json["downloads"], "12.5");
+        assert_eq!(json["last_updated"], "2024-06-01");
+        assert_eq!(serde_json::from_value::<Game>(json).unwrap(), game);
+
+        let bad = "Game;Game;com.game;3;not a date;500 MB;many;x;y";
+        let fields: Vec<&str> = bad.split(';').collect();
+        let game = Game::from_csv_row(&fields).unwrap();
+        assert_eq!(game.version_code, 3);
+        assert_eq!(game.last_updated, None);
+        assert_eq!(game.downloads, 0.0);
+
+        // A version code that isn't an integer is an error, not a fake 0
+        let bad = "Game;Game;com.game;abc;2024-06-01;500 MB;1";
+        let fields: Vec<&str> = bad.split(';').collect();
+        assert!(Game::from_csv_row(&fields).is_none());
+        let mut json = serde_json::to_value(&game).unwrap();
+        json["version_code"] = "1.2".into();
+        assert!(serde_json::from_value::<Game>(json).is_err());
+    }
+
+    #[test]
+    fn test_schema_from_header_and_rejections() {
+        let modern =
+            "Game Name;Release Name;Package Name;Version Code;Last Updated;Size (MB);Downloads";
+        let legacy =
+            "Game Name;Release Name;Package Name;Version Code;Release APK Path;Version Name";
+        let header = |line: &str| CatalogSchema::from_header(&line.split(';').collect::<Vec<_>>());
+        assert_eq!(header(modern), Some(CatalogSchema::Modern));
+        assert_eq!(header(legacy), Some(CatalogSchema::Legacy));
+        assert_eq!(header("Header"), None);
+
+        // A modern row with a missing date still parses as modern when told so
+        let fields: Vec<&str> = "G;G v1+2;com.g;1;;500;10".split(';').collect();
+        assert_eq!(CatalogSchema::detect(&fields), CatalogSchema::Legacy);
+        let game = Game::parse_row(&fields, CatalogSchema::Modern).unwrap();
+        assert_eq!(game.size_label(), "500 MB");
+        assert_eq!(game.downloads, 10.0);
+
+        assert_eq!(
+            Game::parse_row(&["a", "b", "c"], CatalogSchema::Modern),
+            Err(RowRejection::TooFewFields)
+        );
+        assert_eq!(
+            Game::parse_row(&["a", "b", " ", "1"], CatalogSchema::Legacy),
+            Err(RowRejection::MissingPackageName)
+        );
+        assert_eq!(
+            Game::parse_row(&["a", "b", "c", "1.2"], CatalogSchema::Legacy),
+            Err(RowRejection::InvalidVersionCode)
+        );
+        assert_eq!(parse_version_code(" 12"), Some(12));
+        assert_eq!(parse_version_code("1.2"), None);
+        assert_eq!(parse_version_code("beta"), None);
     }
 
     #[test]
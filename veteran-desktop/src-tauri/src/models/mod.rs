@@ -1,5 +1,13 @@
+pub mod catalog_report;
+pub mod collection;
 pub mod config;
 pub mod device;
+pub mod download_queue;
 pub mod game;
+pub mod release_notes;
 pub mod responses;
 pub mod settings;
+pub mod size;
+pub mod tags;
+pub mod version;
+pub mod watchlist;
@@ -0,0 +1,312 @@
+use crate::services::search::tokenize;
+use serde::{Deserialize, Serialize};
+
+/// Longest line still read as a heading when it ends in a colon or is all caps.
+const MAX_HEADING_LEN: usize = 60;
+
+/// Section titles whose bullets are collected into `ReleaseNotes::changelog`.
+const CHANGELOG_TITLES: [&str; 7] = [
+    "change",
+    "what's new",
+    "whats new",
+    "patch notes",
+    "release notes",
+    "update",
+    "fixes",
+];
+
+const MOD_WORDS: [&str; 4] = ["mod", "mods", "modded", "patched"];
+const CAUTION_PREFIXES: [&str; 4] = ["warning", "important", "caution", "attention"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteWarningKind {
+    /// The release ships an `install.txt` with extra install steps
+    InstallScript,
+    /// Modded or patched build
+    Mod,
+    /// Something else the release needs, e.g. "Requires OBB on first launch"
+    Requirement,
+    /// A line flagged with "warning", "important", "caution" or "attention"
+    Caution,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+pub struct NoteWarning {
+    pub kind: NoteWarningKind,
+    /// The note line the warning was found on
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+pub struct NoteSection {
+    /// Empty for text before the first heading
+    pub title: String,
+    pub lines: Vec<String>,
+    pub bullets: Vec<String>,
+}
+
+/// A release note from the metadata archive, split into its parts.
+///
+/// Notes are free-form text written by different uploaders, so this is a best
+/// effort: headings are `# Title`, `[Title]`, `== Title ==`, a short line
+/// ending in a colon or a short all-caps line; bullets start with `-`, `*`,
+/// `•`, `+` or `1.`/`1)`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+pub struct ReleaseNotes {
+    /// Plain lines before the first heading, joined with spaces
+    pub summary: String,
+    pub sections: Vec<NoteSection>,
+    /// Bullets of sections titled like a changelog ("Changes", "What's new", ...)
+    pub changelog: Vec<String>,
+    pub warnings: Vec<NoteWarning>,
+    pub links: Vec<String>,
+}
+
+impl ReleaseNotes {
+    pub fn parse(text: &str) -> Self {
+        let mut notes = Self::default();
+
+        for raw in text.lines() {
+            let line = raw.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(title) = heading_title(line) {
+                notes.sections.push(NoteSection {
+                    title: title.to_string(),
+                    ..NoteSection::default()
+                });
+                continue;
+            }
+            if notes.sections.is_empty() {
+                notes.sections.push(NoteSection::default());
+            }
+            let section = notes.sections.last_mut().expect("section was just pushed");
+            let content = match bullet_text(line) {
+                Some(bullet) => {
+                    section.bullets.push(bullet.to_string());
+                    bullet
+                }
+                None => {
+                    section.lines.push(line.to_string());
+                    line
+                }
+            };
+            for kind in warning_kinds(content) {
+                let warning = NoteWarning {
+                    kind,
+                    text: content.to_string(),
+                };
+                if !notes.warnings.contains(&warning) {
+                    notes.warnings.push(warning);
+                }
+            }
+        }
+
+        notes.summary = notes
+            .sections
+            .first()
+            .filter(|section| section.title.is_empty())
+            .map(|section| section.lines.join(" "))
+            .unwrap_or_default();
+        notes.changelog = notes
+            .sections
+            .iter()
+            .filter(|section| {
+                let title = section.title.to_lowercase();
+                CHANGELOG_TITLES.iter().any(|word| title.contains(word))
+            })
+            .flat_map(|section| section.bullets.iter().cloned())
+            .collect();
+        notes.links = extract_links(text);
+        notes
+    }
+
+    /// Up to `max_chars` characters of the summary, or of the first section
+    /// with any text when the note starts with a heading.
+    pub fn excerpt(&self, max_chars: usize) -> String {
+        let text = if self.summary.is_empty() {
+            self.sections
+                .iter()
+                .map(|section| {
+                    section
+                        .lines
+                        .iter()
+                        .chain(&section.bullets)
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .find(|text| !text.is_empty())
+                .unwrap_or_default()
+        } else {
+            self.summary.clone()
+        };
+        text.chars().take(max_chars).collect()
+    }
+
+    pub fn has_warning(&self, kind: NoteWarningKind) -> bool {
+        self.warnings.iter().any(|warning| warning.kind == kind)
+    }
+}
+
+fn heading_title(line: &str) -> Option<&str> {
+    if line.starts_with('#') {
+        return Some(line.trim_start_matches('#').trim()).filter(|title| !title.is_empty());
+    }
+    if let Some(inner) = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return Some(inner.trim()).filter(|title| !title.is_empty() && !title.contains("://"));
+    }
+    if line.len() > 4 && line.starts_with("==") && line.ends_with("==") {
+        return Some(line.trim_matches('=').trim()).filter(|title| !title.is_empty());
+    }
+    if line.chars().count() > MAX_HEADING_LEN || bullet_text(line).is_some() {
+        return None;
+    }
+    if let Some(title) = line.strip_suffix(':') {
+        return Some(title.trim()).filter(|title| !title.is_empty() && !title.contains("://"));
+    }
+    let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+    let all_caps = letters >= 3 && !line.chars().any(|c| c.is_lowercase());
+    all_caps.then_some(line)
+}
+
+fn bullet_text(line: &str) -> Option<&str> {
+    for marker in ['-', '*', '•', '+', '–'] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return rest
+                .starts_with(char::is_whitespace)
+                .then(|| rest.trim())
+                .filter(|text| !text.is_empty());
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let rest = line[digits..]
+        .strip_prefix('.')
+        .or_else(|| line[digits..].strip_prefix(')'))?;
+    rest.starts_with(char::is_whitespace)
+        .then(|| rest.trim())
+        .filter(|text| !text.is_empty())
+}
+
+fn warning_kinds(line: &str) -> Vec<NoteWarningKind> {
+    let lower = line.to_lowercase();
+    let words = tokenize(&lower);
+    let mut kinds = Vec::new();
+
+    let unflagged = lower.trim_start_matches(|c: char| !c.is_alphanumeric());
+    if CAUTION_PREFIXES
+        .iter()
+        .any(|prefix| unflagged.starts_with(prefix))
+    {
+        kinds.push(NoteWarningKind::Caution);
+    }
+    if lower.contains("install.txt") {
+        kinds.push(NoteWarningKind::InstallScript);
+    } else if words
+        .iter()
+        .any(|word| word == "requires" || word == "required")
+    {
+        kinds.push(NoteWarningKind::Requirement);
+    }
+    if words.iter().any(|word| MOD_WORDS.contains(&word.as_str())) {
+        kinds.push(NoteWarningKind::Mod);
+    }
+    kinds
+}
+
+/// `http(s)://` URLs in order of appearance, without trailing punctuation.
+fn extract_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| rest.find(scheme))
+        .min()
+    {
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\''))
+            .unwrap_or(candidate.len());
+        let link = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+        if link.len() > "https://".len() && !links.iter().any(|known| known == link) {
+            links.push(link.to_string());
+        }
+        rest = &candidate[end..];
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "\
+Beat Saber with all official DLC.
+Requires install.txt to be run for the OBB.
+
+What's new:
+- Added multiplayer lobbies
+- Fixed crash on launch
+
+KNOWN ISSUES
+* Modded songs need the BMBF mod loader
+WARNING: do not update from the store
+
+More info: https://example.com/beat-saber.
+";
+
+    #[test]
+    fn splits_sections_and_changelog() {
+        let notes = ReleaseNotes::parse(NOTE);
+        let titles: Vec<&str> = notes.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["", "What's new", "KNOWN ISSUES"]);
+        assert_eq!(
+            notes.summary,
+            "Beat Saber with all official DLC. Requires install.txt to be run for the OBB."
+        );
+        assert_eq!(
+            notes.changelog,
+            vec!["Added multiplayer lobbies", "Fixed crash on launch"]
+        );
+        assert_eq!(
+            notes.sections[2].bullets,
+            vec!["Modded songs need the BMBF mod loader"]
+        );
+    }
+
+    #[test]
+    fn flags_warnings_and_links() {
+        let notes = ReleaseNotes::parse(NOTE);
+        assert!(notes.has_warning(NoteWarningKind::InstallScript));
+        assert!(notes.has_warning(NoteWarningKind::Mod));
+        assert!(notes.has_warning(NoteWarningKind::Caution));
+        assert!(!notes.has_warning(NoteWarningKind::Requirement));
+        assert_eq!(notes.links, vec!["https://example.com/beat-saber"]);
+
+        // "Modern" is not a mod, and a bare URL line is not a heading
+        let plain = ReleaseNotes::parse("Modern Warfare remaster\nhttps://example.com/a:");
+        assert!(plain.warnings.is_empty());
+        assert_eq!(plain.sections.len(), 1);
+        assert_eq!(plain.links, vec!["https://example.com/a"]);
+    }
+
+    #[test]
+    fn recognises_heading_and_bullet_styles() {
+        let notes =
+            ReleaseNotes::parse("# Changes\n1. one\n2) two\n[Credits]\n+ someone\n== End ==");
+        let titles: Vec<&str> = notes.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["Changes", "Credits", "End"]);
+        assert_eq!(notes.changelog, vec!["one", "two"]);
+        assert_eq!(notes.sections[1].bullets, vec!["someone"]);
+        assert!(notes.summary.is_empty());
+        assert_eq!(notes.excerpt(6), "one tw");
+    }
+}
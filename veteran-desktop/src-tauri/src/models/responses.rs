@@ -6,7 +6,16 @@
 //! - Automatic TypeScript type generation via specta
 //! - No runtime mismatches between Rust and frontend
 
+use crate::models::catalog_report::{
+    CatalogParseReport, CatalogSyncSummary, MediaSyncReport, PopularityTrend,
+};
+use crate::models::collection::GameCollection;
+use crate::models::config::CatalogSourceKind;
+use crate::models::release_notes::{NoteWarning, ReleaseNotes};
+use crate::models::size::SizeBucket;
+use crate::models::watchlist::{WatchAlert, WatchedPackage};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ============================================================================
 // Common Response Types
@@ -106,6 +115,9 @@ pub struct CatalogStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_stale: Option<bool>,
     pub sync_in_progress: bool,
+    /// Operation id of the sync that is running, for `backend_catalog_sync_cancel`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_operation_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -114,10 +126,44 @@ pub struct CatalogLoadCacheResult {
     pub status: CatalogStatus,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CatalogSourceInfo {
+    pub name: String,
+    pub kind: CatalogSourceKind,
+    pub remote_name: String,
+    pub list_file: String,
+    pub priority: i32,
+    pub release_count: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct CatalogSyncResult {
+    /// False when another sync was already running
     pub synced: bool,
     pub status: CatalogStatus,
+    /// Diagnostics for the game list that is currently loaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_report: Option<CatalogParseReport>,
+    /// Thumbnail and note changes, when this sync refreshed them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<MediaSyncReport>,
+    /// Absent when the cache was fresh and no sync ran, the running sync's id
+    /// when one was already in progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<CatalogSyncSummary>,
+    /// New releases of watched packages found by this sync
+    pub watch_alerts: Vec<WatchAlert>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CatalogSyncCancelResult {
+    /// False when no matching sync was running, or it already committed the
+    /// new catalog and will finish
+    pub cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
 }
 
 /// A game in catalog search results (condensed view)
@@ -159,6 +205,10 @@ pub struct CatalogGameDetail {
     pub note_path: String,
     pub note_excerpt: String,
     pub note_exists: bool,
+    /// Warnings found in the release note, e.g. an `install.txt` or a modded build
+    pub note_warnings: Vec<NoteWarning>,
+    /// Catalog source the release is downloaded from
+    pub source: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -175,6 +225,14 @@ pub struct CatalogThumbnailPath {
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct CatalogNote {
     pub note: String,
+    pub parsed: ReleaseNotes,
+}
+
+/// Free-form user annotations for a package, keyed by annotation name
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CatalogAnnotations {
+    pub package_name: String,
+    pub annotations: HashMap<String, String>,
 }
 
 /// A game in the library view (with UI-specific fields)
@@ -190,7 +248,39 @@ pub struct LibraryGame {
     pub is_favorite: bool,
     pub is_new: bool,
     pub popularity_rank: i32,
+    /// Downloads and rank movement across recent syncs, `None` before the
+    /// first sync that recorded the package
+    pub trend: Option<PopularityTrend>,
     pub is_downloaded: bool,
+    pub source: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TagFacet {
+    pub tag: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SizeFacet {
+    pub bucket: SizeBucket,
+    pub label: String,
+    /// Query clauses that select the bucket, empty for unknown sizes
+    pub query: String,
+    pub count: u32,
+}
+
+/// Counts over every game matching the query and filter, before paging
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct LibraryFacets {
+    /// Most common first
+    pub tags: Vec<TagFacet>,
+    pub sizes: Vec<SizeFacet>,
+    pub favorites: u32,
+    pub downloaded: u32,
+    /// `None` when no device is connected
+    pub installed: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -204,6 +294,37 @@ pub struct LibraryResult {
     pub sort_ascending: bool,
     pub filter: String,
     pub favorites_count: u32,
+    /// Tags the query requires, from its `tag:` clauses
+    pub tags: Vec<String>,
+    pub facets: LibraryFacets,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CatalogExportResult {
+    pub path: String,
+    /// `json` or `csv`
+    pub format: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LocalImportRejection {
+    /// 0-based position of the entry in the imported list
+    pub index: u32,
+    pub release_name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LocalImportResult {
+    pub imported: u32,
+    pub rejected: Vec<LocalImportRejection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LocalRemoveResult {
+    pub removed: bool,
+    pub release_name: String,
 }
 
 // ============================================================================
@@ -217,6 +338,63 @@ pub struct FavoritesToggleResult {
     pub favorites: Vec<String>,
 }
 
+// ============================================================================
+// Watchlist Responses
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WatchlistResult {
+    pub watched: Vec<WatchedPackage>,
+}
+
+// ============================================================================
+// Collection Responses
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CollectionListResult {
+    pub collections: Vec<GameCollection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CollectionExportResult {
+    pub path: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CollectionImportResult {
+    pub created: u32,
+    /// Existing collections, matched by name, that gained packages
+    pub merged: u32,
+    pub collections: Vec<GameCollection>,
+}
+
+/// A member left out of a bulk action, with a reason such as
+/// `not_in_catalog`, `already_queued` or `not_downloaded`.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CollectionSkippedGame {
+    pub package_name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CollectionQueueResult {
+    pub collection_id: String,
+    pub queued: Vec<String>,
+    pub skipped: Vec<CollectionSkippedGame>,
+    pub queue_length: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CollectionInstallResult {
+    /// Operation of the whole batch; members are installed one after another
+    pub operation_id: String,
+    pub collection_id: String,
+    pub packages: Vec<String>,
+    pub skipped: Vec<CollectionSkippedGame>,
+}
+
 // ============================================================================
 // Download Responses
 // ============================================================================
@@ -233,8 +411,23 @@ pub struct DownloadQueueItem {
     pub eta: String,
     pub bytes_transferred: f64,
     pub total_bytes: f64,
+    /// Higher priorities download first
+    pub priority: i32,
+    /// Download slot, from 0, while downloading
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u32>,
+    /// Retries made after the first attempt
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_count: Option<u32>,
+    /// RFC 3339 time a failed download is retried at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_retry_at: Option<String>,
+    /// Classified cause of the last failure, e.g. "timeout" or "disk_full"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    /// Whether the last failure may go away on a retry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_transient: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
@@ -245,8 +438,13 @@ pub struct DownloadQueueStatus {
     pub queued_count: u32,
     pub total_count: u32,
     pub processing: bool,
+    /// First running download, kept for callers that show a single one
     #[serde(skip_serializing_if = "Option::is_none")]
     pub active_download: Option<DownloadQueueItem>,
+    /// Every running download, in slot order
+    pub active_downloads: Vec<DownloadQueueItem>,
+    /// Downloads run at once
+    pub slots: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -257,6 +455,9 @@ pub struct DownloadQueueAddResult {
     pub queue_length: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
+    /// Why the game wasn't added, or what to watch out for when it was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -286,6 +487,8 @@ pub struct DownloadLocation {
     pub path: String,
     pub exists: bool,
     pub free_bytes: f64,
+    /// Space the pending downloads still need, extraction included
+    pub queued_bytes: f64,
     pub file_count: u32,
 }
 
@@ -737,13 +940,24 @@ pub struct AdbConsoleHistoryResult {
 pub struct NewAppInfo {
     pub package_name: String,
     pub app_name: String,
+    /// Unix seconds of the sync that first saw the package (or its new version)
     pub install_date: f64,
+    /// "added" or "updated"
+    pub change: String,
+    pub version_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_version_code: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct NewAppsDiscoveryResult {
     pub new_apps: Vec<NewAppInfo>,
     pub count: u32,
+    /// Packages dropped from the catalog by the most recent change
+    pub removed: Vec<String>,
+    /// Unix seconds of the sync the most recent change is compared against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
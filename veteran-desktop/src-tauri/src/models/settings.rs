@@ -1,3 +1,7 @@
+use crate::models::collection::GameCollection;
+use crate::models::config::CatalogSource;
+use crate::models::download_queue::{deserialize_queue, QueuedDownload};
+use crate::models::watchlist::WatchedPackage;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
@@ -12,6 +16,14 @@ pub struct Settings {
     #[serde(alias = "bandwidthlimit")]
     pub bandwidth_limit_mbps: f64,
 
+    /// Downloads run at once, from 1 to `MAX_DOWNLOAD_SLOTS`
+    #[serde(alias = "concurrentdownloads")]
+    pub concurrent_downloads: i32,
+
+    /// Retries of a download that failed for a transient reason, 0 for none
+    #[serde(alias = "downloadmaxretries")]
+    pub download_max_retries: i32,
+
     #[serde(alias = "ipaddress")]
     pub ip_address: String,
 
@@ -21,6 +33,13 @@ pub struct Settings {
     #[serde(alias = "favoritedgames")]
     pub favorited_games: Vec<String>,
 
+    /// Named collections in the order the user arranged them
+    pub collections: Vec<GameCollection>,
+
+    /// Packages to report new releases of after each catalog sync
+    #[serde(alias = "watchedpackages")]
+    pub watched_packages: Vec<WatchedPackage>,
+
     #[serde(alias = "usegalleryview")]
     pub use_gallery_view: bool,
 
@@ -30,8 +49,9 @@ pub struct Settings {
     #[serde(alias = "sortascending")]
     pub sort_ascending: bool,
 
-    #[serde(alias = "queuedgames")]
-    pub queued_games: Vec<String>,
+    /// The download queue as of the last change, restored on startup
+    #[serde(alias = "queuedgames", deserialize_with = "deserialize_queue")]
+    pub queued_games: Vec<QueuedDownload>,
 
     #[serde(alias = "keepawakeduringlongops")]
     pub keep_awake_during_long_ops: bool,
@@ -45,6 +65,20 @@ pub struct Settings {
     #[serde(alias = "offlinemode")]
     pub offline_mode: bool,
 
+    /// URL or local file path of a `vrp-public.json` to use instead of the
+    /// built-in URLs; the `VETERAN_CONFIG_SOURCE` environment variable wins
+    #[serde(alias = "configsource")]
+    pub config_source: String,
+
+    /// Hours a synced catalog is used as is before the remote is checked for a
+    /// new `meta.7z`; 0 checks on every sync
+    #[serde(alias = "catalogcachettlhours")]
+    pub catalog_cache_ttl_hours: f64,
+
+    /// Hours a cached catalog is still accepted in offline mode; 0 accepts any age
+    #[serde(alias = "catalogmaxstalenesshours")]
+    pub catalog_max_staleness_hours: f64,
+
     #[serde(alias = "windowwidth")]
     pub window_width: i32,
 
@@ -66,6 +100,13 @@ pub struct Settings {
     #[serde(alias = "knowncatalogpackages")]
     pub known_catalog_packages: Vec<String>,
 
+    #[serde(alias = "newappwindowdays")]
+    pub new_app_window_days: i32,
+
+    /// Mirrors and local folders merged into the catalog alongside the public source
+    #[serde(alias = "catalogsources")]
+    pub catalog_sources: Vec<CatalogSource>,
+
     #[serde(alias = "performancerefreshratehz")]
     pub performance_refresh_rate_hz: i32,
 
@@ -94,9 +135,13 @@ impl Default for Settings {
             download_dir,
             delete_after_install: true,
             bandwidth_limit_mbps: 0.0,
+            concurrent_downloads: 1,
+            download_max_retries: 3,
             ip_address: String::new(),
             wireless_adb: false,
             favorited_games: Vec::new(),
+            collections: Vec::new(),
+            watched_packages: Vec::new(),
             use_gallery_view: false,
             sort_column: 1,
             sort_ascending: false,
@@ -105,6 +150,9 @@ impl Default for Settings {
             keep_awake_interval_seconds: 30,
             first_run: true,
             offline_mode: false,
+            config_source: String::new(),
+            catalog_cache_ttl_hours: 4.0,
+            catalog_max_staleness_hours: 24.0 * 7.0,
             window_width: 1120,
             window_height: 760,
             window_x: -1,
@@ -112,6 +160,8 @@ impl Default for Settings {
             window_maximized: false,
             diagnostics_uuid: String::new(),
             known_catalog_packages: Vec::new(),
+            new_app_window_days: 7,
+            catalog_sources: Vec::new(),
             performance_refresh_rate_hz: 90,
             performance_cpu_level: 2,
             performance_gpu_level: 2,
@@ -154,4 +204,19 @@ mod tests {
         assert_eq!(settings.window_width, 800);
         assert_eq!(settings.window_height, 760); // Default
     }
+
+    #[test]
+    fn test_queued_games_accept_package_names() {
+        // Older versions saved only package names
+        let json = r#"{"queuedgames": ["com.old", {"package_name": "com.new", "release_name": "New v2", "status": "paused", "retry_count": 2}]}"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.queued_games.len(), 2);
+        assert_eq!(settings.queued_games[0].package_name, "com.old");
+        assert_eq!(settings.queued_games[0].status, "queued");
+        assert_eq!(settings.queued_games[1].retry_count, 2);
+
+        let json = serde_json::to_string(&settings).unwrap();
+        let reloaded: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.queued_games, settings.queued_games);
+    }
 }
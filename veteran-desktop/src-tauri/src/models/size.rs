@@ -0,0 +1,174 @@
+//! Byte size parsing shared by the catalog, rclone stats and device `df` output.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+pub const KIB: f64 = 1024.0;
+pub const MIB: f64 = KIB * 1024.0;
+const GIB: u64 = 1024 * 1024 * 1024;
+
+static SIZE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([0-9]+(?:\.[0-9]+)?)\s*([kmgtp]?)(i?)(b?)$").expect("invalid size regex")
+});
+
+/// How `KB`/`MB` and single-letter suffixes like `k`/`G` are scaled.
+/// IEC suffixes (`KiB`, `Mi`) are always 1024-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitBase {
+    Decimal,
+    Binary,
+}
+
+/// Parse sizes like `1.5 GB`, `500MiB`, `5.8G` or a bare number, which is
+/// taken to be in units of `bare_unit` bytes.
+pub fn parse_size(text: &str, base: UnitBase, bare_unit: f64) -> Option<u64> {
+    let cleaned = text.trim().to_lowercase();
+    let captures = SIZE_PATTERN.captures(&cleaned)?;
+    let value = captures.get(1)?.as_str().parse::<f64>().ok()?;
+    let prefix = captures.get(2).map_or("", |m| m.as_str());
+    let binary = !captures.get(3).map_or("", |m| m.as_str()).is_empty();
+    let has_byte_suffix = !captures.get(4).map_or("", |m| m.as_str()).is_empty();
+
+    let exponent = match prefix {
+        "" if binary => return None,
+        "" if has_byte_suffix => 0,
+        "" => return Some((value * bare_unit) as u64),
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        "p" => 5,
+        _ => return None,
+    };
+    let step = if binary || base == UnitBase::Binary {
+        KIB
+    } else {
+        1000.0
+    };
+    Some((value * step.powi(exponent)) as u64)
+}
+
+/// Catalog sizes are MiB-based, and a bare number means MB.
+pub fn parse_catalog_size(text: &str) -> Option<u64> {
+    parse_size(text, UnitBase::Binary, MIB)
+}
+
+/// Format a byte count the way the catalog lists it, e.g. `1024 MB` or `1.5 MB`.
+pub fn format_megabytes(bytes: u64) -> String {
+    if bytes == 0 {
+        return String::new();
+    }
+    let megabytes = format!("{:.2}", bytes as f64 / MIB);
+    let megabytes = megabytes.trim_end_matches('0').trim_end_matches('.');
+    format!("{megabytes} MB")
+}
+
+/// Size ranges the library counts games in, for its filter chips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeBucket {
+    Small,
+    Medium,
+    Large,
+    Huge,
+    Unknown,
+}
+
+impl SizeBucket {
+    pub const ALL: [SizeBucket; 5] = [
+        SizeBucket::Small,
+        SizeBucket::Medium,
+        SizeBucket::Large,
+        SizeBucket::Huge,
+        SizeBucket::Unknown,
+    ];
+
+    pub fn of(bytes: u64) -> Self {
+        match bytes {
+            0 => SizeBucket::Unknown,
+            bytes if bytes < GIB => SizeBucket::Small,
+            bytes if bytes < 4 * GIB => SizeBucket::Medium,
+            bytes if bytes < 10 * GIB => SizeBucket::Large,
+            _ => SizeBucket::Huge,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SizeBucket::Small => "Under 1 GB",
+            SizeBucket::Medium => "1-4 GB",
+            SizeBucket::Large => "4-10 GB",
+            SizeBucket::Huge => "10 GB and up",
+            SizeBucket::Unknown => "Unknown size",
+        }
+    }
+
+    /// Library query clauses selecting the bucket, empty for unknown sizes.
+    pub fn query(self) -> &'static str {
+        match self {
+            SizeBucket::Small => "size:<1GB",
+            SizeBucket::Medium => "size:>=1GB size:<4GB",
+            SizeBucket::Large => "size:>=4GB size:<10GB",
+            SizeBucket::Huge => "size:>=10GB",
+            SizeBucket::Unknown => "",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_by_base() {
+        assert_eq!(parse_size("1 KiB", UnitBase::Decimal, 1.0), Some(1024));
+        assert_eq!(
+            parse_size("2.5 MB", UnitBase::Decimal, 1.0),
+            Some(2_500_000)
+        );
+        assert_eq!(parse_size("2.5 MB", UnitBase::Binary, 1.0), Some(2_621_440));
+        assert_eq!(
+            parse_size("5.8G", UnitBase::Binary, 1.0),
+            Some(6_227_702_579)
+        );
+        assert_eq!(parse_size("12 b", UnitBase::Binary, KIB), Some(12));
+        assert_eq!(parse_size("64000", UnitBase::Binary, KIB), Some(65_536_000));
+        assert_eq!(parse_size("lots", UnitBase::Binary, 1.0), None);
+        assert_eq!(parse_size("3 ib", UnitBase::Binary, 1.0), None);
+        assert_eq!(parse_size("", UnitBase::Binary, 1.0), None);
+    }
+
+    #[test]
+    fn catalog_sizes_round_trip() {
+        assert_eq!(parse_catalog_size("1024"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_catalog_size("2 GB"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(
+            format_megabytes(parse_catalog_size("1024").unwrap()),
+            "1024 MB"
+        );
+        assert_eq!(
+            format_megabytes(parse_catalog_size("1.5").unwrap()),
+            "1.5 MB"
+        );
+        assert_eq!(format_megabytes(parse_catalog_size("1.0").unwrap()), "1 MB");
+        assert_eq!(format_megabytes(0), "");
+    }
+
+    #[test]
+    fn size_buckets_match_their_queries() {
+        assert_eq!(SizeBucket::of(0), SizeBucket::Unknown);
+        assert_eq!(
+            SizeBucket::of(parse_catalog_size("1023").unwrap()),
+            SizeBucket::Small
+        );
+        assert_eq!(
+            SizeBucket::of(parse_catalog_size("1GB").unwrap()),
+            SizeBucket::Medium
+        );
+        assert_eq!(
+            SizeBucket::of(parse_catalog_size("12 GB").unwrap()),
+            SizeBucket::Huge
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::services::search::tokenize;
+use std::collections::{BTreeSet, HashMap};
+
+/// A tag and the words that give a game that tag.
+struct TagRule {
+    tag: &'static str,
+    /// Words appended to the game name in its release name, e.g. `-MR`
+    release_markers: &'static [&'static str],
+    /// Words or phrases in the release note
+    note_words: &'static [&'static str],
+}
+
+const TAXONOMY: &[TagRule] = &[
+    TagRule {
+        tag: "mod",
+        release_markers: &["mod", "mods", "modded", "patched"],
+        note_words: &["mod", "mods", "modded", "patched"],
+    },
+    TagRule {
+        tag: "mixed-reality",
+        release_markers: &["mr"],
+        note_words: &["mixed reality", "passthrough"],
+    },
+    TagRule {
+        tag: "multiplayer",
+        release_markers: &["multiplayer", "mp", "coop"],
+        note_words: &["multiplayer", "co-op", "coop", "pvp"],
+    },
+    TagRule {
+        tag: "demo",
+        release_markers: &["demo"],
+        note_words: &[],
+    },
+    TagRule {
+        tag: "hand-tracking",
+        release_markers: &[],
+        note_words: &["hand tracking", "hand-tracking"],
+    },
+    TagRule {
+        tag: "rhythm",
+        release_markers: &[],
+        note_words: &["rhythm"],
+    },
+    TagRule {
+        tag: "fitness",
+        release_markers: &[],
+        note_words: &["fitness", "workout"],
+    },
+    TagRule {
+        tag: "horror",
+        release_markers: &[],
+        note_words: &["horror"],
+    },
+    TagRule {
+        tag: "puzzle",
+        release_markers: &[],
+        note_words: &["puzzle", "puzzles"],
+    },
+    TagRule {
+        tag: "shooter",
+        release_markers: &[],
+        note_words: &["shooter", "fps"],
+    },
+    TagRule {
+        tag: "racing",
+        release_markers: &[],
+        note_words: &["racing"],
+    },
+    TagRule {
+        tag: "simulation",
+        release_markers: &[],
+        note_words: &["simulator", "simulation"],
+    },
+];
+
+/// Tags from the markers after the game name in `release_name`, e.g. the
+/// `mr` in `Beat Saber v1400+1.40 -MR`.
+pub fn release_tags(game_name: &str, release_name: &str) -> Vec<&'static str> {
+    let markers = release_name
+        .get(..game_name.len())
+        .filter(|prefix| !game_name.is_empty() && prefix.eq_ignore_ascii_case(game_name))
+        .map_or(release_name, |_| &release_name[game_name.len()..]);
+    let tokens = tokenize(markers);
+    TAXONOMY
+        .iter()
+        .filter(|rule| {
+            rule.release_markers
+                .iter()
+                .any(|marker| tokens.iter().any(|token| token == marker))
+        })
+        .map(|rule| rule.tag)
+        .collect()
+}
+
+/// Tags whose words or phrases appear in a release note.
+pub fn note_tags(note: &str) -> Vec<&'static str> {
+    let tokens = tokenize(note);
+    TAXONOMY
+        .iter()
+        .filter(|rule| {
+            rule.note_words.iter().any(|word| {
+                let phrase = tokenize(word);
+                !phrase.is_empty()
+                    && tokens
+                        .windows(phrase.len())
+                        .any(|window| window == phrase.as_slice())
+            })
+        })
+        .map(|rule| rule.tag)
+        .collect()
+}
+
+/// Lowercase, with runs of anything but letters and digits turned into `-`.
+pub fn normalize_tag(tag: &str) -> String {
+    tokenize(tag).join("-")
+}
+
+/// Tags added and removed per package by the user tag file, a JSON object
+/// mapping package names to tag lists. A tag starting with `-` removes a
+/// derived tag:
+///
+/// ```json
+/// { "com.beatgames.beatsaber": ["fitness", "-mod"] }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagOverrides {
+    added: HashMap<String, BTreeSet<String>>,
+    removed: HashMap<String, BTreeSet<String>>,
+}
+
+impl TagOverrides {
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        let entries: HashMap<String, Vec<String>> = serde_json::from_str(text)?;
+        let mut overrides = Self::default();
+        for (package, tags) in entries {
+            for tag in tags {
+                let (target, tag) = match tag.trim().strip_prefix('-') {
+                    Some(removed) => (&mut overrides.removed, normalize_tag(removed)),
+                    None => (&mut overrides.added, normalize_tag(&tag)),
+                };
+                if !tag.is_empty() {
+                    target.entry(package.clone()).or_default().insert(tag);
+                }
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// The sorted tags of `package`, starting from the `derived` ones.
+    pub fn apply<'a>(
+        &self,
+        package: &str,
+        derived: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<String> {
+        let mut tags: BTreeSet<String> = derived.into_iter().map(str::to_string).collect();
+        if let Some(added) = self.added.get(package) {
+            tags.extend(added.iter().cloned());
+        }
+        if let Some(removed) = self.removed.get(package) {
+            tags.retain(|tag| !removed.contains(tag));
+        }
+        tags.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_markers_follow_the_game_name() {
+        assert_eq!(
+            release_tags("Beat Saber", "Beat Saber v1400+1.40 -MR -mod"),
+            vec!["mod", "mixed-reality"]
+        );
+        // Words of the game name itself are not markers
+        assert!(release_tags("Mod Maker MP", "Mod Maker MP v3+1.0").is_empty());
+        assert_eq!(
+            release_tags("Other", "Some Game v2+2.0 multiplayer"),
+            vec!["multiplayer"]
+        );
+    }
+
+    #[test]
+    fn note_words_and_phrases() {
+        let tags = note_tags(
+            "A co-op rhythm game with hand tracking.\nWorks with passthrough (Mixed Reality).",
+        );
+        assert_eq!(
+            tags,
+            vec!["mixed-reality", "multiplayer", "hand-tracking", "rhythm"]
+        );
+        // Partial words don't count
+        assert!(note_tags("Modern puzzled racingcar").is_empty());
+    }
+
+    #[test]
+    fn overrides_add_and_remove_tags() {
+        let overrides = TagOverrides::from_json(
+            r#"{"com.beat": ["Fitness", "Party Game", "-MOD", " "], "com.other": ["-rhythm"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            overrides.apply("com.beat", ["mod", "rhythm"]),
+            vec!["fitness", "party-game", "rhythm"]
+        );
+        assert!(overrides.apply("com.other", ["rhythm"]).is_empty());
+        assert_eq!(overrides.apply("com.none", ["demo"]), vec!["demo"]);
+        assert!(TagOverrides::from_json("[]").is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use crate::models::game::Game;
+use crate::models::size::format_megabytes;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Words in a version name suffix that mark a build before the release.
+const PRE_RELEASE_WORDS: [&str; 8] = [
+    "alpha", "beta", "rc", "pre", "preview", "dev", "test", "nightly",
+];
+
+/// Where a version name's suffix puts it relative to the plain release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    /// `1.2.0-beta.3`, `1.2rc1`
+    PreRelease,
+    /// `1.2.0`
+    Release,
+    /// Any other suffix, e.g. `1.2.0-hotfix`
+    Patched,
+}
+
+/// Part of a version name suffix, numbers compared as numbers.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Identifier {
+    Number(u64),
+    Text(String),
+}
+
+/// A semver-ish `version_name`: dot-separated numbers with an optional
+/// suffix. `v1.40`, `1.40.0` and `1.40` are equal; names without a leading
+/// number sort below all numbered ones, by their text.
+#[derive(Debug, Clone)]
+pub struct VersionName {
+    numbers: Vec<u64>,
+    stage: Stage,
+    suffix: Vec<Identifier>,
+    text: String,
+}
+
+impl VersionName {
+    pub fn parse(name: &str) -> Self {
+        let text = name.trim().to_lowercase();
+        // Build metadata doesn't take part in ordering
+        let core = text.split('+').next().unwrap_or_default();
+        let core = core
+            .strip_prefix('v')
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(core);
+
+        let numeric_len = core
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(core.len());
+        let numbers: Vec<u64> = core[..numeric_len]
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().unwrap_or(u64::MAX))
+            .collect();
+
+        let suffix = identifiers(&core[numeric_len..]);
+        let stage = if suffix.is_empty() {
+            Stage::Release
+        } else if suffix.iter().any(
+            |id| matches!(id, Identifier::Text(word) if PRE_RELEASE_WORDS.contains(&word.as_str())),
+        ) {
+            Stage::PreRelease
+        } else {
+            Stage::Patched
+        };
+
+        Self {
+            numbers,
+            stage,
+            suffix,
+            text,
+        }
+    }
+
+    fn number(&self, index: usize) -> u64 {
+        self.numbers.get(index).copied().unwrap_or(0)
+    }
+}
+
+impl Ord for VersionName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.numbers.is_empty(), other.numbers.is_empty()) {
+            (true, true) => return self.text.cmp(&other.text),
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+        let len = self.numbers.len().max(other.numbers.len());
+        (0..len)
+            .map(|i| self.number(i).cmp(&other.number(i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.stage.cmp(&other.stage))
+            .then_with(|| self.suffix.cmp(&other.suffix))
+    }
+}
+
+impl PartialEq for VersionName {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for VersionName {}
+
+impl PartialOrd for VersionName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn identifiers(suffix: &str) -> Vec<Identifier> {
+    let mut identifiers = Vec::new();
+    let mut current = String::new();
+    let mut flush = |current: &mut String| {
+        if !current.is_empty() {
+            identifiers.push(match current.parse() {
+                Ok(number) => Identifier::Number(number),
+                Err(_) => Identifier::Text(current.clone()),
+            });
+            current.clear();
+        }
+    };
+    for c in suffix.chars() {
+        let same_kind = current
+            .chars()
+            .last()
+            .is_none_or(|last| last.is_ascii_digit() == c.is_ascii_digit());
+        if !c.is_alphanumeric() || !same_kind {
+            flush(&mut current);
+        }
+        if c.is_alphanumeric() {
+            current.push(c);
+        }
+    }
+    flush(&mut current);
+    identifiers
+}
+
+/// How a release ranks among the releases of its package: by version code,
+/// then version name, then release date.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleaseVersion {
+    code: i64,
+    name: VersionName,
+    date: Option<NaiveDate>,
+}
+
+impl ReleaseVersion {
+    pub fn of(game: &Game) -> Self {
+        Self {
+            code: game.version_code,
+            name: VersionName::parse(&game.version_name),
+            date: game.last_updated,
+        }
+    }
+}
+
+/// Total order of two releases, oldest first. The release name settles
+/// releases whose versions are identical so the order never depends on the
+/// list order.
+pub fn compare_releases(a: &Game, b: &Game) -> Ordering {
+    ReleaseVersion::of(a)
+        .cmp(&ReleaseVersion::of(b))
+        .then_with(|| a.release_name.cmp(&b.release_name))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionOrdering {
+    Older,
+    Same,
+    Newer,
+}
+
+/// What changes between two releases of one package, `from` to `to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct ReleaseDiff {
+    pub package_name: String,
+    pub from_release: String,
+    pub to_release: String,
+    /// Where `to` ranks against `from`
+    pub ordering: VersionOrdering,
+    #[specta(type = f64)]
+    pub from_version_code: i64,
+    #[specta(type = f64)]
+    pub to_version_code: i64,
+    pub from_version_name: String,
+    pub to_version_name: String,
+    #[specta(type = f64)]
+    pub size_delta_bytes: i64,
+    /// e.g. `+12.5 MB`, `-3 MB` or `0 MB`
+    pub size_delta: String,
+    /// Days from `from`'s release date to `to`'s, when both are known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[specta(type = Option<f64>)]
+    pub days_between: Option<i64>,
+    pub game_name_changed: bool,
+    /// Words of `to`'s release name missing from `from`'s, e.g. `+MR`
+    pub release_words_added: Vec<String>,
+    pub release_words_removed: Vec<String>,
+}
+
+impl ReleaseDiff {
+    pub fn between(from: &Game, to: &Game) -> Self {
+        let ordering = match compare_releases(to, from) {
+            Ordering::Less => VersionOrdering::Older,
+            Ordering::Equal => VersionOrdering::Same,
+            Ordering::Greater => VersionOrdering::Newer,
+        };
+        let size_delta_bytes = to.size_bytes as i64 - from.size_bytes as i64;
+        let size_delta = match size_delta_bytes {
+            0 => "0 MB".to_string(),
+            delta if delta > 0 => format!("+{}", format_megabytes(delta.unsigned_abs())),
+            delta => format!("-{}", format_megabytes(delta.unsigned_abs())),
+        };
+        let from_words = release_words(&from.release_name);
+        let to_words = release_words(&to.release_name);
+
+        Self {
+            package_name: to.package_name.clone(),
+            from_release: from.release_name.clone(),
+            to_release: to.release_name.clone(),
+            ordering,
+            from_version_code: from.version_code,
+            to_version_code: to.version_code,
+            from_version_name: from.version_name.clone(),
+            to_version_name: to.version_name.clone(),
+            size_delta_bytes,
+            size_delta,
+            days_between: from
+                .last_updated
+                .zip(to.last_updated)
+                .map(|(from, to)| (to - from).num_days()),
+            game_name_changed: from.game_name != to.game_name,
+            release_words_added: to_words
+                .iter()
+                .filter(|word| !from_words.contains(word))
+                .cloned()
+                .collect(),
+            release_words_removed: from_words
+                .iter()
+                .filter(|word| !to_words.contains(word))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Words of a release name other than its version marker (`v1400+1.40`).
+fn release_words(release_name: &str) -> Vec<String> {
+    release_name
+        .split_whitespace()
+        .filter(|word| {
+            !(word.starts_with('v') && word[1..].starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(release_name: &str, code: i64, name: &str, date: &str) -> Game {
+        Game {
+            game_name: "Beat Saber".to_string(),
+            release_name: release_name.to_string(),
+            package_name: "com.beatgames.beatsaber".to_string(),
+            version_code: code,
+            version_name: name.to_string(),
+            last_updated: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn version_names_order_semver_ish() {
+        let ordered = [
+            "build",
+            "1.9",
+            "1.10-alpha",
+            "1.10-beta.2",
+            "1.10-beta.10",
+            "1.10rc1",
+            "v1.10",
+            "1.10-hotfix",
+            "1.10.1",
+            "2",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(
+                VersionName::parse(pair[0]) < VersionName::parse(pair[1]),
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        assert_eq!(
+            VersionName::parse("v1.40").cmp(&VersionName::parse("1.40.0+quest")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn releases_order_by_code_then_name_then_date() {
+        let mut releases = [
+            release("Beat Saber v0+1.30", 0, "1.30", "2024-01-01"),
+            release("Beat Saber v1400+1.40.1", 1400, "1.40.1", "2024-05-01"),
+            release("Beat Saber v1400+1.40 -QU", 1400, "1.40", "2024-06-01"),
+            release("Beat Saber v1400+1.40", 1400, "1.40", "2024-04-01"),
+            release("Beat Saber v0+1.20", 0, "1.20", "2024-03-01"),
+        ];
+        releases.sort_by(compare_releases);
+        let names: Vec<&str> = releases.iter().map(|r| r.release_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Beat Saber v0+1.20",
+                "Beat Saber v0+1.30",
+                "Beat Saber v1400+1.40",
+                "Beat Saber v1400+1.40 -QU",
+                "Beat Saber v1400+1.40.1",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_size_date_and_name_changes() {
+        let from = Game {
+            size_bytes: 500 * 1024 * 1024,
+            ..release("Beat Saber v1300+1.30", 1300, "1.30", "2024-01-01")
+        };
+        let to = Game {
+            size_bytes: 512 * 1024 * 1024 + 512 * 1024,
+            ..release("Beat Saber v1400+1.40 +MR", 1400, "1.40", "2024-01-31")
+        };
+
+        let diff = ReleaseDiff::between(&from, &to);
+        assert_eq!(diff.ordering, VersionOrdering::Newer);
+        assert_eq!(diff.size_delta, "+12.5 MB");
+        assert_eq!(diff.days_between, Some(30));
+        assert_eq!(diff.release_words_added, vec!["+MR"]);
+        assert!(diff.release_words_removed.is_empty());
+        assert!(!diff.game_name_changed);
+
+        let back = ReleaseDiff::between(&to, &from);
+        assert_eq!(back.ordering, VersionOrdering::Older);
+        assert_eq!(back.size_delta, "-12.5 MB");
+        assert_eq!(back.release_words_removed, vec!["+MR"]);
+        assert_eq!(
+            ReleaseDiff::between(&from, &from).ordering,
+            VersionOrdering::Same
+        );
+    }
+}
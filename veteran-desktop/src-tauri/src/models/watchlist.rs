@@ -0,0 +1,219 @@
+use crate::models::game::Game;
+use crate::models::size::format_megabytes;
+use crate::services::catalog_diff::CatalogDiff;
+use serde::{Deserialize, Serialize};
+
+/// A package watched for new releases, installed or not.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(default)]
+pub struct WatchedPackage {
+    pub package_name: String,
+    /// Queue a new release for download as soon as a sync finds it
+    pub auto_queue: bool,
+    /// Release with the highest version code seen so far, empty until the
+    /// catalog lists the package
+    pub release_name: String,
+    #[specta(type = f64)]
+    pub version_code: i64,
+    #[specta(type = f64)]
+    pub size_bytes: u64,
+}
+
+impl WatchedPackage {
+    /// Start watching `package_name` from its current newest release, if any.
+    pub fn new(package_name: &str, auto_queue: bool, newest: Option<&Game>) -> Self {
+        let mut watched = Self {
+            package_name: package_name.to_string(),
+            auto_queue,
+            ..Self::default()
+        };
+        if let Some(game) = newest {
+            watched.see(game);
+        }
+        watched
+    }
+
+    fn see(&mut self, game: &Game) {
+        self.release_name = game.release_name.clone();
+        self.version_code = game.version_code;
+        self.size_bytes = game.size_bytes;
+    }
+}
+
+/// A sync found a new release of a watched package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct WatchAlert {
+    pub package_name: String,
+    pub game_name: String,
+    /// Absent when the package wasn't listed before
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_release: Option<String>,
+    pub to_release: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[specta(type = Option<f64>)]
+    pub from_version_code: Option<i64>,
+    #[specta(type = f64)]
+    pub to_version_code: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[specta(type = Option<f64>)]
+    pub from_size_bytes: Option<u64>,
+    #[specta(type = f64)]
+    pub to_size_bytes: u64,
+    /// Whether the release was queued for download
+    pub queued: bool,
+}
+
+impl WatchAlert {
+    pub fn message(&self) -> String {
+        let size = match format_megabytes(self.to_size_bytes) {
+            size if size.is_empty() => size,
+            size => format!(" ({size})"),
+        };
+        match self.from_version_code {
+            Some(from) => format!(
+                "New release of {}: version {} -> {}{size}",
+                self.game_name, from, self.to_version_code
+            ),
+            None => format!(
+                "{} is now listed: version {}{size}",
+                self.game_name, self.to_version_code
+            ),
+        }
+    }
+}
+
+/// Alert on the watched packages a sync's `diff` added or bumped, when their
+/// newest release from `newest` is above the highest version code the watch
+/// has seen. A mirror dropping a release and listing it again therefore stays
+/// quiet, since the watch still remembers the higher version.
+pub fn check_watchlist<'a>(
+    watched: &mut [WatchedPackage],
+    diff: &CatalogDiff,
+    newest: impl Fn(&str) -> Option<&'a Game>,
+) -> Vec<WatchAlert> {
+    let mut alerts = Vec::new();
+    for watch in watched.iter_mut() {
+        let Some(game) = newest(&watch.package_name) else {
+            continue;
+        };
+        let seen = !watch.release_name.is_empty();
+        if seen && game.version_code <= watch.version_code {
+            continue;
+        }
+        if diff.is_added_or_updated(&watch.package_name) {
+            alerts.push(WatchAlert {
+                package_name: watch.package_name.clone(),
+                game_name: game.game_name.clone(),
+                from_release: seen.then(|| watch.release_name.clone()),
+                to_release: game.release_name.clone(),
+                from_version_code: seen.then_some(watch.version_code),
+                to_version_code: game.version_code,
+                from_size_bytes: seen.then_some(watch.size_bytes),
+                to_size_bytes: game.size_bytes,
+                queued: false,
+            });
+        }
+        watch.see(game);
+    }
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::catalog_diff::VersionBump;
+
+    fn game(package: &str, release: &str, version_code: i64, size_bytes: u64) -> Game {
+        Game {
+            game_name: format!("Game {package}"),
+            release_name: release.to_string(),
+            package_name: package.to_string(),
+            version_code,
+            size_bytes,
+            ..Game::default()
+        }
+    }
+
+    fn bumped(package: &str, from: i64, to: i64) -> CatalogDiff {
+        CatalogDiff {
+            updated: vec![VersionBump {
+                package_name: package.to_string(),
+                from,
+                to,
+            }],
+            ..CatalogDiff::default()
+        }
+    }
+
+    #[test]
+    fn alerts_on_new_releases_of_watched_packages() {
+        let old = game("com.a", "A v10+1.0", 10, 1_000_000);
+        let mut watched = vec![
+            WatchedPackage::new("com.a", true, Some(&old)),
+            WatchedPackage::new("com.b", false, None),
+            WatchedPackage::new("com.c", false, None),
+        ];
+        assert_eq!(watched[0].version_code, 10);
+
+        let catalog = [
+            game("com.a", "A v12+1.2", 12, 2_000_000),
+            game("com.b", "B v1+1.0", 1, 500),
+        ];
+        let newest = |package: &str| catalog.iter().find(|g| g.package_name == package);
+        let diff = CatalogDiff {
+            added: vec!["com.b".to_string()],
+            ..bumped("com.a", 10, 12)
+        };
+        let alerts = check_watchlist(&mut watched, &diff, newest);
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].from_version_code, Some(10));
+        assert_eq!(alerts[0].to_version_code, 12);
+        assert_eq!(alerts[0].from_size_bytes, Some(1_000_000));
+        assert_eq!(alerts[0].to_size_bytes, 2_000_000);
+        assert_eq!(
+            alerts[0].message(),
+            "New release of Game com.a: version 10 -> 12 (1.91 MB)"
+        );
+        // A package listed for the first time has nothing to compare with
+        assert_eq!(alerts[1].package_name, "com.b");
+        assert_eq!(alerts[1].from_release, None);
+        assert_eq!(watched[1].release_name, "B v1+1.0");
+        assert!(watched[2].release_name.is_empty());
+
+        // Nothing changed since
+        assert!(check_watchlist(&mut watched, &CatalogDiff::default(), newest).is_empty());
+    }
+
+    #[test]
+    fn releases_dropped_and_listed_again_alert_once() {
+        let mut watched = vec![WatchedPackage::new(
+            "com.a",
+            false,
+            Some(&game("com.a", "A v12+1.2", 12, 10)),
+        )];
+
+        // A mirror drops the newest release, then lists it again
+        let dropped = [game("com.a", "A v10+1.0", 10, 10)];
+        let alerts = check_watchlist(&mut watched, &CatalogDiff::default(), |package| {
+            dropped.iter().find(|g| g.package_name == package)
+        });
+        assert!(alerts.is_empty());
+        assert_eq!(watched[0].version_code, 12);
+
+        let relisted = [game("com.a", "A v12+1.2", 12, 10)];
+        let alerts = check_watchlist(&mut watched, &bumped("com.a", 10, 12), |package| {
+            relisted.iter().find(|g| g.package_name == package)
+        });
+        assert!(alerts.is_empty());
+
+        // Only a version above the highest seen is news
+        let newer = [game("com.a", "A v13+1.3", 13, 10)];
+        let alerts = check_watchlist(&mut watched, &bumped("com.a", 12, 13), |package| {
+            newer.iter().find(|g| g.package_name == package)
+        });
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].from_version_code, Some(12));
+        assert_eq!(watched[0].version_code, 13);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::models::device::RawDeviceInfo;
+use crate::models::size::{parse_size, UnitBase, KIB, MIB};
 use adb_client::server::ADBServer;
 use adb_client::ADBDeviceExt;
 use anyhow::{anyhow, Context, Result};
@@ -11,9 +12,6 @@ use std::process::Command;
 use std::sync::LazyLock;
 use std::time::Duration;
 
-static SIZE_TOKEN_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^([0-9]+(?:\.[0-9]+)?)([kmgtp]?i?b?)?$").expect("invalid size token regex")
-});
 static PACKAGE_VERSION_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"versionCode:([0-9]+)").expect("invalid package regex"));
 
@@ -625,32 +623,9 @@ impl AdbService {
     }
 
     fn size_token_to_mb(token: &str, assume_kib_without_suffix: bool) -> Option<i64> {
-        let cleaned = token.trim().to_lowercase();
-        if cleaned.is_empty() {
-            return None;
-        }
-
-        let captures = SIZE_TOKEN_PATTERN.captures(&cleaned)?;
-        let raw_value = captures.get(1)?.as_str().parse::<f64>().ok()?;
-        let suffix = captures.get(2).map(|m| m.as_str()).unwrap_or("");
-
-        let mb = match suffix {
-            "" | "b" => {
-                if assume_kib_without_suffix {
-                    raw_value / 1024.0
-                } else {
-                    raw_value / (1024.0 * 1024.0)
-                }
-            }
-            "k" | "kb" | "ki" | "kib" => raw_value / 1024.0,
-            "m" | "mb" | "mi" | "mib" => raw_value,
-            "g" | "gb" | "gi" | "gib" => raw_value * 1024.0,
-            "t" | "tb" | "ti" | "tib" => raw_value * 1024.0 * 1024.0,
-            "p" | "pb" | "pi" | "pib" => raw_value * 1024.0 * 1024.0 * 1024.0,
-            _ => return None,
-        };
-
-        Some(mb as i64)
+        let bare_unit = if assume_kib_without_suffix { KIB } else { 1.0 };
+        let bytes = parse_size(token, UnitBase::Binary, bare_unit)?;
+        Some((bytes as f64 / MIB) as i64)
     }
 }
 
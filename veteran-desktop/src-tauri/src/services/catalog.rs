@@ -1,16 +1,56 @@
-use crate::models::game::Game;
-use anyhow::{Context, Result};
+use crate::models::catalog_report::{
+    CatalogParseReport, MediaSyncCounts, MediaSyncReport, PopularityTrend,
+};
+use crate::models::config::{LOCAL_SOURCE, PRIMARY_SOURCE};
+use crate::models::game::{CatalogSchema, Game};
+use crate::models::release_notes::ReleaseNotes;
+use crate::models::tags::{self, TagOverrides};
+use crate::models::version::compare_releases;
+use crate::services::catalog_db::{CatalogDb, LocalEntry, MediaPresence};
+use crate::services::catalog_diff::{CatalogDiff, CatalogSnapshot};
+use crate::services::popularity::{current_samples, PopularityHistory};
+use crate::services::search::{NotesIndex, SearchField, SearchIndex};
+use anyhow::{anyhow, Context, Result};
 use md5;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+const DATABASE_FILE: &str = "catalog.db";
+/// Where the snapshot lived before the catalog database
+const LEGACY_SNAPSHOT_FILE: &str = "catalog_snapshot.json";
+const DEFAULT_NEW_WINDOW_DAYS: u32 = 7;
+/// User tag file, next to the cache directory, see [`TagOverrides`].
+const TAG_FILE: &str = "tags.json";
+
+/// The game list of an additional catalog source, see `models::config::CatalogSource`.
+#[derive(Debug, Clone)]
+pub struct SourceList {
+    pub source: String,
+    pub priority: i32,
+    pub content: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct CatalogService {
     games: Vec<Game>,
     all_versions: Vec<Game>,
+    games_index: SearchIndex,
+    versions_index: SearchIndex,
+    notes_index: NotesIndex,
+    /// Tags found in each package's release note
+    note_tags: HashMap<String, Vec<&'static str>>,
     cache_dir: PathBuf,
     thumbnails_dir: PathBuf,
     notes_dir: PathBuf,
+    db: Option<CatalogDb>,
+    media: HashMap<String, MediaPresence>,
+    local_entries: Vec<LocalEntry>,
+    snapshot: CatalogSnapshot,
+    popularity: PopularityHistory,
+    trends: HashMap<String, PopularityTrend>,
+    parse_report: Option<CatalogParseReport>,
+    media_sync_report: Option<MediaSyncReport>,
+    new_window_days: u32,
     syncing: bool,
 }
 
@@ -21,6 +61,42 @@ impl Default for CatalogService {
 }
 
 impl CatalogService {
+    /// The stored snapshot, importing the JSON file of older versions into
+    /// the database the first time, so their first sync doesn't report every
+    /// package as new.
+    fn load_snapshot(db: &CatalogDb, cache_dir: &Path) -> Option<CatalogSnapshot> {
+        if let Ok(Some(snapshot)) = db.load_snapshot() {
+            return Some(snapshot);
+        }
+        let legacy_path = cache_dir.join(LEGACY_SNAPSHOT_FILE);
+        let content = std::fs::read_to_string(&legacy_path).ok()?;
+        let snapshot = match CatalogSnapshot::from_legacy_json(&content) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                crate::logger::log(&format!(
+                    "[CATALOG] Ignoring unreadable {}: {}",
+                    LEGACY_SNAPSHOT_FILE, e
+                ));
+                return None;
+            }
+        };
+        match db.save_snapshot(&snapshot, &snapshot.last_diff) {
+            Ok(()) => {
+                crate::logger::log(&format!(
+                    "[CATALOG] Imported {} packages from {}",
+                    snapshot.packages.len(),
+                    LEGACY_SNAPSHOT_FILE
+                ));
+                let _ = std::fs::remove_file(&legacy_path);
+            }
+            Err(e) => crate::logger::log(&format!(
+                "[CATALOG] Failed to import {}: {:#}",
+                LEGACY_SNAPSHOT_FILE, e
+            )),
+        }
+        Some(snapshot)
+    }
+
     fn default_cache_dir() -> PathBuf {
         dirs::home_dir()
             .map(|path| path.join(".veteran").join("cache"))
@@ -40,14 +116,59 @@ impl CatalogService {
         let _ = std::fs::create_dir_all(&thumbnails_dir);
         let _ = std::fs::create_dir_all(&notes_dir);
 
-        Self {
+        let db = match CatalogDb::open(&cache_dir.join(DATABASE_FILE)) {
+            Ok(db) => Some(db),
+            Err(e) => {
+                crate::logger::log(&format!(
+                    "[CATALOG] Failed to open catalog database: {:#}",
+                    e
+                ));
+                None
+            }
+        };
+        let snapshot = db
+            .as_ref()
+            .and_then(|db| Self::load_snapshot(db, &cache_dir))
+            .unwrap_or_default();
+        let popularity = db
+            .as_ref()
+            .and_then(|db| db.load_popularity().ok())
+            .unwrap_or_default();
+        let media = db
+            .as_ref()
+            .and_then(|db| db.load_media().ok())
+            .unwrap_or_default();
+        let parse_report = db
+            .as_ref()
+            .and_then(|db| db.load_parse_report().ok().flatten());
+        let local_entries = db
+            .as_ref()
+            .and_then(|db| db.load_local_entries().ok())
+            .unwrap_or_default();
+
+        let mut service = Self {
             games: Vec::new(),
             all_versions: Vec::new(),
+            games_index: SearchIndex::default(),
+            versions_index: SearchIndex::default(),
+            notes_index: NotesIndex::default(),
+            note_tags: HashMap::new(),
             cache_dir,
             thumbnails_dir,
             notes_dir,
+            db,
+            media,
+            local_entries,
+            snapshot,
+            popularity,
+            trends: HashMap::new(),
+            parse_report,
+            media_sync_report: None,
+            new_window_days: DEFAULT_NEW_WINDOW_DAYS,
             syncing: false,
-        }
+        };
+        service.rebuild_notes_index();
+        service
     }
 
     pub fn is_syncing(&self) -> bool {
@@ -62,6 +183,15 @@ impl CatalogService {
         &self.games
     }
 
+    /// Number of releases each catalog source contributed after merging.
+    pub fn release_counts_by_source(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for game in &self.all_versions {
+            *counts.entry(game.source.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
     pub fn cache_dir(&self) -> &Path {
         &self.cache_dir
     }
@@ -74,18 +204,365 @@ impl CatalogService {
         &self.notes_dir
     }
 
+    /// The user-editable file that adds and removes tags per package.
+    pub fn tag_file(&self) -> PathBuf {
+        self.cache_dir
+            .parent()
+            .unwrap_or(&self.cache_dir)
+            .join(TAG_FILE)
+    }
+
+    pub fn snapshot(&self) -> &CatalogSnapshot {
+        &self.snapshot
+    }
+
+    /// How the package's downloads and rank moved over the recorded syncs.
+    pub fn trend(&self, package_name: &str) -> Option<&PopularityTrend> {
+        self.trends.get(package_name)
+    }
+
+    /// Diagnostics from the last time a game list was parsed.
+    pub fn parse_report(&self) -> Option<&CatalogParseReport> {
+        self.parse_report.as_ref()
+    }
+
+    pub fn new_window_days(&self) -> u32 {
+        self.new_window_days
+    }
+
+    /// How many days a newly added package keeps its `is_new` flag.
+    pub fn set_new_window_days(&mut self, days: u32) {
+        self.new_window_days = days;
+        self.apply_new_flags(chrono::Utc::now().timestamp());
+    }
+
+    /// Diff the loaded catalog against the previous sync and persist the result.
+    ///
+    /// `known_packages` seeds the comparison when no snapshot exists yet, so
+    /// installs that only have the legacy package list still get a real diff.
+    pub fn record_snapshot(&mut self, known_packages: &[String]) -> Result<CatalogDiff> {
+        self.record_snapshot_at(known_packages, chrono::Utc::now().timestamp())
+    }
+
+    fn record_snapshot_at(&mut self, known_packages: &[String], now: i64) -> Result<CatalogDiff> {
+        let previous = if self.snapshot.is_baseline() && !known_packages.is_empty() {
+            CatalogSnapshot::from_known_packages(known_packages)
+        } else {
+            std::mem::take(&mut self.snapshot)
+        };
+
+        let (snapshot, diff) = previous.advance(&self.all_versions, now);
+        let samples = current_samples(&self.games, now);
+        if let Some(db) = &self.db {
+            db.save_snapshot(&snapshot, &diff)?;
+            db.save_popularity(&samples)?;
+        }
+        self.snapshot = snapshot;
+        self.popularity.record(&samples, now);
+        self.apply_new_flags(now);
+        self.apply_trends(now);
+        Ok(diff)
+    }
+
+    /// Measure the loaded catalog's downloads against the recorded history.
+    fn apply_trends(&mut self, now: i64) {
+        let samples = current_samples(&self.games, now);
+        self.trends = self.popularity.trends(&samples, now);
+    }
+
+    fn apply_new_flags(&mut self, now: i64) {
+        let snapshot = &self.snapshot;
+        let window = self.new_window_days;
+        for game in self.games.iter_mut().chain(self.all_versions.iter_mut()) {
+            game.is_new = snapshot.is_new(&game.package_name, window, now);
+        }
+    }
+
+    /// Bring the thumbnail and note caches in line with an extracted `.meta`
+    /// folder: copy new files, replace changed ones and delete files for
+    /// packages the catalog no longer lists, then record media presence.
+    pub fn sync_media(&mut self, extracted_meta: &Path) -> Result<MediaSyncReport> {
+        // Never prune against an empty catalog, e.g. after a failed parse
+        let packages: Option<HashSet<&str>> = (!self.all_versions.is_empty()).then(|| {
+            self.all_versions
+                .iter()
+                .map(|game| game.package_name.as_str())
+                .collect()
+        });
+        let report = MediaSyncReport {
+            thumbnails: sync_media_dir(
+                &extracted_meta.join("thumbnails"),
+                &self.thumbnails_dir,
+                packages.as_ref(),
+            )?,
+            notes: sync_media_dir(
+                &extracted_meta.join("notes"),
+                &self.notes_dir,
+                packages.as_ref(),
+            )?,
+        };
+        self.media_sync_report = Some(report);
+        self.refresh_media()?;
+        Ok(report)
+    }
+
+    /// Counts from the last [`sync_media`](Self::sync_media) in this session.
+    pub fn media_sync_report(&self) -> Option<MediaSyncReport> {
+        self.media_sync_report
+    }
+
+    /// Rescan the thumbnails and notes directories and record which packages have media.
+    pub fn refresh_media(&mut self) -> Result<()> {
+        let mut media: HashMap<String, MediaPresence> = HashMap::new();
+        for (dir, extension, is_thumbnail) in [
+            (&self.thumbnails_dir, "jpg", true),
+            (&self.notes_dir, "txt", false),
+        ] {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+                    continue;
+                }
+                let Some(package) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                let presence = media.entry(package.to_string()).or_default();
+                if is_thumbnail {
+                    presence.thumbnail_exists = true;
+                } else {
+                    presence.note_exists = true;
+                }
+            }
+        }
+
+        if let Some(db) = &self.db {
+            db.replace_media(&media)?;
+        }
+        self.media = media;
+        self.apply_media();
+        self.rebuild_notes_index();
+        self.apply_tags();
+        Ok(())
+    }
+
+    fn rebuild_notes_index(&mut self) {
+        let mut index = NotesIndex::default();
+        let mut note_tags = HashMap::new();
+        if let Ok(entries) = std::fs::read_dir(&self.notes_dir) {
+            for path in entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+            {
+                if path.extension().and_then(|ext| ext.to_str()) != Some("txt") {
+                    continue;
+                }
+                let Some(package) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                if let Ok(note) = std::fs::read_to_string(&path) {
+                    index.insert(package, &note);
+                    note_tags.insert(package.to_string(), tags::note_tags(&note));
+                }
+            }
+        }
+        self.notes_index = index;
+        self.note_tags = note_tags;
+    }
+
+    /// Re-read the user tag file and tag every release. A missing file adds
+    /// no tags; an invalid one is logged and ignored.
+    pub fn apply_tags(&mut self) {
+        let tag_file = self.tag_file();
+        let overrides = match std::fs::read_to_string(&tag_file) {
+            Ok(text) => TagOverrides::from_json(&text).unwrap_or_else(|e| {
+                crate::logger::log(&format!(
+                    "[CATALOG] Ignoring invalid tag file {}: {}",
+                    tag_file.display(),
+                    e
+                ));
+                TagOverrides::default()
+            }),
+            Err(_) => TagOverrides::default(),
+        };
+        for game in self.games.iter_mut().chain(self.all_versions.iter_mut()) {
+            let derived = tags::release_tags(&game.game_name, &game.release_name)
+                .into_iter()
+                .chain(
+                    self.note_tags
+                        .get(&game.package_name)
+                        .into_iter()
+                        .flatten()
+                        .copied(),
+                );
+            game.tags = overrides.apply(&game.package_name, derived);
+        }
+    }
+
+    /// Raw text of a package's release note, `None` if it has none.
+    pub fn note_text(&self, package_name: &str) -> Option<String> {
+        std::fs::read_to_string(self.notes_dir.join(format!("{package_name}.txt"))).ok()
+    }
+
+    pub fn release_notes(&self, package_name: &str) -> Option<ReleaseNotes> {
+        self.note_text(package_name)
+            .map(|note| ReleaseNotes::parse(&note))
+    }
+
+    pub fn releases(&self) -> &[Game] {
+        &self.all_versions
+    }
+
+    pub fn local_entries(&self) -> &[LocalEntry] {
+        &self.local_entries
+    }
+
+    /// Folder an imported release is installed from.
+    pub fn local_game_dir(&self, release_name: &str) -> Option<&Path> {
+        self.local_entries
+            .iter()
+            .find(|entry| entry.game.release_name == release_name)
+            .map(|entry| entry.game_dir.as_path())
+    }
+
+    /// Whether a catalog source, rather than an import, lists `release_name`.
+    pub fn lists_release(&self, release_name: &str) -> bool {
+        self.all_versions
+            .iter()
+            .any(|game| !game.is_local() && game.release_name == release_name)
+    }
+
+    /// Store imported releases, replacing any with the same release name, and
+    /// add them to the catalog.
+    pub fn add_local_entries(&mut self, entries: Vec<LocalEntry>) -> Result<usize> {
+        let entries: Vec<LocalEntry> = entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.game.source = LOCAL_SOURCE.to_string();
+                entry
+            })
+            .collect();
+        self.database()?.upsert_local_entries(&entries)?;
+
+        let count = entries.len();
+        for entry in entries {
+            self.local_entries
+                .retain(|existing| existing.game.release_name != entry.game.release_name);
+            self.local_entries.push(entry);
+        }
+        let releases = std::mem::take(&mut self.all_versions);
+        self.set_releases(releases);
+        Ok(count)
+    }
+
+    /// Returns whether an imported release with that name existed.
+    pub fn remove_local_entry(&mut self, release_name: &str) -> Result<bool> {
+        if !self.database()?.remove_local_entry(release_name)? {
+            return Ok(false);
+        }
+        self.local_entries
+            .retain(|entry| entry.game.release_name != release_name);
+        let releases = std::mem::take(&mut self.all_versions);
+        self.set_releases(releases);
+        Ok(true)
+    }
+
+    /// Packages whose release notes contain `term`, for `note:` filters.
+    pub fn note_matches(&self, term: &str) -> HashSet<String> {
+        self.notes_index.matching_packages(term)
+    }
+
+    fn apply_media(&mut self) {
+        for game in self.games.iter_mut().chain(self.all_versions.iter_mut()) {
+            let presence = self
+                .media
+                .get(&game.package_name)
+                .copied()
+                .unwrap_or_default();
+            game.thumbnail_path = self
+                .thumbnails_dir
+                .join(format!("{}.jpg", game.package_name))
+                .display()
+                .to_string();
+            game.thumbnail_exists = presence.thumbnail_exists;
+            game.note_path = self
+                .notes_dir
+                .join(format!("{}.txt", game.package_name))
+                .display()
+                .to_string();
+            game.note_exists = presence.note_exists;
+        }
+    }
+
+    pub fn annotations(&self, package_name: &str) -> Result<HashMap<String, String>> {
+        self.database()?.annotations(package_name)
+    }
+
+    pub fn set_annotation(&self, package_name: &str, key: &str, value: Option<&str>) -> Result<()> {
+        self.database()?.set_annotation(package_name, key, value)
+    }
+
+    fn database(&self) -> Result<&CatalogDb> {
+        self.db
+            .as_ref()
+            .ok_or_else(|| anyhow!("catalog database is unavailable"))
+    }
+
+    /// Parse a `VRP-GameList.txt` and store its releases in the catalog database.
     pub fn parse_game_list_file(&mut self, game_list_path: &Path) -> Result<usize> {
+        self.parse_game_list_files(game_list_path, &[])
+    }
+
+    /// Like [`parse_game_list_file`](Self::parse_game_list_file), merging in the
+    /// lists of additional sources.
+    pub fn parse_game_list_files(
+        &mut self,
+        game_list_path: &Path,
+        extra: &[SourceList],
+    ) -> Result<usize> {
         let content = std::fs::read_to_string(game_list_path)
             .with_context(|| format!("failed to read {}", game_list_path.display()))?;
-        self.games = self.parse_game_list_content(&content);
-        Ok(self.games.len())
+        let count = self.parse_source_lists(&content, extra);
+        if let Some(db) = &self.db {
+            let listed: Vec<Game> = self
+                .all_versions
+                .iter()
+                .filter(|game| !game.is_local())
+                .cloned()
+                .collect();
+            db.replace_releases(&listed)?;
+            if let Some(report) = &self.parse_report {
+                db.save_parse_report(report)?;
+            }
+        }
+        Ok(count)
     }
 
+    /// Load the catalog from the database, importing the cached
+    /// `VRP-GameList.txt` if the database has no releases yet.
     pub fn load_from_cache(&mut self) -> bool {
+        let stored = self
+            .db
+            .as_ref()
+            .and_then(|db| db.load_releases().ok())
+            .filter(|releases| !releases.is_empty());
+        if let Some(releases) = stored {
+            return self.set_releases(releases) > 0;
+        }
+
         let cached_path = self.cache_dir.join("VRP-GameList.txt");
-        self.parse_game_list_file(&cached_path)
-            .map(|count| count > 0)
-            .unwrap_or(false)
+        match self.parse_game_list_file(&cached_path) {
+            Ok(count) => count > 0,
+            Err(_) => {
+                // Imported entries don't need a synced catalog to be listed
+                if !self.local_entries.is_empty() {
+                    self.set_releases(Vec::new());
+                }
+                false
+            }
+        }
     }
 
     pub fn get_cache_age(&self) -> Option<f64> {
@@ -102,40 +579,51 @@ impl CatalogService {
     }
 
     pub fn search(&self, query: &str) -> Vec<Game> {
+        self.search_refs(query).into_iter().cloned().collect()
+    }
+
+    /// Like [`search`](Self::search), but borrows the matches so callers that
+    /// only need a page of results don't clone the whole catalog.
+    pub fn search_refs(&self, query: &str) -> Vec<&Game> {
         let query = query.trim();
         if query.is_empty() {
-            return self.games.clone();
+            return self.games.iter().collect();
         }
 
         if let Some(release_query) = query.strip_prefix("release:") {
-            let q = release_query.trim().to_lowercase();
             return self
-                .all_versions
-                .iter()
-                .filter(|game| game.release_name.to_lowercase().contains(&q))
-                .cloned()
+                .versions_index
+                .search_field(release_query, SearchField::ReleaseName)
+                .into_iter()
+                .map(|index| &self.all_versions[index])
                 .collect();
         }
 
         if let Some(pkg_query) = query.strip_prefix("pkg:") {
-            let q = pkg_query.trim().to_lowercase();
             return self
-                .all_versions
+                .versions_index
+                .search_field(pkg_query, SearchField::PackageName)
+                .into_iter()
+                .map(|index| &self.all_versions[index])
+                .collect();
+        }
+
+        if let Some(note_query) = query
+            .strip_prefix("note:")
+            .or_else(|| query.strip_prefix("notes:"))
+        {
+            let packages = self.note_matches(note_query);
+            return self
+                .games
                 .iter()
-                .filter(|game| game.package_name.to_lowercase().contains(&q))
-                .cloned()
+                .filter(|game| packages.contains(&game.package_name))
                 .collect();
         }
 
-        let query_lower = query.to_lowercase();
-        self.games
-            .iter()
-            .filter(|game| {
-                game.game_name.to_lowercase().contains(&query_lower)
-                    || game.release_name.to_lowercase().contains(&query_lower)
-                    || game.package_name.to_lowercase().contains(&query_lower)
-            })
-            .cloned()
+        self.games_index
+            .search(query)
+            .into_iter()
+            .map(|index| &self.games[index])
             .collect()
     }
 
@@ -146,10 +634,6 @@ impl CatalogService {
     }
 
     pub fn get_games_by_package(&self, package_name: &str) -> Vec<Game> {
-        fn version_sort_key(version_code: &str) -> i64 {
-            version_code.parse::<i64>().unwrap_or(0)
-        }
-
         let mut games: Vec<Game> = self
             .all_versions
             .iter()
@@ -157,9 +641,7 @@ impl CatalogService {
             .cloned()
             .collect();
 
-        games.sort_by(|left, right| {
-            version_sort_key(&right.version_code).cmp(&version_sort_key(&left.version_code))
-        });
+        games.sort_by(|a, b| compare_releases(b, a));
         games
     }
 
@@ -181,56 +663,138 @@ impl CatalogService {
     }
 
     pub fn parse_game_list_content(&mut self, content: &str) -> Vec<Game> {
-        let mut games_by_key: HashMap<(String, String), Game> = HashMap::new();
-        let mut game_key_order: Vec<(String, String)> = Vec::new();
-        let mut all_versions: Vec<Game> = Vec::new();
+        self.parse_source_lists(content, &[]);
+        self.games.clone()
+    }
 
-        // First pass: collect all games and track popularity scores per package
-        let mut popularity_scores: HashMap<String, f64> = HashMap::new();
+    /// Parse the public game list together with the lists of additional sources
+    /// and merge them into one catalog, see [`merge_sources`]. Returns the
+    /// number of games.
+    pub fn parse_source_lists(&mut self, primary: &str, extra: &[SourceList]) -> usize {
+        let (games, report) = Self::parse_rows(primary, PRIMARY_SOURCE);
+        self.parse_report = Some(report);
+
+        let mut lists = vec![(0, games)];
+        for list in extra {
+            let (games, _) = Self::parse_rows(&list.content, &list.source);
+            lists.push((list.priority, games));
+        }
+        self.set_releases(merge_sources(lists))
+    }
+
+    fn parse_rows(content: &str, source: &str) -> (Vec<Game>, CatalogParseReport) {
+        let mut all_versions: Vec<Game> = Vec::new();
+        let mut report = CatalogParseReport {
+            parsed_at: chrono::Utc::now().timestamp(),
+            ..Default::default()
+        };
+        let mut release_names: HashSet<String> = HashSet::new();
+        let mut header_schema = None;
 
         for (i, line) in content.lines().enumerate() {
+            let fields: Vec<&str> = line.trim().split(';').collect();
             if i == 0 {
+                report.header = fields.iter().map(|f| f.trim().to_string()).collect();
+                header_schema = CatalogSchema::from_header(&fields);
+                report.schema = header_schema;
+                report.schema_from_header = header_schema.is_some();
                 continue;
-            } // Skip header
+            }
             let line = line.trim();
             if line.is_empty() {
                 continue;
             }
+            let line_number = (i + 1) as u32;
+            report.total_rows += 1;
 
-            let fields: Vec<&str> = line.split(';').collect();
-            if let Some(game) = Game::from_csv_row(&fields) {
-                all_versions.push(game.clone());
-                // Track highest popularity score per package
-                if let Ok(score) = game.downloads.parse::<f64>() {
-                    let package = &game.package_name;
-                    if let Some(existing) = popularity_scores.get(package) {
-                        if score > *existing {
-                            popularity_scores.insert(package.clone(), score);
-                        }
-                    } else {
-                        popularity_scores.insert(package.clone(), score);
+            // Trust the header over the per-row guess when it names a schema
+            let detected = CatalogSchema::detect(&fields);
+            let schema = header_schema.unwrap_or(detected);
+
+            match Game::parse_row(&fields, schema) {
+                Ok(mut game) => {
+                    report.accepted_rows += 1;
+                    if schema != detected {
+                        report.schema_mismatches += 1;
+                    }
+                    report.record_schema(schema);
+                    if !game.release_name.is_empty()
+                        && !release_names.insert(game.release_name.clone())
+                    {
+                        report.duplicate_keys += 1;
                     }
+                    game.source = source.to_string();
+                    all_versions.push(game);
                 }
+                Err(reason) => report.record_rejection(line_number, reason, line),
+            }
+        }
 
-                let key = (game.package_name.clone(), game.game_name.clone());
+        report.settle_schema();
+        if report.has_problems() {
+            crate::logger::log(&format!(
+                "[CATALOG] Game list parse ({}): {}",
+                source,
+                report.summary()
+            ));
+        }
+        (all_versions, report)
+    }
 
-                // Compare versions
-                if let Some(existing) = games_by_key.get(&key) {
-                    let existing_ver = existing.version_code.parse::<i64>().unwrap_or(0);
-                    let new_ver = game.version_code.parse::<i64>().unwrap_or(0);
+    /// Swap the imported entries in `all_versions` for the current ones, skipping
+    /// any whose release name a source already lists.
+    fn with_local_entries(&self, mut all_versions: Vec<Game>) -> Vec<Game> {
+        all_versions.retain(|game| !game.is_local());
+        let listed: HashSet<&str> = all_versions
+            .iter()
+            .map(|game| game.release_name.as_str())
+            .collect();
+        let local: Vec<Game> = self
+            .local_entries
+            .iter()
+            .filter(|entry| !listed.contains(entry.game.release_name.as_str()))
+            .map(|entry| entry.game.clone())
+            .collect();
+        all_versions.extend(local);
+        all_versions
+    }
 
-                    if new_ver > existing_ver {
-                        games_by_key.insert(key, game);
-                    } else if new_ver == existing_ver {
-                        // Fallback string compare if numbers match
-                        if game.version_code > existing.version_code {
-                            games_by_key.insert(key, game);
-                        }
-                    }
+    /// Replace the in-memory catalog with `all_versions`, keeping the newest
+    /// release per game and rebuilding rankings and search indexes. Returns the
+    /// number of games.
+    fn set_releases(&mut self, all_versions: Vec<Game>) -> usize {
+        let all_versions = self.with_local_entries(all_versions);
+        let mut games_by_key: HashMap<(String, String), Game> = HashMap::new();
+        let mut game_key_order: Vec<(String, String)> = Vec::new();
+
+        // First pass: collect all games and track popularity scores per package
+        let mut popularity_scores: HashMap<String, f64> = HashMap::new();
+
+        for game in &all_versions {
+            // Track highest popularity score per package
+            let score = popularity_scores
+                .entry(game.package_name.clone())
+                .or_insert(game.downloads);
+            if game.downloads > *score {
+                *score = game.downloads;
+            }
+
+            let key = (game.package_name.clone(), game.game_name.clone());
+
+            // Compare versions. Releases arrive in source priority order, so
+            // across sources an equal version code keeps the earlier one
+            if let Some(existing) = games_by_key.get(&key) {
+                let newer = if game.source != existing.source {
+                    game.version_code > existing.version_code
                 } else {
-                    game_key_order.push(key.clone());
-                    games_by_key.insert(key, game);
+                    compare_releases(game, existing).is_gt()
+                };
+                if newer {
+                    games_by_key.insert(key, game.clone());
                 }
+            } else {
+                game_key_order.push(key.clone());
+                games_by_key.insert(key, game.clone());
             }
         }
 
@@ -260,14 +824,132 @@ impl CatalogService {
             }
         }
 
+        self.versions_index = SearchIndex::build(&all_versions);
         self.all_versions = all_versions;
-        games
+        self.games_index = SearchIndex::build(&games);
+        self.games = games;
+        self.apply_media();
+        self.apply_tags();
+        let now = chrono::Utc::now().timestamp();
+        self.apply_new_flags(now);
+        self.apply_trends(now);
+        self.games.len()
+    }
+}
+
+/// Mirror media files from `source` into `target`. Files are compared by size
+/// and mtime, falling back to their contents when only the mtime differs. With
+/// `packages`, only files named after a listed package are kept.
+fn sync_media_dir(
+    source: &Path,
+    target: &Path,
+    packages: Option<&HashSet<&str>>,
+) -> Result<MediaSyncCounts> {
+    let referenced = |path: &Path| {
+        packages.is_none_or(|packages| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| packages.contains(stem))
+        })
+    };
+    let mut counts = MediaSyncCounts::default();
+    std::fs::create_dir_all(target)
+        .with_context(|| format!("failed to create {}", target.display()))?;
+
+    // A missing source folder just means there is nothing to copy
+    for entry in std::fs::read_dir(source).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if !path.is_file() || !referenced(&path) {
+            continue;
+        }
+        let dest = target.join(entry.file_name());
+        if !dest.exists() {
+            copy_media_file(&path, &dest)?;
+            counts.added += 1;
+        } else if media_file_changed(&path, &dest)? {
+            copy_media_file(&path, &dest)?;
+            counts.updated += 1;
+        } else {
+            counts.unchanged += 1;
+        }
+    }
+
+    if packages.is_some() {
+        for entry in std::fs::read_dir(target)?.flatten() {
+            let path = entry.path();
+            if path.is_file() && !referenced(&path) {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+                counts.removed += 1;
+            }
+        }
     }
+    Ok(counts)
+}
+
+fn media_file_changed(source: &Path, dest: &Path) -> Result<bool> {
+    let source_meta = std::fs::metadata(source)?;
+    let dest_meta = std::fs::metadata(dest)?;
+    if source_meta.len() != dest_meta.len() {
+        return Ok(true);
+    }
+    let source_modified = source_meta.modified()?;
+    if source_modified == dest_meta.modified()? {
+        return Ok(false);
+    }
+
+    // Same size but a different mtime, e.g. after re-extracting the archive
+    let changed = md5::compute(std::fs::read(source)?) != md5::compute(std::fs::read(dest)?);
+    if !changed {
+        // Adopt the source mtime so the next sync can skip hashing
+        std::fs::File::options()
+            .write(true)
+            .open(dest)?
+            .set_modified(source_modified)?;
+    }
+    Ok(changed)
+}
+
+fn copy_media_file(source: &Path, dest: &Path) -> Result<()> {
+    std::fs::copy(source, dest)
+        .with_context(|| format!("failed to copy {} to {}", source.display(), dest.display()))?;
+    let modified = std::fs::metadata(source)?.modified()?;
+    std::fs::File::options()
+        .write(true)
+        .open(dest)?
+        .set_modified(modified)?;
+    Ok(())
+}
+
+/// Merge per-source releases. Sources are taken from the highest priority down,
+/// configured order breaking ties, and a release name already listed by an
+/// earlier source is dropped. Since `set_releases` keeps the earlier source's
+/// release when version codes tie across sources, the higher-priority source
+/// also wins the latest-version slot.
+fn merge_sources(mut lists: Vec<(i32, Vec<Game>)>) -> Vec<Game> {
+    if lists.len() == 1 {
+        return lists.pop().map(|(_, games)| games).unwrap_or_default();
+    }
+    lists.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut merged = Vec::new();
+    for (_, games) in lists {
+        let listed: HashSet<String> = games.iter().map(|g| g.release_name.clone()).collect();
+        merged.extend(
+            games
+                .into_iter()
+                .filter(|game| !seen.contains(&game.release_name)),
+        );
+        seen.extend(listed);
+    }
+    merged
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::game::RowRejection;
     use tempfile::tempdir;
 
     #[test]
@@ -282,7 +964,160 @@ mod tests {
         let mut service = CatalogService::new();
         let games = service.parse_game_list_content(content);
         assert_eq!(games.len(), 1);
-        assert_eq!(games[0].version_code, "11");
+        assert_eq!(games[0].version_code, 11);
+    }
+
+    #[test]
+    fn test_equal_version_codes_order_by_name_and_date() {
+        let content = "Header\n\
+            Game;Game v5+1.10;com.test;5;2023-01-01;100;0\n\
+            Game;Game v5+1.9;com.test;5;2023-02-01;100;0\n\
+            Game;Game v5+1.10 fix;com.test;5;2023-03-01;100;0";
+        let mut service = CatalogService::new();
+        let games = service.parse_game_list_content(content);
+        assert_eq!(games[0].release_name, "Game v5+1.10 fix");
+
+        let names: Vec<String> = service
+            .get_games_by_package("com.test")
+            .into_iter()
+            .map(|game| game.release_name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Game v5+1.10 fix", "Game v5+1.10", "Game v5+1.9"]
+        );
+    }
+
+    #[test]
+    fn test_parse_report_records_problems() {
+        let content =
+            "Game Name;Release Name;Package Name;Version Code;Last Updated;Size (MB);Downloads\n\
+            A;A v1+1;pkg.a;1;2023-01-01;100;5\n\
+            \n\
+            broken row\n\
+            B;B v2+1;;2;2023-01-01;100;5\n\
+            A;A v1+1;pkg.a;1;2023-01-01;100;5\n\
+            C;C v1+1;pkg.c;beta;2023-01-01;100;5\n\
+            D;D v1+1;pkg.d;3;/apk/path;1.0;5;100 MB;2023-01-01";
+        let mut service = CatalogService::new();
+        let games = service.parse_game_list_content(content);
+        assert_eq!(games.len(), 2);
+
+        let report = service.parse_report().unwrap();
+        assert_eq!(report.schema, Some(CatalogSchema::Modern));
+        assert!(report.schema_from_header);
+        assert_eq!(report.header[3], "Version Code");
+        assert_eq!(report.total_rows, 6);
+        assert_eq!(report.accepted_rows, 3);
+        assert_eq!(report.rejected_count, 3);
+        assert_eq!(report.rejected[0].line, 4);
+        assert_eq!(report.rejected[0].reason, RowRejection::TooFewFields);
+        assert_eq!(report.rejected[1].line, 5);
+        assert_eq!(report.rejected[1].reason, RowRejection::MissingPackageName);
+        assert_eq!(report.rejected[2].line, 7);
+        assert_eq!(report.rejected[2].reason, RowRejection::InvalidVersionCode);
+        assert_eq!(report.schema_mismatches, 1);
+        assert_eq!(report.duplicate_keys, 1);
+        assert_eq!(report.invalid_version_codes, 1);
+
+        // Unrecognised headers fall back to guessing each row
+        service.parse_game_list_content("Header\nName;Rel;Pkg;1");
+        let report = service.parse_report().unwrap();
+        assert!(!report.schema_from_header);
+        assert_eq!(report.schema, Some(CatalogSchema::Legacy));
+        assert!(!report.has_problems());
+    }
+
+    #[test]
+    fn test_sources_merge_by_priority() {
+        let primary = "Header\n\
+            Shared;Shared v2+1;com.shared;2;2023-01-01;100;50\n\
+            Public;Public v1+1;com.public;1;2023-01-01;100;10";
+        let mirror = SourceList {
+            source: "mirror".to_string(),
+            priority: 10,
+            content: "Header\n\
+                Shared;Shared v2+1;com.shared;2;2023-01-01;100;50\n\
+                Shared;Shared v1+1;com.shared;1;2022-01-01;100;50"
+                .to_string(),
+        };
+        let local = SourceList {
+            source: "builds".to_string(),
+            priority: -1,
+            content: "Header\n\
+                Shared;Shared v3+dev;com.shared;3;2023-02-01;100;0\n\
+                Public;Public v1+1;com.public;1;2023-01-01;100;10"
+                .to_string(),
+        };
+        let mut service = CatalogService::new();
+        let count = service.parse_source_lists(primary, &[local, mirror]);
+
+        // The identical release comes from the highest priority source only
+        let versions = service.get_games_by_package("com.shared");
+        assert_eq!(versions.len(), 3);
+        let release = service
+            .get_game_by_package_and_release("com.shared", "Shared v2+1")
+            .unwrap();
+        assert_eq!(release.source, "mirror");
+        assert_eq!(
+            service
+                .get_game_by_package_and_release("com.public", "Public v1+1")
+                .unwrap()
+                .source,
+            PRIMARY_SOURCE
+        );
+
+        // A newer build from any source is still the latest version
+        assert_eq!(count, 2);
+        let shared = service.get_game_by_package("com.shared").unwrap();
+        assert_eq!(shared.version_code, 3);
+        assert_eq!(shared.source, "builds");
+
+        let counts = service.release_counts_by_source();
+        assert_eq!(counts["mirror"], 2);
+        assert_eq!(counts[PRIMARY_SOURCE], 1);
+        assert_eq!(counts["builds"], 1);
+    }
+
+    #[test]
+    fn test_equal_version_codes_keep_the_higher_priority_source() {
+        // The primary release has the later name and date, which would win
+        // within one source
+        let primary = "Header\n\
+            Game;Game v4+1.1;com.test;4;2023-03-01;100;0";
+        let mirror = SourceList {
+            source: "mirror".to_string(),
+            priority: 10,
+            content: "Header\n\
+                Game;Game v4+1.0;com.test;4;2023-01-01;100;0"
+                .to_string(),
+        };
+        let mut service = CatalogService::new();
+        service.parse_source_lists(primary, &[mirror]);
+
+        let latest = service.get_game_by_package("com.test").unwrap();
+        assert_eq!(latest.release_name, "Game v4+1.0");
+        assert_eq!(latest.source, "mirror");
+    }
+
+    #[test]
+    fn test_search_ranks_by_relevance() {
+        let content = "Header\n\
+            Saber Duel;Saber Duel v3+0.3;com.example.saberduel;3;2023-01-01;100;0\n\
+            Beat Saber;Beat Saber v1+1.40;com.beatgames.beatsaber;1;2023-01-01;100;0\n\
+            Beat Saber;Beat Saber v2+1.41;com.beatgames.beatsaber;2;2023-01-02;100;0";
+        let mut service = CatalogService::new();
+        service.parse_game_list_content(content);
+
+        let results = service.search("beat sabr");
+        assert_eq!(results[0].package_name, "com.beatgames.beatsaber");
+
+        let results = service.search("release:v1+1.40");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].version_code, 1);
+
+        let results = service.search("pkg:beatgames");
+        assert_eq!(results.len(), 2);
     }
 
     #[test]
@@ -297,4 +1132,293 @@ mod tests {
         assert_eq!(service.games().len(), 1);
         assert_eq!(service.games()[0].package_name, "pkg.a");
     }
+
+    #[test]
+    fn test_record_snapshot_marks_new_games() {
+        let temp_dir = tempdir().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let mut service = CatalogService::with_cache_dir(temp_dir.path().to_path_buf());
+
+        service.parse_game_list_content("Header\nA;RelA;pkg.a;1;2023-01-01;10;1");
+        let diff = service.record_snapshot_at(&[], now - 60).unwrap();
+        assert!(diff.is_empty());
+
+        service.parse_game_list_content(
+            "Header\nA;RelA;pkg.a;2;2023-01-01;10;1\nB;RelB;pkg.b;1;2023-01-01;10;1",
+        );
+        let diff = service.record_snapshot_at(&[], now).unwrap();
+        assert_eq!(diff.added, vec!["pkg.b".to_string()]);
+        assert_eq!(diff.updated[0].package_name, "pkg.a");
+
+        let new_games: Vec<_> = service.games().iter().filter(|g| g.is_new).collect();
+        assert_eq!(new_games.len(), 1);
+        assert_eq!(new_games[0].package_name, "pkg.b");
+
+        // The snapshot survives a restart and keeps flagging the new package
+        let mut reloaded = CatalogService::with_cache_dir(temp_dir.path().to_path_buf());
+        reloaded.parse_game_list_content(
+            "Header\nA;RelA;pkg.a;2;2023-01-01;10;1\nB;RelB;pkg.b;1;2023-01-01;10;1",
+        );
+        assert!(reloaded.get_game_by_package("pkg.b").unwrap().is_new);
+
+        reloaded.set_new_window_days(0);
+        assert!(!reloaded.get_game_by_package("pkg.b").unwrap().is_new);
+    }
+
+    #[test]
+    fn test_legacy_snapshot_file_is_imported_once() {
+        let temp_dir = tempdir().unwrap();
+        let legacy_path = temp_dir.path().join(LEGACY_SNAPSHOT_FILE);
+        std::fs::write(
+            &legacy_path,
+            r#"{"taken_at": 100, "packages": {"pkg.a": {"game_name": "A", "version_code": "1", "first_seen": 0, "updated_at": 0}}}"#,
+        )
+        .unwrap();
+
+        let mut service = CatalogService::with_cache_dir(temp_dir.path().to_path_buf());
+        assert!(!legacy_path.exists());
+
+        // The imported snapshot is the baseline, so only the new package is added
+        service.parse_game_list_content(
+            "Header\nA;RelA;pkg.a;1;2023-01-01;10;1\nB;RelB;pkg.b;1;2023-01-01;10;1",
+        );
+        let diff = service.record_snapshot_at(&[], 200).unwrap();
+        assert_eq!(diff.added, vec!["pkg.b".to_string()]);
+        assert_eq!(diff.previous_taken_at, Some(100));
+
+        let reloaded = CatalogService::with_cache_dir(temp_dir.path().to_path_buf());
+        assert_eq!(reloaded.snapshot.taken_at, 200);
+    }
+
+    #[test]
+    fn test_tags_from_release_names_notes_and_tag_file() {
+        let temp_dir = tempdir().unwrap();
+        let mut service = CatalogService::with_cache_dir(temp_dir.path().join("cache"));
+        std::fs::write(service.notes_dir().join("pkg.a.txt"), "A co-op shooter.").unwrap();
+        std::fs::write(service.tag_file(), r#"{"pkg.a": ["-shooter", "Party"]}"#).unwrap();
+        service.refresh_media().unwrap();
+
+        service.parse_game_list_content(
+            "Header\nA;A v1+1.0 -MR;pkg.a;1;2023-01-01;10;1\nB;B v1+1.0;pkg.b;1;2023-01-01;10;1",
+        );
+        assert_eq!(
+            service.get_game_by_package("pkg.a").unwrap().tags,
+            vec!["mixed-reality", "multiplayer", "party"]
+        );
+        assert!(service
+            .get_game_by_package("pkg.b")
+            .unwrap()
+            .tags
+            .is_empty());
+
+        // The plain search takes `note:` like the library query does
+        let hits: Vec<_> = service
+            .search_refs("note:\"co-op shooter\"")
+            .iter()
+            .map(|game| game.package_name.as_str())
+            .collect();
+        assert_eq!(hits, vec!["pkg.a"]);
+        assert!(service.search_refs("note:racing").is_empty());
+
+        // An invalid tag file is ignored
+        std::fs::write(service.tag_file(), "not json").unwrap();
+        service.apply_tags();
+        assert_eq!(
+            service.get_game_by_package("pkg.a").unwrap().tags,
+            vec!["mixed-reality", "multiplayer", "shooter"]
+        );
+    }
+
+    #[test]
+    fn test_record_snapshot_tracks_popularity_trends() {
+        let temp_dir = tempdir().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let day = 24 * 60 * 60;
+        let mut service = CatalogService::with_cache_dir(temp_dir.path().to_path_buf());
+
+        service.parse_game_list_content(
+            "Header\nA;RelA;pkg.a;1;2023-01-01;10;100\nB;RelB;pkg.b;1;2023-01-01;10;50",
+        );
+        service.record_snapshot_at(&[], now - 8 * day).unwrap();
+        assert_eq!(service.trend("pkg.a").unwrap().growth_7d, None);
+
+        // The history is reloaded from the database
+        let mut reloaded = CatalogService::with_cache_dir(temp_dir.path().to_path_buf());
+        reloaded.parse_game_list_content(
+            "Header\nA;RelA;pkg.a;1;2023-01-01;10;110\nB;RelB;pkg.b;1;2023-01-01;10;150",
+        );
+        let trend = reloaded.trend("pkg.b").unwrap();
+        assert_eq!(trend.growth_7d, Some(100.0));
+        assert_eq!(trend.rank_change_7d, Some(1));
+        assert_eq!(trend.rank, 1);
+        assert_eq!(reloaded.trend("pkg.a").unwrap().growth_30d, Some(10.0));
+    }
+
+    #[test]
+    fn test_sync_media_replaces_changed_and_prunes_orphans() {
+        let temp_dir = tempdir().unwrap();
+        let meta = temp_dir.path().join(".meta");
+        let meta_thumbnails = meta.join("thumbnails");
+        let meta_notes = meta.join("notes");
+        std::fs::create_dir_all(&meta_thumbnails).unwrap();
+        std::fs::create_dir_all(&meta_notes).unwrap();
+
+        let mut service = CatalogService::with_cache_dir(temp_dir.path().join("cache"));
+        service.parse_game_list_content(
+            "Header\nA;RelA;pkg.a;1;2023-01-01;10;1\nB;RelB;pkg.b;1;2023-01-01;10;1",
+        );
+        std::fs::write(meta_thumbnails.join("pkg.a.jpg"), b"art v1").unwrap();
+        std::fs::write(meta_thumbnails.join("pkg.b.jpg"), b"art").unwrap();
+        std::fs::write(meta_thumbnails.join("pkg.gone.jpg"), b"art").unwrap();
+        std::fs::write(meta_notes.join("pkg.a.txt"), b"note").unwrap();
+
+        let report = service.sync_media(&meta).unwrap();
+        assert_eq!(report.thumbnails.added, 2);
+        assert_eq!(report.notes.added, 1);
+        assert!(!service.thumbnails_dir().join("pkg.gone.jpg").exists());
+        assert!(service.get_game_by_package("pkg.a").unwrap().note_exists);
+
+        // Revised artwork replaces the stale copy; identical files are left alone
+        std::fs::write(meta_thumbnails.join("pkg.a.jpg"), b"art v2!").unwrap();
+        std::fs::write(service.thumbnails_dir().join("pkg.old.jpg"), b"orphan").unwrap();
+        let report = service.sync_media(&meta).unwrap();
+        assert_eq!(
+            report.thumbnails,
+            MediaSyncCounts {
+                added: 0,
+                updated: 1,
+                removed: 1,
+                unchanged: 1,
+            }
+        );
+        assert_eq!(report.notes.unchanged, 1);
+        assert_eq!(
+            std::fs::read(service.thumbnails_dir().join("pkg.a.jpg")).unwrap(),
+            b"art v2!"
+        );
+        assert!(!service.thumbnails_dir().join("pkg.old.jpg").exists());
+
+        // Same size and contents but a new mtime is not an update
+        std::fs::write(meta_notes.join("pkg.a.txt"), b"note").unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(meta_notes.join("pkg.a.txt"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(service.sync_media(&meta).unwrap().notes.unchanged, 1);
+        assert_eq!(service.media_sync_report().unwrap().notes.updated, 0);
+    }
+
+    #[test]
+    fn test_sync_media_keeps_files_without_catalog() {
+        let temp_dir = tempdir().unwrap();
+        let mut service = CatalogService::with_cache_dir(temp_dir.path().join("cache"));
+        std::fs::write(service.notes_dir().join("pkg.a.txt"), b"note").unwrap();
+
+        let report = service
+            .sync_media(&temp_dir.path().join("missing"))
+            .unwrap();
+        assert_eq!(report, MediaSyncReport::default());
+        assert!(service.notes_dir().join("pkg.a.txt").exists());
+    }
+
+    #[test]
+    fn test_notes_are_indexed_and_parsed() {
+        let temp_dir = tempdir().unwrap();
+        let cache_path = temp_dir.path().to_path_buf();
+        let mut service = CatalogService::with_cache_dir(cache_path.clone());
+        std::fs::write(
+            service.notes_dir().join("pkg.a.txt"),
+            "Online multiplayer build.\nChanges:\n- Requires install.txt",
+        )
+        .unwrap();
+        assert!(service.note_matches("multiplayer").is_empty());
+
+        service.refresh_media().unwrap();
+        assert_eq!(
+            service.note_matches("multiplayer"),
+            HashSet::from(["pkg.a".to_string()])
+        );
+        let notes = service.release_notes("pkg.a").unwrap();
+        assert_eq!(notes.summary, "Online multiplayer build.");
+        assert_eq!(notes.changelog, vec!["Requires install.txt"]);
+        assert!(service.release_notes("pkg.missing").is_none());
+
+        // The index is rebuilt from the notes directory on startup
+        let reopened = CatalogService::with_cache_dir(cache_path);
+        assert_eq!(reopened.note_matches("online multiplayer").len(), 1);
+    }
+
+    #[test]
+    fn test_local_entries_survive_sync_and_reopen() {
+        let temp_dir = tempdir().unwrap();
+        let cache_path = temp_dir.path().to_path_buf();
+        let mut service = CatalogService::with_cache_dir(cache_path.clone());
+        service.parse_game_list_content("Header\nA;A v1;pkg.a;1;2023-01-01;10;1");
+
+        let entry = LocalEntry {
+            game: Game {
+                game_name: "Tool".to_string(),
+                release_name: "Tool v7".to_string(),
+                package_name: "com.team.tool".to_string(),
+                version_code: 7,
+                ..Default::default()
+            },
+            game_dir: temp_dir.path().join("Tool v7"),
+        };
+        assert_eq!(service.add_local_entries(vec![entry.clone()]).unwrap(), 1);
+        assert_eq!(service.games().len(), 2);
+        let local = service.get_game_by_package("com.team.tool").unwrap();
+        assert!(local.is_local());
+        assert_eq!(
+            service.local_game_dir("Tool v7"),
+            Some(entry.game_dir.as_path())
+        );
+        assert!(!service.lists_release("Tool v7"));
+
+        // A sync replaces the listed releases but keeps the imports
+        let list = temp_dir.path().join("list.txt");
+        std::fs::write(&list, "Header\nB;B v1;pkg.b;1;2023-01-01;10;1").unwrap();
+        service.parse_game_list_file(&list).unwrap();
+        assert!(service.get_game_by_package("com.team.tool").is_some());
+        assert!(service.get_game_by_package("pkg.a").is_none());
+        assert_eq!(service.database().unwrap().release_count().unwrap(), 1);
+
+        let mut reopened = CatalogService::with_cache_dir(cache_path);
+        assert!(reopened.load_from_cache());
+        assert_eq!(reopened.games().len(), 2);
+        assert!(reopened.remove_local_entry("Tool v7").unwrap());
+        assert!(!reopened.remove_local_entry("Tool v7").unwrap());
+        assert!(reopened.get_game_by_package("com.team.tool").is_none());
+    }
+
+    #[test]
+    fn test_load_from_cache_prefers_database() {
+        let temp_dir = tempdir().unwrap();
+        let cache_path = temp_dir.path();
+        let cache_file = cache_path.join("VRP-GameList.txt");
+        std::fs::write(&cache_file, "Header\nA;Rel;pkg.a;1;2023-01-01;10;1").unwrap();
+
+        let mut service = CatalogService::with_cache_dir(cache_path.to_path_buf());
+        std::fs::write(service.thumbnails_dir().join("pkg.a.jpg"), b"jpg").unwrap();
+        assert!(service.load_from_cache());
+        service.refresh_media().unwrap();
+        assert!(service.games()[0].thumbnail_exists);
+
+        // Once imported, the text file is no longer needed
+        std::fs::remove_file(&cache_file).unwrap();
+        let mut reopened = CatalogService::with_cache_dir(cache_path.to_path_buf());
+        assert!(reopened.load_from_cache());
+        assert_eq!(reopened.games()[0].package_name, "pkg.a");
+        assert!(reopened.games()[0].thumbnail_exists);
+        assert!(!reopened.games()[0].note_exists);
+        assert_eq!(reopened.parse_report().unwrap().accepted_rows, 1);
+
+        reopened
+            .set_annotation("pkg.a", "comment", Some("great"))
+            .unwrap();
+        assert_eq!(reopened.annotations("pkg.a").unwrap()["comment"], "great");
+    }
 }
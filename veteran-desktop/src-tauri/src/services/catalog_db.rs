@@ -0,0 +1,692 @@
+use crate::models::catalog_report::CatalogParseReport;
+use crate::models::game::Game;
+use crate::services::catalog_diff::{CatalogDiff, CatalogSnapshot, SnapshotEntry};
+use crate::services::popularity::{PopularityHistory, PopularitySample, HISTORY_DAYS};
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// How many per-sync snapshots are kept before the oldest are pruned.
+const SNAPSHOT_HISTORY: i64 = 30;
+
+/// Schema migrations, applied in order and tracked with `PRAGMA user_version`.
+/// Append new entries; never edit one that has shipped.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE releases (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        game_name TEXT NOT NULL,
+        release_name TEXT NOT NULL,
+        package_name TEXT NOT NULL,
+        version_code INTEGER NOT NULL,
+        release_apk_path TEXT NOT NULL,
+        version_name TEXT NOT NULL,
+        downloads REAL NOT NULL,
+        size_bytes INTEGER NOT NULL,
+        last_updated TEXT
+    );
+    CREATE INDEX idx_releases_package ON releases(package_name);
+    CREATE INDEX idx_releases_name ON releases(game_name COLLATE NOCASE);
+    CREATE INDEX idx_releases_updated ON releases(last_updated);
+
+    CREATE TABLE syncs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        taken_at INTEGER NOT NULL,
+        diff TEXT NOT NULL,
+        last_diff TEXT NOT NULL
+    );
+
+    CREATE TABLE sync_packages (
+        sync_id INTEGER NOT NULL REFERENCES syncs(id) ON DELETE CASCADE,
+        package_name TEXT NOT NULL,
+        game_name TEXT NOT NULL,
+        version_code INTEGER NOT NULL,
+        first_seen INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (sync_id, package_name)
+    );
+
+    CREATE TABLE media (
+        package_name TEXT PRIMARY KEY,
+        thumbnail_exists INTEGER NOT NULL,
+        note_exists INTEGER NOT NULL
+    );
+
+    CREATE TABLE annotations (
+        package_name TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (package_name, key)
+    );
+",
+    "
+    CREATE TABLE parse_report (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        parsed_at INTEGER NOT NULL,
+        report TEXT NOT NULL
+    );
+",
+    "
+    ALTER TABLE releases ADD COLUMN source TEXT NOT NULL DEFAULT 'vrp';
+    CREATE INDEX idx_releases_source ON releases(source);
+",
+    "
+    CREATE TABLE local_entries (
+        release_name TEXT PRIMARY KEY,
+        game_name TEXT NOT NULL,
+        package_name TEXT NOT NULL,
+        version_code INTEGER NOT NULL,
+        release_apk_path TEXT NOT NULL,
+        version_name TEXT NOT NULL,
+        downloads REAL NOT NULL,
+        size_bytes INTEGER NOT NULL,
+        last_updated TEXT,
+        source TEXT NOT NULL,
+        game_dir TEXT NOT NULL,
+        added_at INTEGER NOT NULL
+    );
+",
+    "
+    CREATE TABLE popularity_samples (
+        day INTEGER NOT NULL,
+        package_name TEXT NOT NULL,
+        taken_at INTEGER NOT NULL,
+        downloads REAL NOT NULL,
+        popularity_rank INTEGER NOT NULL,
+        PRIMARY KEY (day, package_name)
+    );
+",
+];
+
+const RELEASE_COLUMNS: &str = "game_name, release_name, package_name, version_code, \
+    release_apk_path, version_name, downloads, size_bytes, last_updated, source";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MediaPresence {
+    pub thumbnail_exists: bool,
+    pub note_exists: bool,
+}
+
+/// A release imported from disk, installed straight from `game_dir`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalEntry {
+    pub game: Game,
+    pub game_dir: PathBuf,
+}
+
+/// On-disk store for the catalog: every release row, per-sync snapshots,
+/// daily popularity samples, thumbnail/note presence and user annotations.
+#[derive(Debug, Clone)]
+pub struct CatalogDb {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl CatalogDb {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn =
+            Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            conn.execute_batch(migration)
+                .with_context(|| format!("catalog database migration {} failed", index + 1))?;
+            conn.pragma_update(None, "user_version", index + 1)?;
+        }
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow!("catalog database lock poisoned"))
+    }
+
+    pub fn release_count(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn()?
+            .query_row("SELECT COUNT(*) FROM releases", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Replace every release row with `releases`, keeping their order.
+    pub fn replace_releases(&self, releases: &[Game]) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM releases", [])?;
+        {
+            let mut insert = tx.prepare(&format!(
+                "INSERT INTO releases ({RELEASE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+            ))?;
+            for game in releases {
+                insert.execute(params![
+                    game.game_name,
+                    game.release_name,
+                    game.package_name,
+                    game.version_code,
+                    game.release_apk_path,
+                    game.version_name,
+                    game.downloads,
+                    game.size_bytes as i64,
+                    game.last_updated,
+                    game.source,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_releases(&self) -> Result<Vec<Game>> {
+        self.query_releases(
+            &format!("SELECT {RELEASE_COLUMNS} FROM releases ORDER BY id"),
+            [],
+        )
+    }
+
+    fn query_releases<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<Game>> {
+        let conn = self.conn()?;
+        let mut statement = conn.prepare(sql)?;
+        let games = statement
+            .query_map(params, release_from_row)?
+            .collect::<rusqlite::Result<Vec<Game>>>()?;
+        Ok(games)
+    }
+
+    /// Store `snapshot` as the newest sync and prune history beyond the retention limit.
+    pub fn save_snapshot(&self, snapshot: &CatalogSnapshot, diff: &CatalogDiff) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO syncs (taken_at, diff, last_diff) VALUES (?1, ?2, ?3)",
+            params![
+                snapshot.taken_at,
+                serde_json::to_string(diff)?,
+                serde_json::to_string(&snapshot.last_diff)?,
+            ],
+        )?;
+        let sync_id = tx.last_insert_rowid();
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO sync_packages \
+                 (sync_id, package_name, game_name, version_code, first_seen, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for (package, entry) in &snapshot.packages {
+                insert.execute(params![
+                    sync_id,
+                    package,
+                    entry.game_name,
+                    entry.version_code,
+                    entry.first_seen,
+                    entry.updated_at,
+                ])?;
+            }
+        }
+        tx.execute(
+            "DELETE FROM syncs WHERE id NOT IN (SELECT id FROM syncs ORDER BY id DESC LIMIT ?1)",
+            [SNAPSHOT_HISTORY],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The snapshot written by the most recent sync, if any.
+    pub fn load_snapshot(&self) -> Result<Option<CatalogSnapshot>> {
+        let conn = self.conn()?;
+        let latest = conn
+            .query_row(
+                "SELECT id, taken_at, last_diff FROM syncs ORDER BY id DESC LIMIT 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((sync_id, taken_at, last_diff)) = latest else {
+            return Ok(None);
+        };
+
+        let mut statement = conn.prepare(
+            "SELECT package_name, game_name, version_code, first_seen, updated_at \
+             FROM sync_packages WHERE sync_id = ?1",
+        )?;
+        let packages = statement
+            .query_map([sync_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    SnapshotEntry {
+                        game_name: row.get(1)?,
+                        version_code: row.get(2)?,
+                        first_seen: row.get(3)?,
+                        updated_at: row.get(4)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Some(CatalogSnapshot {
+            taken_at,
+            packages,
+            last_diff: serde_json::from_str(&last_diff)?,
+        }))
+    }
+
+    /// Store the popularity samples of a sync, replacing any taken earlier the
+    /// same day, and prune those older than the trend history.
+    pub fn save_popularity(&self, samples: &BTreeMap<String, PopularitySample>) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let mut newest_day = None;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO popularity_samples \
+                 (day, package_name, taken_at, downloads, popularity_rank) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for (package, sample) in samples {
+                insert.execute(params![
+                    sample.day(),
+                    package,
+                    sample.taken_at,
+                    sample.downloads,
+                    sample.rank,
+                ])?;
+                newest_day = newest_day.max(Some(sample.day()));
+            }
+        }
+        if let Some(day) = newest_day {
+            tx.execute(
+                "DELETE FROM popularity_samples WHERE day < ?1",
+                [day - HISTORY_DAYS],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_popularity(&self) -> Result<PopularityHistory> {
+        let conn = self.conn()?;
+        let mut statement = conn.prepare(
+            "SELECT package_name, taken_at, downloads, popularity_rank FROM popularity_samples",
+        )?;
+        let samples = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    PopularitySample {
+                        taken_at: row.get(1)?,
+                        downloads: row.get(2)?,
+                        rank: row.get(3)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(PopularityHistory::from_samples(samples))
+    }
+
+    pub fn replace_media(&self, media: &HashMap<String, MediaPresence>) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM media", [])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO media (package_name, thumbnail_exists, note_exists) VALUES (?1, ?2, ?3)",
+            )?;
+            for (package, presence) in media {
+                insert.execute(params![
+                    package,
+                    presence.thumbnail_exists,
+                    presence.note_exists
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Keep only the report for the most recently parsed game list.
+    pub fn save_parse_report(&self, report: &CatalogParseReport) -> Result<()> {
+        self.conn()?.execute(
+            "INSERT OR REPLACE INTO parse_report (id, parsed_at, report) VALUES (1, ?1, ?2)",
+            params![report.parsed_at, serde_json::to_string(report)?],
+        )?;
+        Ok(())
+    }
+
+    pub fn load_parse_report(&self) -> Result<Option<CatalogParseReport>> {
+        let report = self
+            .conn()?
+            .query_row("SELECT report FROM parse_report WHERE id = 1", [], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()?;
+        Ok(match report {
+            Some(json) => Some(serde_json::from_str(&json)?),
+            None => None,
+        })
+    }
+
+    pub fn load_media(&self) -> Result<HashMap<String, MediaPresence>> {
+        let conn = self.conn()?;
+        let mut statement =
+            conn.prepare("SELECT package_name, thumbnail_exists, note_exists FROM media")?;
+        let media = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    MediaPresence {
+                        thumbnail_exists: row.get(1)?,
+                        note_exists: row.get(2)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(media)
+    }
+
+    /// Insert or replace imported entries, keyed by release name.
+    pub fn upsert_local_entries(&self, entries: &[LocalEntry]) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        {
+            let mut insert = tx.prepare(&format!(
+                "INSERT OR REPLACE INTO local_entries ({RELEASE_COLUMNS}, game_dir, added_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+            ))?;
+            let now = chrono::Utc::now().timestamp();
+            for entry in entries {
+                let game = &entry.game;
+                insert.execute(params![
+                    game.game_name,
+                    game.release_name,
+                    game.package_name,
+                    game.version_code,
+                    game.release_apk_path,
+                    game.version_name,
+                    game.downloads,
+                    game.size_bytes as i64,
+                    game.last_updated,
+                    game.source,
+                    entry.game_dir.to_string_lossy(),
+                    now,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns whether an entry with that release name existed.
+    pub fn remove_local_entry(&self, release_name: &str) -> Result<bool> {
+        let removed = self.conn()?.execute(
+            "DELETE FROM local_entries WHERE release_name = ?1",
+            [release_name],
+        )?;
+        Ok(removed > 0)
+    }
+
+    pub fn load_local_entries(&self) -> Result<Vec<LocalEntry>> {
+        let conn = self.conn()?;
+        let mut statement = conn.prepare(&format!(
+            "SELECT {RELEASE_COLUMNS}, game_dir FROM local_entries ORDER BY added_at, release_name"
+        ))?;
+        let entries = statement
+            .query_map([], |row| {
+                Ok(LocalEntry {
+                    game: release_from_row(row)?,
+                    game_dir: PathBuf::from(row.get::<_, String>(10)?),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<LocalEntry>>>()?;
+        Ok(entries)
+    }
+
+    pub fn annotations(&self, package_name: &str) -> Result<HashMap<String, String>> {
+        let conn = self.conn()?;
+        let mut statement =
+            conn.prepare("SELECT key, value FROM annotations WHERE package_name = ?1")?;
+        let annotations = statement
+            .query_map([package_name], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(annotations)
+    }
+
+    /// Set an annotation, or remove it when `value` is `None`.
+    pub fn set_annotation(&self, package_name: &str, key: &str, value: Option<&str>) -> Result<()> {
+        let conn = self.conn()?;
+        match value {
+            Some(value) => conn.execute(
+                "INSERT INTO annotations (package_name, key, value, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4) \
+                 ON CONFLICT (package_name, key) DO UPDATE SET \
+                 value = excluded.value, updated_at = excluded.updated_at",
+                params![package_name, key, value, chrono::Utc::now().timestamp()],
+            )?,
+            None => conn.execute(
+                "DELETE FROM annotations WHERE package_name = ?1 AND key = ?2",
+                params![package_name, key],
+            )?,
+        };
+        Ok(())
+    }
+}
+
+fn release_from_row(row: &Row<'_>) -> rusqlite::Result<Game> {
+    Ok(Game {
+        game_name: row.get(0)?,
+        release_name: row.get(1)?,
+        package_name: row.get(2)?,
+        version_code: row.get(3)?,
+        release_apk_path: row.get(4)?,
+        version_name: row.get(5)?,
+        downloads: row.get(6)?,
+        size_bytes: row.get::<_, i64>(7)?.max(0) as u64,
+        last_updated: row.get(8)?,
+        source: row.get(9)?,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    fn release(name: &str, package: &str, version: i64, updated: &str) -> Game {
+        Game {
+            game_name: name.to_string(),
+            release_name: format!("{name} v{version}"),
+            package_name: package.to_string(),
+            version_code: version,
+            last_updated: NaiveDate::parse_from_str(updated, "%Y-%m-%d").ok(),
+            size_bytes: 100 * 1024 * 1024,
+            downloads: 12.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn releases_round_trip_and_are_replaced() {
+        let db = CatalogDb::open_in_memory().unwrap();
+        db.replace_releases(&[
+            release("Beat Saber", "com.beat", 1, "2024-01-01"),
+            release("Other", "com.other", 3, "2024-05-01"),
+            release("Beat Saber", "com.beat", 2, "2024-06-01"),
+        ])
+        .unwrap();
+
+        assert_eq!(db.release_count().unwrap(), 3);
+        let loaded = db.load_releases().unwrap();
+        assert_eq!(loaded[1].package_name, "com.other");
+        assert_eq!(loaded[1].version_code, 3);
+        assert_eq!(loaded[1].size_bytes, 100 * 1024 * 1024);
+        assert_eq!(loaded[1].downloads, 12.5);
+
+        assert_eq!(loaded[2].last_updated, NaiveDate::from_ymd_opt(2024, 6, 1));
+
+        db.replace_releases(&[release("Other", "com.other", 4, "2024-07-01")])
+            .unwrap();
+        assert_eq!(db.release_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn snapshots_persist_across_reopen_and_are_pruned() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("catalog.db");
+        let games = [release("A", "pkg.a", 1, "2024-01-01")];
+
+        {
+            let db = CatalogDb::open(&path).unwrap();
+            let mut snapshot = CatalogSnapshot::default();
+            for sync in 0..(SNAPSHOT_HISTORY + 5) {
+                let (next, diff) = snapshot.advance(&games, 100 + sync);
+                db.save_snapshot(&next, &diff).unwrap();
+                snapshot = next;
+            }
+        }
+
+        let db = CatalogDb::open(&path).unwrap();
+        let snapshot = db.load_snapshot().unwrap().unwrap();
+        assert_eq!(snapshot.taken_at, 100 + SNAPSHOT_HISTORY + 4);
+        assert_eq!(snapshot.packages["pkg.a"].version_code, 1);
+
+        let conn = db.conn().unwrap();
+        let syncs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM syncs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(syncs, SNAPSHOT_HISTORY);
+
+        let orphaned: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sync_packages WHERE sync_id NOT IN (SELECT id FROM syncs)",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(orphaned, 0);
+    }
+
+    #[test]
+    fn popularity_keeps_one_sample_per_day() {
+        let db = CatalogDb::open_in_memory().unwrap();
+        let day = 24 * 60 * 60;
+        for (taken_at, downloads) in [(0, 1.0), (10 * day, 5.0), (10 * day + 60, 7.0)] {
+            let samples = BTreeMap::from([(
+                "pkg.a".to_string(),
+                PopularitySample {
+                    taken_at,
+                    downloads,
+                    rank: 1,
+                },
+            )]);
+            db.save_popularity(&samples).unwrap();
+        }
+        let count = |db: &CatalogDb| -> i64 {
+            db.conn()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM popularity_samples", [], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        };
+        assert_eq!(count(&db), 2);
+        assert!(!db.load_popularity().unwrap().is_empty());
+
+        let samples = BTreeMap::from([(
+            "pkg.b".to_string(),
+            PopularitySample {
+                taken_at: (HISTORY_DAYS + 5) * day,
+                downloads: 1.0,
+                rank: 1,
+            },
+        )]);
+        db.save_popularity(&samples).unwrap();
+        // Only the day-10 sample of pkg.a is recent enough to keep
+        assert_eq!(count(&db), 2);
+    }
+
+    #[test]
+    fn media_and_annotations() {
+        let db = CatalogDb::open_in_memory().unwrap();
+        let media = HashMap::from([(
+            "pkg.a".to_string(),
+            MediaPresence {
+                thumbnail_exists: true,
+                note_exists: false,
+            },
+        )]);
+        db.replace_media(&media).unwrap();
+        assert_eq!(db.load_media().unwrap(), media);
+
+        db.set_annotation("pkg.a", "comment", Some("needs 2 controllers"))
+            .unwrap();
+        db.set_annotation("pkg.a", "comment", Some("co-op only"))
+            .unwrap();
+        assert_eq!(db.annotations("pkg.a").unwrap()["comment"], "co-op only");
+
+        db.set_annotation("pkg.a", "comment", None).unwrap();
+        assert!(db.annotations("pkg.a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_report_keeps_latest() {
+        let db = CatalogDb::open_in_memory().unwrap();
+        assert_eq!(db.load_parse_report().unwrap(), None);
+
+        for parsed_at in [100, 200] {
+            let report = CatalogParseReport {
+                parsed_at,
+                duplicate_keys: 2,
+                ..Default::default()
+            };
+            db.save_parse_report(&report).unwrap();
+        }
+        let report = db.load_parse_report().unwrap().unwrap();
+        assert_eq!(report.parsed_at, 200);
+        assert_eq!(report.duplicate_keys, 2);
+    }
+
+    #[test]
+    fn local_entries_upsert_and_remove() {
+        let db = CatalogDb::open_in_memory().unwrap();
+        let mut entry = LocalEntry {
+            game: Game {
+                source: "local".to_string(),
+                ..release("In-house", "com.team.inhouse", 3, "2024-05-01")
+            },
+            game_dir: PathBuf::from("/builds/inhouse"),
+        };
+        db.upsert_local_entries(std::slice::from_ref(&entry))
+            .unwrap();
+        entry.game.version_name = "3.0-rc1".to_string();
+        db.upsert_local_entries(std::slice::from_ref(&entry))
+            .unwrap();
+
+        assert_eq!(db.load_local_entries().unwrap(), vec![entry.clone()]);
+        // Imported entries are kept apart from the synced releases
+        assert_eq!(db.release_count().unwrap(), 0);
+
+        assert!(db.remove_local_entry(&entry.game.release_name).unwrap());
+        assert!(!db.remove_local_entry(&entry.game.release_name).unwrap());
+        assert!(db.load_local_entries().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,363 @@
+use crate::models::game::Game;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub game_name: String,
+    /// 0 when unknown (seeded from the legacy package list)
+    pub version_code: i64,
+    /// Unix seconds of the sync that first saw the package, 0 for the initial baseline
+    pub first_seen: i64,
+    /// Unix seconds of the sync that saw the current version code
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionBump {
+    pub package_name: String,
+    pub from: i64,
+    pub to: i64,
+}
+
+/// What changed between two consecutive catalog syncs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatalogDiff {
+    /// When the previous snapshot was taken, `None` if this was the first sync
+    pub previous_taken_at: Option<i64>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub updated: Vec<VersionBump>,
+}
+
+impl CatalogDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+
+    /// Whether `package` appeared or moved to a higher version code.
+    pub fn is_added_or_updated(&self, package: &str) -> bool {
+        self.added.iter().any(|added| added == package)
+            || self.updated.iter().any(|bump| bump.package_name == package)
+    }
+}
+
+/// Per-package state of the catalog as of the last sync, persisted between runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CatalogSnapshot {
+    pub taken_at: i64,
+    pub packages: BTreeMap<String, SnapshotEntry>,
+    /// The most recent sync that actually changed something
+    pub last_diff: CatalogDiff,
+}
+
+impl CatalogSnapshot {
+    /// Build a baseline from a bare package list (the legacy `known_catalog_packages`
+    /// setting). Versions are unknown, so only additions and removals can be diffed.
+    pub fn from_known_packages(packages: &[String]) -> Self {
+        Self {
+            taken_at: 0,
+            packages: packages
+                .iter()
+                .map(|package| (package.clone(), SnapshotEntry::default()))
+                .collect(),
+            last_diff: CatalogDiff::default(),
+        }
+    }
+
+    /// Read the `catalog_snapshot.json` written before the catalog database,
+    /// whose version codes were kept as strings. Codes that aren't integers
+    /// become 0 (unknown), and bumps between them are dropped.
+    pub fn from_legacy_json(content: &str) -> serde_json::Result<Self> {
+        let legacy: LegacySnapshot = serde_json::from_str(content)?;
+        Ok(Self {
+            taken_at: legacy.taken_at,
+            packages: legacy
+                .packages
+                .into_iter()
+                .map(|(package, entry)| {
+                    let entry = SnapshotEntry {
+                        game_name: entry.game_name,
+                        version_code: legacy_version_code(&entry.version_code).unwrap_or(0),
+                        first_seen: entry.first_seen,
+                        updated_at: entry.updated_at,
+                    };
+                    (package, entry)
+                })
+                .collect(),
+            last_diff: CatalogDiff {
+                previous_taken_at: legacy.last_diff.previous_taken_at,
+                added: legacy.last_diff.added,
+                removed: legacy.last_diff.removed,
+                updated: legacy
+                    .last_diff
+                    .updated
+                    .into_iter()
+                    .filter_map(|bump| {
+                        Some(VersionBump {
+                            from: legacy_version_code(&bump.from)?,
+                            to: legacy_version_code(&bump.to)?,
+                            package_name: bump.package_name,
+                        })
+                    })
+                    .collect(),
+            },
+        })
+    }
+
+    pub fn is_baseline(&self) -> bool {
+        self.taken_at == 0 && self.packages.is_empty()
+    }
+
+    /// Diff `games` against this snapshot and return the snapshot for the new sync
+    /// together with what changed.
+    ///
+    /// Packages seen for the first time get `first_seen = now`, except on the very
+    /// first sync where everything is treated as already known.
+    pub fn advance(&self, games: &[Game], now: i64) -> (Self, CatalogDiff) {
+        let current = latest_by_package(games);
+        let first_sync = self.is_baseline();
+        let mut packages = BTreeMap::new();
+        let mut diff = CatalogDiff {
+            previous_taken_at: (self.taken_at > 0).then_some(self.taken_at),
+            ..Default::default()
+        };
+
+        for (package, game) in current {
+            let entry = match self.packages.get(package) {
+                Some(previous) => {
+                    let bumped =
+                        previous.version_code > 0 && game.version_code > previous.version_code;
+                    if bumped {
+                        diff.updated.push(VersionBump {
+                            package_name: package.to_string(),
+                            from: previous.version_code,
+                            to: game.version_code,
+                        });
+                    }
+                    SnapshotEntry {
+                        game_name: game.game_name.clone(),
+                        version_code: game.version_code,
+                        first_seen: previous.first_seen,
+                        updated_at: if bumped { now } else { previous.updated_at },
+                    }
+                }
+                None => {
+                    let first_seen = if first_sync {
+                        0
+                    } else {
+                        diff.added.push(package.to_string());
+                        now
+                    };
+                    SnapshotEntry {
+                        game_name: game.game_name.clone(),
+                        version_code: game.version_code,
+                        first_seen,
+                        updated_at: first_seen,
+                    }
+                }
+            };
+            packages.insert(package.to_string(), entry);
+        }
+
+        diff.removed = self
+            .packages
+            .keys()
+            .filter(|package| !packages.contains_key(*package))
+            .cloned()
+            .collect();
+
+        let last_diff = if diff.is_empty() {
+            self.last_diff.clone()
+        } else {
+            diff.clone()
+        };
+        let snapshot = Self {
+            taken_at: now,
+            packages,
+            last_diff,
+        };
+        (snapshot, diff)
+    }
+
+    /// Whether `package` first appeared within the last `window_days` days.
+    pub fn is_new(&self, package: &str, window_days: u32, now: i64) -> bool {
+        self.packages.get(package).is_some_and(|entry| {
+            entry.first_seen > 0
+                && now - entry.first_seen < i64::from(window_days) * SECONDS_PER_DAY
+        })
+    }
+
+    /// Whether `package` got a new version within the last `window_days` days.
+    pub fn is_recently_updated(&self, package: &str, window_days: u32, now: i64) -> bool {
+        self.packages.get(package).is_some_and(|entry| {
+            entry.updated_at > entry.first_seen
+                && now - entry.updated_at < i64::from(window_days) * SECONDS_PER_DAY
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacySnapshot {
+    taken_at: i64,
+    packages: BTreeMap<String, LegacyEntry>,
+    #[serde(default)]
+    last_diff: LegacyDiff,
+}
+
+#[derive(Deserialize)]
+struct LegacyEntry {
+    game_name: String,
+    version_code: serde_json::Value,
+    first_seen: i64,
+    updated_at: i64,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct LegacyDiff {
+    previous_taken_at: Option<i64>,
+    added: Vec<String>,
+    removed: Vec<String>,
+    updated: Vec<LegacyBump>,
+}
+
+#[derive(Deserialize)]
+struct LegacyBump {
+    package_name: String,
+    from: serde_json::Value,
+    to: serde_json::Value,
+}
+
+fn legacy_version_code(value: &serde_json::Value) -> Option<i64> {
+    match value {
+        serde_json::Value::String(code) => code.trim().parse().ok(),
+        value => value.as_i64(),
+    }
+}
+
+fn latest_by_package(games: &[Game]) -> BTreeMap<&str, &Game> {
+    let mut latest: BTreeMap<&str, &Game> = BTreeMap::new();
+    for game in games {
+        match latest.get(game.package_name.as_str()) {
+            Some(existing) if game.version_code <= existing.version_code => {}
+            _ => {
+                latest.insert(&game.package_name, game);
+            }
+        }
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn game(package: &str, version: i64) -> Game {
+        Game {
+            game_name: package.to_uppercase(),
+            package_name: package.to_string(),
+            version_code: version,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn legacy_json_snapshot_converts_string_version_codes() {
+        let legacy = r#"{
+            "taken_at": 200,
+            "packages": {
+                "a": {"game_name": "A", "version_code": "7", "first_seen": 0, "updated_at": 150},
+                "b": {"game_name": "B", "version_code": "", "first_seen": 150, "updated_at": 150}
+            },
+            "last_diff": {
+                "previous_taken_at": 100,
+                "added": ["b"],
+                "removed": [],
+                "updated": [
+                    {"package_name": "a", "from": "6", "to": "7"},
+                    {"package_name": "b", "from": "", "to": "1"}
+                ]
+            }
+        }"#;
+
+        let snapshot = CatalogSnapshot::from_legacy_json(legacy).unwrap();
+        assert_eq!(snapshot.taken_at, 200);
+        assert_eq!(snapshot.packages["a"].version_code, 7);
+        assert_eq!(snapshot.packages["b"].version_code, 0);
+        assert!(snapshot.is_new("b", 7, 200));
+        assert_eq!(snapshot.last_diff.added, vec!["b".to_string()]);
+        assert_eq!(
+            snapshot.last_diff.updated,
+            vec![VersionBump {
+                package_name: "a".to_string(),
+                from: 6,
+                to: 7,
+            }]
+        );
+    }
+
+    #[test]
+    fn first_sync_is_a_baseline() {
+        let (snapshot, diff) =
+            CatalogSnapshot::default().advance(&[game("a", 1), game("b", 1)], 100);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.previous_taken_at, None);
+        assert!(!snapshot.is_new("a", 7, 100));
+    }
+
+    #[test]
+    fn diff_records_added_removed_and_bumped() {
+        let (first, _) = CatalogSnapshot::default().advance(&[game("a", 1), game("b", 5)], 100);
+        let (second, diff) =
+            first.advance(&[game("b", 7), game("c", 1), game("b", 6)], 100 + DAY);
+
+        assert_eq!(diff.previous_taken_at, Some(100));
+        assert_eq!(diff.added, vec!["c".to_string()]);
+        assert_eq!(diff.removed, vec!["a".to_string()]);
+        assert_eq!(
+            diff.updated,
+            vec![VersionBump {
+                package_name: "b".to_string(),
+                from: 5,
+                to: 7,
+            }]
+        );
+        assert_eq!(second.packages["b"].updated_at, 100 + DAY);
+        assert_eq!(second.last_diff, diff);
+        assert!(second.is_recently_updated("b", 7, 100 + 7 * DAY));
+        assert!(!second.is_recently_updated("b", 7, 100 + 8 * DAY));
+        // Being added isn't an update
+        assert!(!second.is_recently_updated("c", 7, 100 + DAY));
+    }
+
+    #[test]
+    fn new_window_expires() {
+        let (first, _) = CatalogSnapshot::default().advance(&[game("a", 1)], 100);
+        let (second, _) = first.advance(&[game("a", 1), game("b", 1)], 1000);
+        let (third, diff) = second.advance(&[game("a", 1), game("b", 1)], 1000 + DAY);
+
+        assert!(diff.is_empty());
+        assert_eq!(third.last_diff.added, vec!["b".to_string()]);
+        assert_eq!(third.packages["b"].first_seen, 1000);
+        assert!(third.is_new("b", 7, 1000 + 6 * DAY));
+        assert!(!third.is_new("b", 7, 1000 + 7 * DAY));
+        assert!(!third.is_new("b", 0, 1000));
+    }
+
+    #[test]
+    fn known_packages_seed_additions() {
+        let legacy = CatalogSnapshot::from_known_packages(&["a".to_string()]);
+        let (snapshot, diff) = legacy.advance(&[game("a", 3), game("b", 1)], 100);
+
+        assert_eq!(diff.added, vec!["b".to_string()]);
+        assert!(diff.updated.is_empty());
+        assert!(snapshot.is_new("b", 7, 100));
+    }
+}
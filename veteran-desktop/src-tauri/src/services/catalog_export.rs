@@ -0,0 +1,293 @@
+use crate::models::config::LOCAL_SOURCE;
+use crate::models::game::Game;
+use crate::services::catalog_db::LocalEntry;
+use crate::services::install::is_installable_dir;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+
+    /// The format named by the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .ok_or_else(|| anyhow!("cannot tell the format of {}", path.display()))?;
+        match extension.as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            other => bail!("unsupported format '.{other}', expected .json or .csv"),
+        }
+    }
+}
+
+/// One release as written by an export and read back by an import. `path` is
+/// only set for local entries: the folder holding the APK or install.txt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatalogRecord {
+    pub game_name: String,
+    pub release_name: String,
+    pub package_name: String,
+    pub version_code: i64,
+    pub version_name: String,
+    pub size_bytes: u64,
+    /// `YYYY-MM-DD`, empty when unknown
+    pub last_updated: String,
+    pub downloads: f64,
+    pub source: String,
+    pub path: String,
+}
+
+impl CatalogRecord {
+    pub fn from_game(game: &Game, game_dir: Option<&Path>) -> Self {
+        Self {
+            game_name: game.game_name.clone(),
+            release_name: game.release_name.clone(),
+            package_name: game.package_name.clone(),
+            version_code: game.version_code,
+            version_name: game.version_name.clone(),
+            size_bytes: game.size_bytes,
+            last_updated: game
+                .last_updated
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            downloads: game.downloads,
+            source: game.source.clone(),
+            path: game_dir
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Turn an imported record into a local entry. A relative `path` is
+    /// resolved against `base_dir`; names, size and date left empty are filled
+    /// in from the folder.
+    pub fn into_local_entry(self, base_dir: &Path) -> Result<LocalEntry, String> {
+        let package_name = self.package_name.trim().to_string();
+        if package_name.is_empty() {
+            return Err("missing package_name".to_string());
+        }
+        if self.path.trim().is_empty() {
+            return Err("missing path to the game folder".to_string());
+        }
+        let game_dir = base_dir.join(self.path.trim());
+        if !game_dir.is_dir() {
+            return Err(format!("{} is not a folder", game_dir.display()));
+        }
+        if !is_installable_dir(&game_dir) {
+            return Err(format!("{} has no APK or install.txt", game_dir.display()));
+        }
+
+        let last_updated = if self.last_updated.trim().is_empty() {
+            modified_date(&game_dir)
+        } else {
+            Some(
+                NaiveDate::parse_from_str(self.last_updated.trim(), "%Y-%m-%d")
+                    .map_err(|_| format!("invalid last_updated '{}'", self.last_updated))?,
+            )
+        };
+        let release_name = match self.release_name.trim() {
+            "" => game_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| package_name.clone()),
+            name => name.to_string(),
+        };
+        let game_name = match self.game_name.trim() {
+            "" => release_name.clone(),
+            name => name.to_string(),
+        };
+        let size_bytes = match self.size_bytes {
+            0 => dir_size(&game_dir),
+            size => size,
+        };
+
+        Ok(LocalEntry {
+            game: Game {
+                game_name,
+                release_name,
+                package_name,
+                version_code: self.version_code,
+                version_name: self.version_name,
+                size_bytes,
+                last_updated,
+                downloads: self.downloads,
+                source: LOCAL_SOURCE.to_string(),
+                ..Default::default()
+            },
+            game_dir,
+        })
+    }
+}
+
+pub fn write_records(path: &Path, format: ExportFormat, records: &[CatalogRecord]) -> Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    match format {
+        ExportFormat::Json => {
+            let json = serde_json::to_string_pretty(records)?;
+            std::fs::write(path, json)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_path(path)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            for record in records {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Read records written by [`write_records`], or a hand-written file with a
+/// subset of the columns. The format is taken from the file extension.
+pub fn read_records(path: &Path) -> Result<Vec<CatalogRecord>> {
+    match ExportFormat::from_path(path)? {
+        ExportFormat::Json => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("{} is not a JSON list of entries", path.display()))
+        }
+        ExportFormat::Csv => {
+            let mut reader = csv::Reader::from_path(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            reader
+                .deserialize()
+                .enumerate()
+                // Line 1 is the header
+                .map(|(i, row)| row.with_context(|| format!("line {}", i + 2)))
+                .collect()
+        }
+    }
+}
+
+fn modified_date(path: &Path) -> Option<NaiveDate> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Utc>::from(modified).date_naive())
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending: Vec<PathBuf> = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => pending.push(entry.path()),
+                Ok(metadata) => total += metadata.len(),
+                Err(_) => {}
+            }
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn record(release: &str) -> CatalogRecord {
+        CatalogRecord {
+            game_name: "Beat Saber".to_string(),
+            release_name: release.to_string(),
+            package_name: "com.beatgames.beatsaber".to_string(),
+            version_code: 1400,
+            version_name: "1.40".to_string(),
+            size_bytes: 500 * 1024 * 1024,
+            last_updated: "2024-07-01".to_string(),
+            downloads: 12.5,
+            source: "vrp".to_string(),
+            path: String::new(),
+        }
+    }
+
+    #[test]
+    fn format_follows_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("list.CSV")).unwrap(),
+            ExportFormat::Csv
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out/list.json")).unwrap(),
+            ExportFormat::Json
+        );
+        assert!(ExportFormat::from_path(Path::new("list")).is_err());
+        assert!(ExportFormat::from_path(Path::new("list.xml")).is_err());
+    }
+
+    #[test]
+    fn records_round_trip_through_both_formats() {
+        let temp_dir = tempdir().unwrap();
+        let records = vec![
+            record("Beat Saber v1400+1.40"),
+            record("Beat Saber v1300+1.30"),
+        ];
+        for name in ["list.json", "list.csv"] {
+            let path = temp_dir.path().join("out").join(name);
+            let format = ExportFormat::from_path(&path).unwrap();
+            write_records(&path, format, &records).unwrap();
+            assert_eq!(read_records(&path).unwrap(), records);
+        }
+
+        // Hand-written lists may leave columns out
+        let partial = temp_dir.path().join("partial.csv");
+        std::fs::write(&partial, "package_name,path\ncom.team.tool,builds/tool\n").unwrap();
+        let read = read_records(&partial).unwrap();
+        assert_eq!(read[0].package_name, "com.team.tool");
+        assert_eq!(read[0].version_code, 0);
+    }
+
+    #[test]
+    fn local_entries_are_validated_and_filled_in() {
+        let temp_dir = tempdir().unwrap();
+        let game_dir = temp_dir.path().join("Team Tool v7");
+        std::fs::create_dir_all(&game_dir).unwrap();
+
+        let local = CatalogRecord {
+            package_name: "com.team.tool".to_string(),
+            path: "Team Tool v7".to_string(),
+            ..Default::default()
+        };
+        assert!(local
+            .clone()
+            .into_local_entry(temp_dir.path())
+            .unwrap_err()
+            .contains("no APK"));
+
+        std::fs::write(game_dir.join("tool.apk"), b"apk").unwrap();
+        let entry = local.into_local_entry(temp_dir.path()).unwrap();
+        assert_eq!(entry.game_dir, game_dir);
+        assert_eq!(entry.game.release_name, "Team Tool v7");
+        assert_eq!(entry.game.game_name, "Team Tool v7");
+        assert_eq!(entry.game.size_bytes, 3);
+        assert!(entry.game.is_local());
+        assert!(entry.game.last_updated.is_some());
+
+        let missing = record("x").into_local_entry(temp_dir.path());
+        assert_eq!(missing.unwrap_err(), "missing path to the game folder");
+    }
+}
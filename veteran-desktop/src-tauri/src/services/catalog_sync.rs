@@ -0,0 +1,282 @@
+use crate::models::catalog_report::{CatalogSyncSummary, PhaseTiming, SyncOutcome, SyncPhase};
+use crate::models::settings::Settings;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+pub const CANCELLED_MESSAGE: &str = "Catalog sync cancelled";
+
+const RUNNING: u8 = 0;
+const CANCELLED: u8 = 1;
+/// The new catalog was committed, so the sync can no longer be cancelled
+const COMMITTED: u8 = 2;
+
+/// What a sync should do with the cached game list before going online.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheDecision {
+    /// The cache is younger than the TTL, or offline mode accepts its age
+    UseCache,
+    /// Stat the remote `meta.7z` and download it if it changed
+    CheckRemote,
+    /// Offline mode, and the cache is missing or older than the max staleness
+    TooStale,
+}
+
+/// Decide from the age of the cached game list, in hours, whether a sync needs
+/// the network. Offline mode never does, even when forced.
+pub fn cache_decision(age_hours: Option<f64>, settings: &Settings, force: bool) -> CacheDecision {
+    if settings.offline_mode {
+        let max = settings.catalog_max_staleness_hours;
+        return match age_hours {
+            Some(age) if max <= 0.0 || age <= max => CacheDecision::UseCache,
+            _ => CacheDecision::TooStale,
+        };
+    }
+    match age_hours {
+        Some(age) if !force && age < settings.catalog_cache_ttl_hours => CacheDecision::UseCache,
+        _ => CacheDecision::CheckRemote,
+    }
+}
+
+/// What other commands need to know about the sync that is running.
+#[derive(Debug, Clone)]
+pub struct SyncHandle {
+    pub operation_id: String,
+    state: Arc<AtomicU8>,
+}
+
+impl SyncHandle {
+    /// Ask the sync to stop. Returns false once it has committed the new
+    /// catalog and runs to completion regardless.
+    pub fn cancel(&self) -> bool {
+        match self
+            .state
+            .compare_exchange(RUNNING, CANCELLED, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            Ok(_) => true,
+            Err(state) => state == CANCELLED,
+        }
+    }
+}
+
+/// Progress of one catalog sync: the running phase, the time spent in each
+/// finished one, and whether cancellation was requested.
+#[derive(Debug)]
+pub struct SyncOperation {
+    id: String,
+    state: Arc<AtomicU8>,
+    started: Instant,
+    current: Option<(SyncPhase, Instant)>,
+    phases: Vec<PhaseTiming>,
+    bytes_downloaded: u64,
+    metadata_unchanged: bool,
+}
+
+impl Default for SyncOperation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncOperation {
+    pub fn new() -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            state: Arc::new(AtomicU8::new(RUNNING)),
+            started: Instant::now(),
+            current: None,
+            phases: Vec::new(),
+            bytes_downloaded: 0,
+            metadata_unchanged: false,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Handle whose `cancel` stops the operation at the next phase boundary.
+    pub fn handle(&self) -> SyncHandle {
+        SyncHandle {
+            operation_id: self.id.clone(),
+            state: self.state.clone(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == CANCELLED
+    }
+
+    /// Mark the point of no return: from here on the sync changes the stored
+    /// catalog, so cancelling it would leave it half applied.
+    pub fn commit(&mut self) -> Result<(), String> {
+        match self
+            .state
+            .compare_exchange(RUNNING, COMMITTED, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            Err(CANCELLED) => Err(CANCELLED_MESSAGE.to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn current_phase(&self) -> Option<SyncPhase> {
+        self.current.map(|(phase, _)| phase)
+    }
+
+    /// Close the running phase and start `phase`, unless the sync was cancelled.
+    pub fn enter(&mut self, phase: SyncPhase) -> Result<(), String> {
+        self.close_phase();
+        if self.is_cancelled() {
+            return Err(CANCELLED_MESSAGE.to_string());
+        }
+        self.current = Some((phase, Instant::now()));
+        Ok(())
+    }
+
+    pub fn record_bytes(&mut self, bytes: u64) {
+        self.bytes_downloaded = self.bytes_downloaded.max(bytes);
+    }
+
+    /// The remote `meta.7z` matched the local copy, so it was not downloaded.
+    pub fn mark_metadata_unchanged(&mut self) {
+        self.metadata_unchanged = true;
+    }
+
+    pub fn finish(mut self, error: Option<String>) -> CatalogSyncSummary {
+        self.close_phase();
+        let outcome = match (&error, self.is_cancelled()) {
+            (None, _) => SyncOutcome::Succeeded,
+            (Some(_), true) => SyncOutcome::Cancelled,
+            (Some(_), false) => SyncOutcome::Failed,
+        };
+        CatalogSyncSummary {
+            operation_id: self.id,
+            outcome,
+            phases: self.phases,
+            total_ms: millis(self.started.elapsed()),
+            bytes_downloaded: self.bytes_downloaded,
+            metadata_unchanged: self.metadata_unchanged,
+            error,
+        }
+    }
+
+    fn close_phase(&mut self) {
+        if let Some((phase, started)) = self.current.take() {
+            self.phases.push(PhaseTiming {
+                phase,
+                duration_ms: millis(started.elapsed()),
+            });
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_phases_in_order() {
+        let mut operation = SyncOperation::new();
+        operation.enter(SyncPhase::ConfigFetch).unwrap();
+        operation.enter(SyncPhase::MetadataDownload).unwrap();
+        operation.record_bytes(2048);
+        operation.record_bytes(1024);
+        assert_eq!(operation.current_phase(), Some(SyncPhase::MetadataDownload));
+
+        let id = operation.id().to_string();
+        let summary = operation.finish(None);
+        assert_eq!(summary.operation_id, id);
+        assert_eq!(summary.outcome, SyncOutcome::Succeeded);
+        let phases: Vec<SyncPhase> = summary.phases.iter().map(|t| t.phase).collect();
+        assert_eq!(
+            phases,
+            vec![SyncPhase::ConfigFetch, SyncPhase::MetadataDownload]
+        );
+        assert_eq!(summary.bytes_downloaded, 2048);
+        assert!(summary.summary().starts_with("Catalog sync finished"));
+    }
+
+    #[test]
+    fn cache_decision_follows_ttl_and_offline_staleness() {
+        let mut settings = Settings::default();
+        assert_eq!(
+            cache_decision(Some(1.0), &settings, false),
+            CacheDecision::UseCache
+        );
+        assert_eq!(
+            cache_decision(Some(1.0), &settings, true),
+            CacheDecision::CheckRemote
+        );
+        assert_eq!(
+            cache_decision(Some(5.0), &settings, false),
+            CacheDecision::CheckRemote
+        );
+        assert_eq!(
+            cache_decision(None, &settings, false),
+            CacheDecision::CheckRemote
+        );
+        settings.catalog_cache_ttl_hours = 0.0;
+        assert_eq!(
+            cache_decision(Some(0.1), &settings, false),
+            CacheDecision::CheckRemote
+        );
+
+        settings.offline_mode = true;
+        settings.catalog_max_staleness_hours = 48.0;
+        assert_eq!(
+            cache_decision(Some(30.0), &settings, true),
+            CacheDecision::UseCache
+        );
+        assert_eq!(
+            cache_decision(Some(50.0), &settings, false),
+            CacheDecision::TooStale
+        );
+        assert_eq!(
+            cache_decision(None, &settings, false),
+            CacheDecision::TooStale
+        );
+        settings.catalog_max_staleness_hours = 0.0;
+        assert_eq!(
+            cache_decision(Some(5000.0), &settings, false),
+            CacheDecision::UseCache
+        );
+    }
+
+    #[test]
+    fn cancellation_stops_at_the_next_phase() {
+        let mut operation = SyncOperation::new();
+        operation.enter(SyncPhase::ConfigFetch).unwrap();
+        operation.handle().cancel();
+
+        let error = operation.enter(SyncPhase::RemoteCheck).unwrap_err();
+        assert_eq!(error, CANCELLED_MESSAGE);
+        let summary = operation.finish(Some(error));
+        assert_eq!(summary.outcome, SyncOutcome::Cancelled);
+        // The interrupted phase is still timed
+        assert_eq!(summary.phases.len(), 1);
+
+        let failed = SyncOperation::new().finish(Some("offline".to_string()));
+        assert_eq!(failed.outcome, SyncOutcome::Failed);
+    }
+
+    #[test]
+    fn committed_sync_ignores_cancellation() {
+        let mut operation = SyncOperation::new();
+        let handle = operation.handle();
+        operation.enter(SyncPhase::Parse).unwrap();
+        operation.commit().unwrap();
+
+        assert!(!handle.cancel());
+        operation.enter(SyncPhase::MediaCopy).unwrap();
+        assert_eq!(operation.finish(None).outcome, SyncOutcome::Succeeded);
+
+        let mut cancelled = SyncOperation::new();
+        assert!(cancelled.handle().cancel());
+        assert_eq!(cancelled.commit().unwrap_err(), CANCELLED_MESSAGE);
+    }
+}
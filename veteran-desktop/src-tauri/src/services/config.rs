@@ -1,15 +1,76 @@
-use crate::models::config::PublicConfig;
-use anyhow::Result;
+use crate::models::config::{ConfigOrigin, ConfigSourceHealth, ConfigSourceKind, PublicConfig};
+use crate::services::secret_store::SecretStore;
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+use zeroize::Zeroizing;
 
 pub const CONFIG_URLS: &[&str] = &[
     "https://raw.githubusercontent.com/vrpyou/quest/main/vrp-public.json",
     "https://vrpirates.wiki/downloads/vrp-public.json",
 ];
 
+/// URL or path of a config that replaces the built-in URLs, taking precedence
+/// over `Settings::config_source`.
+pub const CONFIG_SOURCE_ENV: &str = "VETERAN_CONFIG_SOURCE";
+
+const CACHE_VERSION: u32 = 1;
+
+/// On-disk form of the cached config: its JSON sealed with the per-install key.
+#[derive(Serialize, Deserialize)]
+struct EncryptedCache {
+    version: u32,
+    /// Base64 of nonce and ciphertext
+    sealed: String,
+}
+
+/// Where the public config is fetched from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLocation {
+    Url(String),
+    File(PathBuf),
+}
+
+impl ConfigLocation {
+    /// `http(s)://` is a URL; `file://` or anything else is a path.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        if text.starts_with("http://") || text.starts_with("https://") {
+            return Some(Self::Url(text.to_string()));
+        }
+        let path = text.strip_prefix("file://").unwrap_or(text);
+        Some(Self::File(PathBuf::from(path)))
+    }
+
+    pub fn kind(&self) -> ConfigSourceKind {
+        match self {
+            Self::Url(_) => ConfigSourceKind::Url,
+            Self::File(_) => ConfigSourceKind::File,
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Self::Url(url) => url.clone(),
+            Self::File(path) => path.display().to_string(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ConfigService {
     cache_dir: PathBuf,
+    /// `Settings::config_source`
+    configured_source: Arc<RwLock<String>>,
+    health: Arc<Mutex<HashMap<String, ConfigSourceHealth>>>,
 }
 
 impl ConfigService {
@@ -22,24 +83,76 @@ impl ConfigService {
 
         let _ = std::fs::create_dir_all(&cache_dir);
 
-        Self { cache_dir }
+        Self {
+            cache_dir,
+            configured_source: Arc::new(RwLock::new(String::new())),
+            health: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 
     pub fn cache_path(&self) -> PathBuf {
         self.cache_dir.join("vrp-public.json")
     }
 
+    /// Use `source` (a URL or a path to a local JSON file) instead of the
+    /// built-in URLs; empty restores them.
+    pub fn set_configured_source(&self, source: &str) -> Result<()> {
+        *self
+            .configured_source
+            .write()
+            .map_err(|_| anyhow!("config source lock poisoned"))? = source.trim().to_string();
+        Ok(())
+    }
+
+    /// The sources `fetch_config` tries, in order.
+    pub fn sources(&self) -> Result<Vec<(ConfigLocation, ConfigOrigin)>> {
+        let env = std::env::var(CONFIG_SOURCE_ENV).ok();
+        let configured = self
+            .configured_source
+            .read()
+            .map_err(|_| anyhow!("config source lock poisoned"))?
+            .clone();
+        Ok(resolve_sources(env.as_deref(), &configured))
+    }
+
+    /// Health of the current sources, in the order they are tried.
+    pub fn source_health(&self) -> Result<Vec<ConfigSourceHealth>> {
+        let sources = self.sources()?;
+        let health = self
+            .health
+            .lock()
+            .map_err(|_| anyhow!("config health lock poisoned"))?;
+        Ok(sources
+            .into_iter()
+            .map(|(location, origin)| {
+                health
+                    .get(&location.display())
+                    .cloned()
+                    .map(|known| ConfigSourceHealth { origin, ..known })
+                    .unwrap_or_else(|| untried_health(&location, origin))
+            })
+            .collect())
+    }
+
     pub async fn fetch_config(&self) -> Result<PublicConfig> {
         let mut last_error = None;
 
-        for url in CONFIG_URLS {
-            match self.fetch_url(url).await {
+        for (location, origin) in self.sources()? {
+            let started = Instant::now();
+            let result = self.fetch_location(&location).await;
+            self.record_attempt(&location, origin, started, &result)?;
+            match result {
                 Ok(config) => {
                     // Cache it
                     let _ = self.save_to_cache(&config);
                     return Ok(config);
                 }
                 Err(e) => {
+                    crate::logger::log(&format!(
+                        "[CONFIG] Source {} failed: {:#}",
+                        location.display(),
+                        e
+                    ));
                     last_error = Some(e);
                 }
             }
@@ -53,24 +166,226 @@ impl ConfigService {
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No config available")))
     }
 
-    async fn fetch_url(&self, url: &str) -> Result<PublicConfig> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
-            .build()?;
-        let resp = client.get(url).send().await?;
-        let json: serde_json::Value = resp.json().await?;
-        PublicConfig::from_json(&json).map_err(|e| anyhow::anyhow!(e))
+    async fn fetch_location(&self, location: &ConfigLocation) -> Result<PublicConfig> {
+        let json: serde_json::Value = match location {
+            ConfigLocation::Url(url) => {
+                let client = reqwest::Client::builder()
+                    .timeout(std::time::Duration::from_secs(5))
+                    .build()?;
+                let resp = client.get(url).send().await?.error_for_status()?;
+                resp.json().await?
+            }
+            ConfigLocation::File(path) => {
+                let content = tokio::fs::read_to_string(path)
+                    .await
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                serde_json::from_str(&content)?
+            }
+        };
+        PublicConfig::from_json_validated(&json).map_err(|e| anyhow::anyhow!(e))
+    }
+
+    fn record_attempt(
+        &self,
+        location: &ConfigLocation,
+        origin: ConfigOrigin,
+        started: Instant,
+        result: &Result<PublicConfig>,
+    ) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let mut health = self
+            .health
+            .lock()
+            .map_err(|_| anyhow!("config health lock poisoned"))?;
+        let entry = health
+            .entry(location.display())
+            .or_insert_with(|| untried_health(location, origin));
+        entry.origin = origin;
+        entry.latency_ms = Some(started.elapsed().as_millis() as u64);
+        match result {
+            Ok(_) => {
+                entry.last_success_at = Some(now);
+                entry.consecutive_failures = 0;
+            }
+            Err(e) => {
+                entry.last_error = Some(format!("{:#}", e));
+                entry.last_error_at = Some(now);
+                entry.consecutive_failures += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Key for the cached config, kept next to the cache directory rather than in it.
+    pub fn key_path(&self) -> PathBuf {
+        self.cache_dir
+            .parent()
+            .unwrap_or(&self.cache_dir)
+            .join("config.key")
     }
 
     fn save_to_cache(&self, config: &PublicConfig) -> Result<()> {
-        let json = serde_json::to_string_pretty(config)?;
-        std::fs::write(self.cache_path(), json)?;
+        let json = Zeroizing::new(serde_json::to_vec(config)?);
+        let sealed = SecretStore::new(self.key_path()).encrypt(&json)?;
+        let cache = EncryptedCache {
+            version: CACHE_VERSION,
+            sealed: general_purpose::STANDARD.encode(sealed),
+        };
+        std::fs::write(self.cache_path(), serde_json::to_string_pretty(&cache)?)?;
         Ok(())
     }
 
+    /// Load the cached config. A plaintext cache written by an older version is
+    /// re-written encrypted.
     pub fn load_from_cache(&self) -> Result<PublicConfig> {
-        let content = std::fs::read_to_string(self.cache_path())?;
+        let content = Zeroizing::new(std::fs::read_to_string(self.cache_path())?);
+        if let Ok(cache) = serde_json::from_str::<EncryptedCache>(&content) {
+            if cache.version != CACHE_VERSION {
+                anyhow::bail!("unsupported config cache version {}", cache.version);
+            }
+            let sealed = general_purpose::STANDARD.decode(cache.sealed)?;
+            let json = SecretStore::new(self.key_path()).decrypt(&sealed)?;
+            return Ok(serde_json::from_slice(&json)?);
+        }
+
         let config: PublicConfig = serde_json::from_str(&content)?;
+        match self.save_to_cache(&config) {
+            Ok(()) => crate::logger::log("[CONFIG] Encrypted the plaintext config cache"),
+            Err(e) => crate::logger::log(&format!(
+                "[CONFIG] Failed to encrypt the plaintext config cache: {:#}",
+                e
+            )),
+        }
         Ok(config)
     }
 }
+
+fn untried_health(location: &ConfigLocation, origin: ConfigOrigin) -> ConfigSourceHealth {
+    ConfigSourceHealth {
+        location: location.display(),
+        kind: location.kind(),
+        origin,
+        last_success_at: None,
+        last_error: None,
+        last_error_at: None,
+        latency_ms: None,
+        consecutive_failures: 0,
+    }
+}
+
+/// An override from the environment wins over one from settings, and either
+/// replaces the built-in URLs.
+fn resolve_sources(env: Option<&str>, configured: &str) -> Vec<(ConfigLocation, ConfigOrigin)> {
+    if let Some(location) = env.and_then(ConfigLocation::parse) {
+        return vec![(location, ConfigOrigin::Environment)];
+    }
+    if let Some(location) = ConfigLocation::parse(configured) {
+        return vec![(location, ConfigOrigin::Settings)];
+    }
+    CONFIG_URLS
+        .iter()
+        .map(|url| (ConfigLocation::Url(url.to_string()), ConfigOrigin::Default))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn config_json(base_uri: &str) -> String {
+        serde_json::json!({
+            "baseUri": base_uri,
+            "password": general_purpose::STANDARD.encode("secret"),
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn overrides_replace_the_built_in_urls() {
+        let defaults = resolve_sources(None, "");
+        assert_eq!(defaults.len(), CONFIG_URLS.len());
+        assert!(defaults
+            .iter()
+            .all(|(_, origin)| *origin == ConfigOrigin::Default));
+
+        assert_eq!(
+            resolve_sources(None, " https://example.com/vrp.json "),
+            vec![(
+                ConfigLocation::Url("https://example.com/vrp.json".to_string()),
+                ConfigOrigin::Settings
+            )]
+        );
+        assert_eq!(
+            resolve_sources(Some("file:///srv/vrp.json"), "https://example.com/vrp.json"),
+            vec![(
+                ConfigLocation::File(PathBuf::from("/srv/vrp.json")),
+                ConfigOrigin::Environment
+            )]
+        );
+        assert_eq!(resolve_sources(Some("  "), "").len(), CONFIG_URLS.len());
+    }
+
+    #[test]
+    fn plaintext_cache_is_migrated_to_encrypted() {
+        let temp_dir = tempdir().unwrap();
+        let service = ConfigService::new(Some(temp_dir.path().join("cache")));
+        let config = PublicConfig {
+            base_uri: "https://good.example.com/".to_string(),
+            password: Zeroizing::new("secret_password".to_string()),
+        };
+        std::fs::write(
+            service.cache_path(),
+            serde_json::to_string_pretty(&config).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(service.load_from_cache().unwrap(), config);
+        let on_disk = std::fs::read_to_string(service.cache_path()).unwrap();
+        assert!(!on_disk.contains("secret_password"));
+        assert!(service.key_path().exists());
+        assert!(!service
+            .key_path()
+            .starts_with(temp_dir.path().join("cache")));
+        assert_eq!(service.load_from_cache().unwrap(), config);
+
+        // Without its key the cache is unreadable rather than silently wrong
+        std::fs::remove_file(service.key_path()).unwrap();
+        assert!(service.load_from_cache().is_err());
+    }
+
+    #[tokio::test]
+    async fn broken_file_keeps_the_cached_config_and_is_tracked() {
+        let temp_dir = tempdir().unwrap();
+        let service = ConfigService::new(Some(temp_dir.path().join("cache")));
+        let config_path = temp_dir.path().join("vrp-public.json");
+        service
+            .set_configured_source(&config_path.display().to_string())
+            .unwrap();
+
+        std::fs::write(&config_path, config_json("https://good.example.com/")).unwrap();
+        let config = service.fetch_config().await.unwrap();
+        assert_eq!(config.base_uri, "https://good.example.com/");
+
+        // A response without a usable base URI must not replace the cache
+        std::fs::write(&config_path, config_json("not a url")).unwrap();
+        let config = service.fetch_config().await.unwrap();
+        assert_eq!(config.base_uri, "https://good.example.com/");
+        assert_eq!(
+            service.load_from_cache().unwrap().base_uri,
+            "https://good.example.com/"
+        );
+
+        let health = service.source_health().unwrap();
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0].kind, ConfigSourceKind::File);
+        assert_eq!(health[0].origin, ConfigOrigin::Settings);
+        assert!(health[0].last_success_at.is_some());
+        assert_eq!(health[0].consecutive_failures, 1);
+        assert!(health[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("not an http(s) URL"));
+    }
+}
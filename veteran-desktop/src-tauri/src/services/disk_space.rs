@@ -0,0 +1,106 @@
+use crate::models::size::format_megabytes;
+use std::path::Path;
+
+/// Room an extracted release takes next to its archives, as a share of the
+/// archive size. Installing extracts before deleting the archives, and APKs
+/// and OBBs barely compress, so both copies exist at once.
+pub const EXTRACTION_OVERHEAD: f64 = 1.0;
+
+/// Free space for the current user on the filesystem holding `path`. The
+/// path need not exist yet; its nearest existing parent is asked instead.
+pub fn available_space(path: &Path) -> std::io::Result<u64> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path);
+    fs4::available_space(existing)
+}
+
+/// Space a release of `size_bytes` still needs with `transferred_bytes`
+/// already on disk: the rest of the download plus the extracted copy.
+pub fn required_space(size_bytes: u64, transferred_bytes: u64) -> u64 {
+    let extracted = (size_bytes as f64 * EXTRACTION_OVERHEAD) as u64;
+    size_bytes
+        .saturating_sub(transferred_bytes)
+        .saturating_add(extracted)
+}
+
+/// Whether a release fits on the download drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpaceCheck {
+    pub available_bytes: u64,
+    /// What the release itself still needs
+    pub required_bytes: u64,
+    /// What the other downloads counted against the drive still need
+    pub reserved_bytes: u64,
+}
+
+impl SpaceCheck {
+    /// Fits even if the other downloads don't go ahead.
+    pub fn fits_alone(&self) -> bool {
+        self.required_bytes <= self.available_bytes
+    }
+
+    /// Fits next to the other downloads.
+    pub fn fits(&self) -> bool {
+        self.required_bytes.saturating_add(self.reserved_bytes) <= self.available_bytes
+    }
+
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "not enough space on the download drive: needs {}",
+            format_megabytes(self.required_bytes)
+        );
+        if self.reserved_bytes > 0 {
+            message.push_str(&format!(
+                " plus {} for other downloads",
+                format_megabytes(self.reserved_bytes)
+            ));
+        }
+        let available = match format_megabytes(self.available_bytes) {
+            available if available.is_empty() => "0 MB".to_string(),
+            available => available,
+        };
+        message.push_str(&format!(", {available} free"));
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::download_retry::DownloadFailure;
+    use tempfile::tempdir;
+
+    #[test]
+    fn counts_the_rest_of_the_download_and_the_extracted_copy() {
+        assert_eq!(required_space(1000, 0), 2000);
+        assert_eq!(required_space(1000, 400), 1600);
+        assert_eq!(required_space(1000, 5000), 1000);
+        assert_eq!(required_space(u64::MAX, 0), u64::MAX);
+
+        let check = SpaceCheck {
+            available_bytes: 3 << 20,
+            required_bytes: 2 << 20,
+            reserved_bytes: 2 << 20,
+        };
+        assert!(check.fits_alone());
+        assert!(!check.fits());
+        assert_eq!(
+            check.message(),
+            "not enough space on the download drive: needs 2 MB plus 2 MB for other downloads, 3 MB free"
+        );
+        // Refused starts fail for good rather than being retried
+        assert_eq!(
+            DownloadFailure::classify(&check.message()),
+            DownloadFailure::DiskFull
+        );
+    }
+
+    #[test]
+    fn asks_the_nearest_existing_parent() {
+        let temp = tempdir().unwrap();
+        let missing = temp.path().join("not").join("yet");
+        assert!(available_space(&missing).is_ok());
+    }
+}
@@ -1,23 +1,81 @@
+use crate::models::download_queue::QueuedDownload;
 use crate::models::game::Game;
 use crate::services::catalog::CatalogService;
-use crate::services::rclone::{DownloadProgress, RcloneService};
+use crate::services::disk_space::{available_space, required_space, SpaceCheck};
+use crate::services::download_retry::{DownloadFailure, RetryPolicy};
+use crate::services::download_verify::{verify_release, VERIFIED_MARKER};
+use crate::services::rclone::{DownloadProgress, RcloneResult, RcloneService, RemoteFile};
 use anyhow::Result;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, Notify, RwLock};
+use tokio::task::JoinSet;
 use uuid::Uuid;
 
+/// Most downloads the scheduler runs at once.
+pub const MAX_DOWNLOAD_SLOTS: usize = 8;
+
+/// How often the progress of running downloads is reported. Every slot with
+/// new progress is reported once per interval, so a slot whose transfer
+/// reports often doesn't crowd out the others.
+const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_millis(250);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DownloadStatus {
     Queued,
     Downloading,
+    /// Checking the downloaded files against the remote listing
+    Verifying,
     Paused,
     Completed,
     Failed,
     Cancelled,
 }
 
+impl DownloadStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DownloadStatus::Queued => "queued",
+            DownloadStatus::Downloading => "downloading",
+            DownloadStatus::Verifying => "verifying",
+            DownloadStatus::Paused => "paused",
+            DownloadStatus::Completed => "completed",
+            DownloadStatus::Failed => "failed",
+            DownloadStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        [
+            DownloadStatus::Queued,
+            DownloadStatus::Downloading,
+            DownloadStatus::Verifying,
+            DownloadStatus::Paused,
+            DownloadStatus::Completed,
+            DownloadStatus::Failed,
+            DownloadStatus::Cancelled,
+        ]
+        .into_iter()
+        .find(|candidate| candidate.as_str() == status)
+    }
+
+    /// Whether the item holds a slot.
+    pub fn is_active(&self) -> bool {
+        matches!(self, DownloadStatus::Downloading | DownloadStatus::Verifying)
+    }
+
+    /// Whether the item is still to download, now or later.
+    pub fn is_pending(&self) -> bool {
+        self.is_active() || matches!(self, DownloadStatus::Queued | DownloadStatus::Paused)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DownloadItem {
     pub operation_id: String,
@@ -25,6 +83,16 @@ pub struct DownloadItem {
     pub status: DownloadStatus,
     pub progress: DownloadProgress,
     pub error: String,
+    /// Higher priorities start first; equal priorities keep queue order
+    pub priority: i32,
+    /// Slot the item is downloading in, from 0
+    pub slot: Option<usize>,
+    /// Retries made after the first attempt
+    pub retry_count: u32,
+    /// Classified cause of the last failure
+    pub failure: Option<DownloadFailure>,
+    /// When a queued item waiting to retry may start again
+    pub next_retry_at: Option<DateTime<Utc>>,
 }
 
 impl DownloadItem {
@@ -35,6 +103,11 @@ impl DownloadItem {
             status: DownloadStatus::Queued,
             progress: DownloadProgress::default(),
             error: String::new(),
+            priority: 0,
+            slot: None,
+            retry_count: 0,
+            failure: None,
+            next_retry_at: None,
         }
     }
 
@@ -43,70 +116,95 @@ impl DownloadItem {
     }
 }
 
-// Internal structure to track active downloads
-type ProgressUpdate = (String, DownloadProgress);
+pub type TransferFuture<'a> = Pin<Box<dyn Future<Output = Result<RcloneResult>> + Send + 'a>>;
+pub type StopFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+pub type ListFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<RemoteFile>>> + Send + 'a>>;
+
+/// Moves a release's files into a local folder.
+pub trait DownloadTransport: std::fmt::Debug + Send + Sync {
+    /// Copy `game` into `dest`, sending progress while it runs. The bandwidth
+    /// limit is shared by every running transfer.
+    fn download<'a>(
+        &'a self,
+        game: &'a Game,
+        dest: &'a Path,
+        bandwidth_limit_mbps: f64,
+        progress: mpsc::UnboundedSender<DownloadProgress>,
+    ) -> TransferFuture<'a>;
+
+    /// Stop the transfer of the release with hash `game_hash`.
+    fn stop<'a>(&'a self, game_hash: &'a str) -> StopFuture<'a>;
+
+    /// The files of `game` on the remote, to verify a download against.
+    fn list<'a>(&'a self, game: &'a Game) -> ListFuture<'a>;
+}
+
+impl DownloadTransport for RcloneService {
+    fn download<'a>(
+        &'a self,
+        game: &'a Game,
+        dest: &'a Path,
+        bandwidth_limit_mbps: f64,
+        progress: mpsc::UnboundedSender<DownloadProgress>,
+    ) -> TransferFuture<'a> {
+        // Download from the source that listed the release
+        Box::pin(self.download_game(&game.source, &game.release_name, dest, bandwidth_limit_mbps, Some(progress)))
+    }
+
+    fn stop<'a>(&'a self, game_hash: &'a str) -> StopFuture<'a> {
+        Box::pin(self.stop_download(game_hash))
+    }
 
-#[derive(Debug)]
+    fn list<'a>(&'a self, game: &'a Game) -> ListFuture<'a> {
+        Box::pin(self.list_release(&game.source, &game.release_name))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct DownloadService {
-    rclone: Arc<RcloneService>,
+    transport: Arc<dyn DownloadTransport>,
     download_dir: PathBuf,
     bandwidth_limit_mbps: f64,
     queue: Arc<RwLock<Vec<DownloadItem>>>,
     processing: Arc<RwLock<bool>>,
-    progress_tx: mpsc::UnboundedSender<ProgressUpdate>,
-    progress_rx: Arc<Mutex<mpsc::UnboundedReceiver<ProgressUpdate>>>,
-    active_handles: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
-}
-
-impl Clone for DownloadService {
-    fn clone(&self) -> Self {
-        Self {
-            rclone: self.rclone.clone(),
-            download_dir: self.download_dir.clone(),
-            bandwidth_limit_mbps: self.bandwidth_limit_mbps,
-            queue: self.queue.clone(),
-            processing: self.processing.clone(),
-            progress_tx: self.progress_tx.clone(),
-            progress_rx: self.progress_rx.clone(),
-            active_handles: self.active_handles.clone(),
-        }
-    }
+    max_slots: Arc<AtomicUsize>,
+    retry_policy: Arc<std::sync::RwLock<RetryPolicy>>,
+    /// Cancel signal of each running download, by package
+    active: Arc<Mutex<HashMap<String, Arc<Notify>>>>,
+    /// Packages with progress not reported yet
+    unreported: Arc<Mutex<HashSet<String>>>,
+    /// Status changes of running items not reported yet, oldest first
+    changes: Arc<Mutex<Vec<DownloadItem>>>,
+    /// Wakes the scheduler when the queue or the slot count changes
+    wake: Arc<Notify>,
 }
 
 impl DownloadService {
     pub fn new(rclone: RcloneService, download_dir: PathBuf, bandwidth_limit_mbps: f64) -> Self {
-        // Ensure download directory exists
-        if !download_dir.exists() {
-            let _ = std::fs::create_dir_all(&download_dir);
-        }
-        let (progress_tx, progress_rx) = mpsc::unbounded_channel();
-        Self {
-            rclone: Arc::new(rclone),
-            download_dir,
-            bandwidth_limit_mbps,
-            queue: Arc::new(RwLock::new(Vec::new())),
-            processing: Arc::new(RwLock::new(false)),
-            progress_tx,
-            progress_rx: Arc::new(Mutex::new(progress_rx)),
-            active_handles: Arc::new(Mutex::new(HashMap::new())),
-        }
+        Self::with_transport(Arc::new(rclone), download_dir, bandwidth_limit_mbps)
     }
 
     pub fn new_with_arc(rclone: Arc<RcloneService>, download_dir: PathBuf, bandwidth_limit_mbps: f64) -> Self {
+        Self::with_transport(rclone, download_dir, bandwidth_limit_mbps)
+    }
+
+    pub fn with_transport(transport: Arc<dyn DownloadTransport>, download_dir: PathBuf, bandwidth_limit_mbps: f64) -> Self {
         // Ensure download directory exists
         if !download_dir.exists() {
             let _ = std::fs::create_dir_all(&download_dir);
         }
-        let (progress_tx, progress_rx) = mpsc::unbounded_channel();
         Self {
-            rclone,
+            transport,
             download_dir,
             bandwidth_limit_mbps,
             queue: Arc::new(RwLock::new(Vec::new())),
             processing: Arc::new(RwLock::new(false)),
-            progress_tx,
-            progress_rx: Arc::new(Mutex::new(progress_rx)),
-            active_handles: Arc::new(Mutex::new(HashMap::new())),
+            max_slots: Arc::new(AtomicUsize::new(1)),
+            retry_policy: Arc::new(std::sync::RwLock::new(RetryPolicy::default())),
+            active: Arc::new(Mutex::new(HashMap::new())),
+            unreported: Arc::new(Mutex::new(HashSet::new())),
+            changes: Arc::new(Mutex::new(Vec::new())),
+            wake: Arc::new(Notify::new()),
         }
     }
 
@@ -122,20 +220,58 @@ impl DownloadService {
         &self.download_dir
     }
 
+    pub fn max_slots(&self) -> usize {
+        self.max_slots.load(Ordering::SeqCst)
+    }
+
+    /// Run up to `slots` downloads at once, clamped to 1..=[`MAX_DOWNLOAD_SLOTS`].
+    /// Lowering it lets running downloads finish rather than stopping them.
+    pub fn set_max_slots(&self, slots: usize) {
+        self.max_slots
+            .store(slots.clamp(1, MAX_DOWNLOAD_SLOTS), Ordering::SeqCst);
+        self.wake.notify_one();
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        *self.retry_policy.read().unwrap()
+    }
+
+    /// Applies to failures from now on; retries already waiting keep their time.
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        *self.retry_policy.write().unwrap() = policy;
+    }
+
     pub async fn add_to_queue(&self, game: Game) -> bool {
         let mut queue = self.queue.write().await;
         if queue.iter().any(|item| item.game.package_name == game.package_name) {
             return false;
         }
         queue.push(DownloadItem::new(game));
+        self.wake.notify_one();
         true
     }
 
+    /// Space the pending downloads in the queue still need.
+    pub async fn queued_space(&self) -> u64 {
+        space_needed_by(&self.queue.read().await, "", DownloadStatus::is_pending)
+    }
+
+    /// Whether `game` fits on the download drive next to the pending
+    /// downloads in the queue.
+    pub async fn space_to_queue(&self, game: &Game) -> std::io::Result<SpaceCheck> {
+        let reserved = space_needed_by(&self.queue.read().await, &game.package_name, DownloadStatus::is_pending);
+        Ok(SpaceCheck {
+            available_bytes: available_space(&self.download_dir)?,
+            required_bytes: required_space(game.size_bytes, 0),
+            reserved_bytes: reserved,
+        })
+    }
+
     pub async fn remove_from_queue(&self, package_name: &str) -> bool {
         let mut queue = self.queue.write().await;
         let before = queue.len();
         queue.retain(|item| {
-            item.game.package_name != package_name || item.status == DownloadStatus::Downloading
+            item.game.package_name != package_name || item.status.is_active()
         });
         queue.len() != before
     }
@@ -155,37 +291,69 @@ impl DownloadService {
         true
     }
 
-    pub async fn cancel_current(&self) -> Result<bool> {
+    /// Set the priority of a queued item; when a slot frees up, the highest
+    /// priority starts first.
+    pub async fn set_priority(&self, package_name: &str, priority: i32) -> bool {
         let mut queue = self.queue.write().await;
-        if let Some(item) = queue
+        let Some(item) = queue
             .iter_mut()
-            .find(|item| item.status == DownloadStatus::Downloading)
-        {
-            item.status = DownloadStatus::Cancelled;
-            drop(queue); // Release lock before await
-            self.rclone.cancel_download().await?;
-            return Ok(true);
+            .find(|item| item.game.package_name == package_name)
+        else {
+            return false;
+        };
+        item.priority = priority;
+        self.wake.notify_one();
+        true
+    }
+
+    /// Cancel every running download.
+    pub async fn cancel_current(&self) -> Result<bool> {
+        let downloading: Vec<String> = self
+            .queue
+            .read()
+            .await
+            .iter()
+            .filter(|item| item.status.is_active())
+            .map(|item| item.game.package_name.clone())
+            .collect();
+        let mut cancelled = false;
+        for package_name in downloading {
+            cancelled |= self.cancel_item(&package_name).await;
         }
-        Ok(false)
+        Ok(cancelled)
+    }
+
+    /// Cancel the download of `package_name` if it is running, leaving the
+    /// other slots alone.
+    pub async fn cancel_item(&self, package_name: &str) -> bool {
+        self.stop_item(package_name, DownloadStatus::Cancelled).await
     }
 
     pub async fn pause_item(&self, package_name: &str) -> Result<bool> {
-        let mut queue = self.queue.write().await;
-        if let Some(item) = queue
-            .iter_mut()
-            .find(|item| item.game.package_name == package_name)
+        Ok(self.stop_item(package_name, DownloadStatus::Paused).await)
+    }
+
+    /// Stop a running download, leaving it in `status`.
+    async fn stop_item(&self, package_name: &str, status: DownloadStatus) -> bool {
         {
-            if item.status == DownloadStatus::Downloading {
-                item.status = DownloadStatus::Paused;
-                let hash = item.game_hash();
-                drop(queue);
-                self.rclone.stop_download(&hash).await?;
-                return Ok(true);
-            }
+            let mut queue = self.queue.write().await;
+            let Some(item) = queue
+                .iter_mut()
+                .find(|item| item.game.package_name == package_name && item.status.is_active())
+            else {
+                return false;
+            };
+            item.status = status;
         }
-        Ok(false)
+        if let Some(cancel) = self.active.lock().await.get(package_name) {
+            cancel.notify_one();
+        }
+        true
     }
 
+    /// Queue a paused, failed or cancelled item again, with a fresh set of
+    /// retries. A running scheduler picks it up; otherwise start one with
+    /// [`Self::process_queue`].
     pub async fn resume_item(&self, package_name: &str) -> Result<bool> {
         let mut queue = self.queue.write().await;
         if let Some(item) = queue
@@ -194,278 +362,437 @@ impl DownloadService {
         {
             if item.status == DownloadStatus::Paused || item.status == DownloadStatus::Failed || item.status == DownloadStatus::Cancelled {
                 item.status = DownloadStatus::Queued;
-                drop(queue);
-                self.process_queue().await?;
+                item.retry_count = 0;
+                item.next_retry_at = None;
+                self.wake.notify_one();
                 return Ok(true);
             }
         }
         Ok(false)
     }
 
-    pub async fn process_queue(&self) -> Result<()> {
-        // Check if already processing
-        {
-            let processing = self.processing.read().await;
-            if *processing {
-                return Ok(());
+    /// The queue as saved in the settings.
+    pub async fn snapshot(&self) -> Vec<QueuedDownload> {
+        self.queue
+            .read()
+            .await
+            .iter()
+            .map(|item| QueuedDownload {
+                package_name: item.game.package_name.clone(),
+                release_name: item.game.release_name.clone(),
+                game_name: item.game.game_name.clone(),
+                source: item.game.source.clone(),
+                status: item.status.as_str().to_string(),
+                error: item.error.clone(),
+                retry_count: item.retry_count,
+                priority: item.priority,
+                progress_percent: item.progress.percent,
+                bytes_transferred: item.progress.bytes_transferred,
+                total_bytes: item.progress.total_bytes,
+            })
+            .collect()
+    }
+
+    /// Add saved entries to the queue, returning how many were restored.
+    /// `release` looks up the catalog release of a package and release name,
+    /// an empty release name meaning the newest. Downloads cut off by the
+    /// shutdown are queued again and resume where rclone left them, and
+    /// retries that were waiting start right away. Entries
+    /// already queued, completed downloads whose files are gone and bare
+    /// package names the catalog doesn't list are skipped.
+    pub async fn restore(&self, saved: &[QueuedDownload], release: impl Fn(&str, &str) -> Option<Game>) -> usize {
+        let mut queue = self.queue.write().await;
+        let before = queue.len();
+        for entry in saved {
+            if queue.iter().any(|item| item.game.package_name == entry.package_name) {
+                continue;
             }
+            // Completed downloads whose files were since removed are done with
+            if entry.status == DownloadStatus::Completed.as_str() && !self.is_release_downloaded(&entry.release_name) {
+                continue;
+            }
+            let game = match release(&entry.package_name, &entry.release_name) {
+                Some(game) => game,
+                None if entry.release_name.is_empty() => continue,
+                None => Game {
+                    game_name: entry.game_name.clone(),
+                    release_name: entry.release_name.clone(),
+                    package_name: entry.package_name.clone(),
+                    source: entry.source.clone(),
+                    ..Game::default()
+                },
+            };
+            let mut item = DownloadItem::new(game);
+            item.status = match DownloadStatus::parse(&entry.status) {
+                Some(DownloadStatus::Downloading | DownloadStatus::Verifying) | None => DownloadStatus::Queued,
+                Some(status) => status,
+            };
+            item.error = entry.error.clone();
+            item.failure = (!entry.error.is_empty()).then(|| DownloadFailure::classify(&entry.error));
+            item.retry_count = entry.retry_count;
+            item.priority = entry.priority;
+            item.progress = DownloadProgress {
+                bytes_transferred: entry.bytes_transferred,
+                total_bytes: entry.total_bytes,
+                percent: entry.progress_percent,
+                ..DownloadProgress::default()
+            };
+            queue.push(item);
         }
+        self.wake.notify_one();
+        queue.len() - before
+    }
+
+    pub async fn process_queue(&self) -> Result<()> {
+        self.process_queue_with_callback(|_| async {}).await
+    }
 
-        // Mark as processing
+    /// Start the scheduler unless it is running: it fills free slots with
+    /// queued items, highest priority first and in queue order otherwise,
+    /// until the queue is empty. `on_update` gets every status change and,
+    /// at most once per interval, the progress of each running download.
+    pub async fn process_queue_with_callback<F, Fut>(&self, on_update: F) -> Result<()>
+    where
+        F: Fn(DownloadItem) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
         {
             let mut processing = self.processing.write().await;
+            if *processing {
+                return Ok(());
+            }
             *processing = true;
         }
 
-        // Spawn the queue processor as a background task
-        let self_clone = self.clone();
-        tokio::spawn(async move {
-            self_clone.run_queue_processor().await;
-        });
-
-        // Spawn progress updater
-        let self_clone = self.clone();
+        let scheduler = self.clone();
         tokio::spawn(async move {
-            self_clone.run_progress_updater().await;
+            scheduler.run_scheduler(on_update).await;
         });
-
         Ok(())
     }
 
-    async fn run_progress_updater(&self) {
-        let mut rx = self.progress_rx.lock().await;
-        while let Some((package_name, progress)) = rx.recv().await {
-            let mut queue = self.queue.write().await;
-            if let Some(item) = queue.iter_mut().find(|i| i.game.package_name == package_name) {
-                item.progress = progress;
-            }
-        }
-    }
+    async fn run_scheduler<F, Fut>(&self, on_update: F)
+    where
+        F: Fn(DownloadItem) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
+        let mut slots: JoinSet<DownloadItem> = JoinSet::new();
+        let mut report = tokio::time::interval(PROGRESS_REPORT_INTERVAL);
 
-    async fn run_queue_processor(&self) {
         loop {
-            // Find next queued item
-            let next_item = {
-                let queue = self.queue.read().await;
-                queue
+            while slots.len() < self.max_slots() {
+                let Some(item) = self.claim_next().await else {
+                    break;
+                };
+                on_update(item.clone()).await;
+                let slot = self.clone();
+                slots.spawn(async move { slot.run_slot(item).await });
+            }
+            self.report_changes(&on_update).await;
+
+            let next_retry = self.next_retry_in().await;
+            if slots.is_empty() && next_retry.is_none() {
+                // Stop unless something was queued since the check above
+                let mut processing = self.processing.write().await;
+                let queued = self
+                    .queue
+                    .read()
+                    .await
                     .iter()
-                    .enumerate()
-                    .find(|(_, item)| item.status == DownloadStatus::Queued)
-                    .map(|(idx, item)| (idx, item.clone()))
-            };
-
-            if let Some((index, item)) = next_item {
-                // Mark as downloading
-                {
-                    let mut queue = self.queue.write().await;
-                    queue[index].status = DownloadStatus::Downloading;
+                    .any(|item| item.status == DownloadStatus::Queued);
+                if !queued {
+                    *processing = false;
+                    break;
                 }
+                continue;
+            }
 
-                // Spawn the download as a background task
-                let package_name = item.game.package_name.clone();
-                let handle = self.spawn_download_task(item).await;
-                
-                {
-                    let mut handles = self.active_handles.lock().await;
-                    handles.insert(package_name, handle);
+            tokio::select! {
+                Some(finished) = slots.join_next() => {
+                    self.report_changes(&on_update).await;
+                    self.report_progress(&on_update).await;
+                    if let Ok(item) = finished {
+                        on_update(item).await;
+                    }
                 }
-            } else {
-                // No more queued items
-                break;
+                _ = report.tick() => self.report_progress(&on_update).await,
+                _ = tokio::time::sleep(next_retry.unwrap_or_default()), if next_retry.is_some() => {}
+                _ = self.wake.notified() => self.report_changes(&on_update).await,
             }
         }
+    }
 
-        // Mark as not processing
-        {
-            let mut processing = self.processing.write().await;
-            *processing = false;
-        }
+    /// Time until the earliest queued item waiting to retry may start.
+    async fn next_retry_in(&self) -> Option<Duration> {
+        let now = Utc::now();
+        self.queue
+            .read()
+            .await
+            .iter()
+            .filter(|item| item.status == DownloadStatus::Queued)
+            .filter_map(|item| item.next_retry_at)
+            .min()
+            .map(|at| (at - now).to_std().unwrap_or_default())
     }
 
-    async fn spawn_download_task(&self, item: DownloadItem) -> tokio::task::JoinHandle<()> {
-        let rclone = self.rclone.clone();
-        let download_dir = self.download_dir.clone();
-        let bandwidth_limit = self.bandwidth_limit_mbps;
-        let queue = self.queue.clone();
-        let progress_tx = self.progress_tx.clone();
-        let package_name = item.game.package_name.clone();
+    /// Mark the next item to download as downloading in the lowest free slot.
+    /// Items that don't fit on the download drive next to the running ones
+    /// fail on the way; queued ones are left out, as they may never start.
+    async fn claim_next(&self) -> Option<DownloadItem> {
+        let now = Utc::now();
+        let available_bytes = available_space(&self.download_dir)
+            .map_err(|e| crate::logger::log(&format!("[DOWNLOAD] Could not read free space, starting anyway: {}", e)))
+            .ok();
+        let mut queue = self.queue.write().await;
+        let index = loop {
+            let index = queue
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| {
+                    item.status == DownloadStatus::Queued && item.next_retry_at.is_none_or(|at| at <= now)
+                })
+                // The first of the highest priority
+                .max_by(|(a_index, a), (b_index, b)| a.priority.cmp(&b.priority).then(b_index.cmp(a_index)))
+                .map(|(index, _)| index)?;
+            let Some(available_bytes) = available_bytes else {
+                break index;
+            };
+            let item = &queue[index];
+            let check = SpaceCheck {
+                available_bytes,
+                required_bytes: required_space(item.game.size_bytes, item.progress.bytes_transferred.max(0) as u64),
+                reserved_bytes: space_needed_by(&queue, &item.game.package_name, DownloadStatus::is_active),
+            };
+            if check.fits() {
+                break index;
+            }
+            let item = &mut queue[index];
+            self.fail(item, check.message());
+            self.changes.lock().await.push(item.clone());
+        };
+        let used: HashSet<usize> = queue.iter().filter_map(|item| item.slot).collect();
+        let slot = (0..).find(|slot| !used.contains(slot));
+
+        let item = &mut queue[index];
+        item.status = DownloadStatus::Downloading;
+        item.slot = slot;
+        item.next_retry_at = None;
+        self.active
+            .lock()
+            .await
+            .insert(item.game.package_name.clone(), Arc::new(Notify::new()));
+        Some(item.clone())
+    }
 
-        tokio::spawn(async move {
-            let game_hash = item.game_hash();
-            let game_dir = download_dir.join(&game_hash);
-
-            // Create progress callback that sends to our channel
-            let (rclone_tx, mut rclone_rx) = mpsc::unbounded_channel::<DownloadProgress>();
-            let progress_tx_clone = progress_tx.clone();
-            let package_name_clone = package_name.clone();
-            
-            let progress_forwarder = tokio::spawn(async move {
-                while let Some(progress) = rclone_rx.recv().await {
-                    let _ = progress_tx_clone.send((package_name_clone.clone(), progress));
-                }
-            });
+    async fn report_changes<F, Fut>(&self, on_update: &F)
+    where
+        F: Fn(DownloadItem) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let changes = std::mem::take(&mut *self.changes.lock().await);
+        for item in changes {
+            on_update(item).await;
+        }
+    }
+
+    /// Report the latest progress of each download that made some since the
+    /// last report, in slot order.
+    async fn report_progress<F, Fut>(&self, on_update: &F)
+    where
+        F: Fn(DownloadItem) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let packages = std::mem::take(&mut *self.unreported.lock().await);
+        if packages.is_empty() {
+            return;
+        }
+        let mut items: Vec<DownloadItem> = self
+            .queue
+            .read()
+            .await
+            .iter()
+            .filter(|item| item.status.is_active() && packages.contains(&item.game.package_name))
+            .cloned()
+            .collect();
+        items.sort_by_key(|item| item.slot);
+        for item in items {
+            on_update(item).await;
+        }
+    }
 
-            // Run the download
-            let result = rclone
-                .download_game(&game_hash, &game_dir, bandwidth_limit, Some(rclone_tx))
-                .await;
+    /// Download and verify `item` in its slot, returning it with its final
+    /// status.
+    async fn run_slot(&self, mut item: DownloadItem) -> DownloadItem {
+        let package_name = item.game.package_name.clone();
+        let game_dir = self.download_dir.join(item.game_hash());
+        let cancel = self.active.lock().await.get(&package_name).cloned().unwrap_or_default();
+        let _ = std::fs::remove_file(game_dir.join(VERIFIED_MARKER));
 
-            // Wait for progress forwarder to finish
-            let _ = progress_forwarder.await;
+        let mut result = self.transfer(&item.game, &game_dir, &cancel).await;
+        if result.is_ok() && self.set_active_status(&package_name, DownloadStatus::Verifying).await {
+            result = self.verify(&item.game, &game_dir, &cancel).await;
+        }
+        self.active.lock().await.remove(&package_name);
 
-            // Update final status
-            let mut queue = queue.write().await;
-            if let Some(item) = queue.iter_mut().find(|i| i.game.package_name == package_name) {
-                if item.status == DownloadStatus::Cancelled {
-                    return;
-                }
-                if item.status == DownloadStatus::Paused {
-                    return;
+        let mut queue = self.queue.write().await;
+        let Some(queued) = queue.iter_mut().find(|i| i.game.package_name == package_name) else {
+            // Cancelled and removed from the queue meanwhile
+            item.status = DownloadStatus::Cancelled;
+            item.slot = None;
+            return item;
+        };
+        queued.slot = None;
+        // A paused or cancelled item keeps its status
+        if queued.status.is_active() {
+            match result {
+                Ok(()) => {
+                    queued.status = DownloadStatus::Completed;
+                    queued.progress.percent = 100.0;
+                    queued.error.clear();
+                    queued.failure = None;
                 }
+                Err(error) => self.fail(queued, error),
+            }
+        }
+        queued.clone()
+    }
 
-                match result {
-                    Ok(download_result) if download_result.success() => {
-                        item.status = DownloadStatus::Completed;
-                        item.progress.percent = 100.0;
-                    }
-                    Ok(download_result) => {
-                        item.status = DownloadStatus::Failed;
-                        item.error = download_result.stderr;
+    /// Run one transfer of `game` into `game_dir`, passing on its progress and
+    /// stopping it when `cancel` fires.
+    async fn transfer(&self, game: &Game, game_dir: &Path, cancel: &Notify) -> std::result::Result<(), String> {
+        let package_name = &game.package_name;
+        let game_hash = CatalogService::game_name_to_hash(&game.release_name);
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<DownloadProgress>();
+        let transfer = self
+            .transport
+            .download(game, game_dir, self.bandwidth_limit_mbps, progress_tx);
+        tokio::pin!(transfer);
+
+        let mut stopping = false;
+        let result = loop {
+            tokio::select! {
+                result = &mut transfer => break result,
+                Some(progress) = progress_rx.recv() => {
+                    let mut queue = self.queue.write().await;
+                    if let Some(queued) = queue.iter_mut().find(|i| &i.game.package_name == package_name) {
+                        queued.progress = progress;
+                        self.unreported.lock().await.insert(package_name.clone());
                     }
-                    Err(error) => {
-                        item.status = DownloadStatus::Failed;
-                        item.error = error.to_string();
+                }
+                // Let the transfer wind down so the transport can clean up
+                _ = cancel.notified(), if !stopping => {
+                    stopping = true;
+                    if let Err(e) = self.transport.stop(&game_hash).await {
+                        crate::logger::log(&format!("[DOWNLOAD] Failed to stop {}: {}", package_name, e));
                     }
                 }
             }
-        })
+        };
+        match result {
+            Ok(download_result) if download_result.success() => Ok(()),
+            Ok(download_result) => Err(download_result.stderr),
+            Err(error) => Err(error.to_string()),
+        }
     }
 
-    pub async fn process_queue_with_callback<F, Fut>(&self, on_update: F) -> Result<()>
-    where
-        F: Fn(DownloadItem) -> Fut + Send + Sync + 'static,
-        Fut: std::future::Future<Output = ()> + Send,
-    {
-        // Check if already processing
-        {
-            let processing = self.processing.read().await;
-            if *processing {
+    /// Check the downloaded files against the remote listing. Bad files are
+    /// deleted and downloaded once more, leaving the good ones alone; if that
+    /// doesn't fix them the attempt fails, to be retried as usual.
+    async fn verify(&self, game: &Game, game_dir: &Path, cancel: &Notify) -> std::result::Result<(), String> {
+        let package_name = &game.package_name;
+        let remote = self.transport.list(game).await.map_err(|e| e.to_string())?;
+        if remote.is_empty() {
+            return Err("release folder not found on the mirror".to_string());
+        }
+
+        let mut repaired = false;
+        loop {
+            let dir = game_dir.to_path_buf();
+            let listing = remote.clone();
+            let verification = tokio::task::spawn_blocking(move || verify_release(&dir, &listing))
+                .await
+                .map_err(|e| e.to_string())?;
+            if !verification.missing_volumes.is_empty() {
+                return Err(format!(
+                    "archive volumes not found on the mirror: {}",
+                    verification.missing_volumes.join(", ")
+                ));
+            }
+            if verification.is_ok() {
+                let _ = std::fs::write(game_dir.join(VERIFIED_MARKER), "");
                 return Ok(());
             }
-        }
+            if repaired {
+                return Err(format!(
+                    "{} parts failed verification: {}",
+                    verification.bad_files.len(),
+                    verification.bad_files.join(", ")
+                ));
+            }
 
-        // Mark as processing
-        {
-            let mut processing = self.processing.write().await;
-            *processing = true;
+            crate::logger::log(&format!(
+                "[DOWNLOAD] {}: downloading {} bad parts again: {}",
+                package_name,
+                verification.bad_files.len(),
+                verification.bad_files.join(", ")
+            ));
+            for file in &verification.bad_files {
+                let _ = std::fs::remove_file(game_dir.join(file));
+            }
+            // The copy skips the files that are already complete
+            if !self.set_active_status(package_name, DownloadStatus::Downloading).await {
+                return Ok(());
+            }
+            self.transfer(game, game_dir, cancel).await?;
+            if !self.set_active_status(package_name, DownloadStatus::Verifying).await {
+                return Ok(());
+            }
+            repaired = true;
         }
+    }
 
-        let on_update = Arc::new(on_update);
-        let queue = self.queue.clone();
-        let rclone = self.rclone.clone();
-        let download_dir = self.download_dir.clone();
-        let bandwidth_limit = self.bandwidth_limit_mbps;
-        let processing = self.processing.clone();
-
-        // Spawn queue processor with callback
-        tokio::spawn(async move {
-            let mut idx = 0usize;
-            loop {
-                // Find next queued item
-                let next_item = {
-                    let queue = queue.read().await;
-                    queue
-                        .iter()
-                        .enumerate()
-                        .skip(idx)
-                        .find(|(_, item)| item.status == DownloadStatus::Queued)
-                        .map(|(i, item)| (i, item.clone()))
-                };
+    /// Move a running item to `status`, e.g. from downloading to verifying,
+    /// unless it was paused or cancelled meanwhile.
+    async fn set_active_status(&self, package_name: &str, status: DownloadStatus) -> bool {
+        let mut queue = self.queue.write().await;
+        let Some(item) = queue
+            .iter_mut()
+            .find(|item| item.game.package_name == package_name && item.status.is_active())
+        else {
+            return false;
+        };
+        item.status = status;
+        self.changes.lock().await.push(item.clone());
+        self.wake.notify_one();
+        true
+    }
 
-                if let Some((index, mut item)) = next_item {
-                    idx = index + 1;
-                    
-                    // Mark as downloading
-                    item.status = DownloadStatus::Downloading;
-                    {
-                        let mut queue = queue.write().await;
-                        if let Some(qitem) = queue.iter_mut().find(|i| i.game.package_name == item.game.package_name) {
-                            qitem.status = DownloadStatus::Downloading;
-                        }
-                    }
-                    on_update(item.clone()).await;
-
-                    // Run download
-                    let game_hash = item.game_hash();
-                    let game_dir = download_dir.join(&game_hash);
-                    let (tx, mut rx) = mpsc::unbounded_channel();
-
-                    let rclone_clone = rclone.clone();
-                    let bandwidth = bandwidth_limit;
-                    
-                    let download_task = async move {
-                        rclone_clone
-                            .download_game(&game_hash, &game_dir, bandwidth, Some(tx))
-                            .await
-                    };
-
-                    tokio::pin!(download_task);
-
-                    let result = loop {
-                        tokio::select! {
-                            res = &mut download_task => break res,
-                            Some(progress) = rx.recv() => {
-                                let mut queue = queue.write().await;
-                                if let Some(qitem) = queue.iter_mut().find(|i| i.game.package_name == item.game.package_name) {
-                                    qitem.progress = progress;
-                                }
-                                drop(queue);
-                                on_update(item.clone()).await;
-                            }
-                        }
-                    };
-
-                    // Update final status
-                    {
-                        let mut queue = queue.write().await;
-                        if let Some(qitem) = queue.iter_mut().find(|i| i.game.package_name == item.game.package_name) {
-                            if qitem.status == DownloadStatus::Cancelled {
-                                on_update(qitem.clone()).await;
-                                continue;
-                            }
-                            if qitem.status == DownloadStatus::Paused {
-                                on_update(qitem.clone()).await;
-                                continue;
-                            }
-
-                            match result {
-                                Ok(download_result) if download_result.success() => {
-                                    qitem.status = DownloadStatus::Completed;
-                                    qitem.progress.percent = 100.0;
-                                }
-                                Ok(download_result) => {
-                                    qitem.status = DownloadStatus::Failed;
-                                    qitem.error = download_result.stderr;
-                                }
-                                Err(error) => {
-                                    qitem.status = DownloadStatus::Failed;
-                                    qitem.error = error.to_string();
-                                }
-                            }
-                            on_update(qitem.clone()).await;
-                        }
-                    }
-                } else {
-                    break;
-                }
+    /// Record a failed attempt: transient failures are queued to retry after
+    /// a backoff until the retries run out, others fail right away.
+    fn fail(&self, item: &mut DownloadItem, error: String) {
+        let failure = DownloadFailure::classify(&error);
+        let delay = if failure.is_transient() {
+            self.retry_policy().delay(item.retry_count + 1)
+        } else {
+            None
+        };
+        crate::logger::log(&format!(
+            "[DOWNLOAD] {} failed ({}, attempt {}): {}",
+            item.game.package_name,
+            failure.as_str(),
+            item.retry_count + 1,
+            error
+        ));
+        match delay.and_then(|delay| chrono::Duration::from_std(delay).ok()) {
+            Some(delay) => {
+                item.status = DownloadStatus::Queued;
+                item.retry_count += 1;
+                item.next_retry_at = Some(Utc::now() + delay);
             }
-
-            // Mark as not processing
-            let mut processing = processing.write().await;
-            *processing = false;
-        });
-
-        Ok(())
+            None => item.status = DownloadStatus::Failed,
+        }
+        item.error = error;
+        item.failure = Some(failure);
     }
 
     pub fn get_download_dir(&self, game: &Game) -> PathBuf {
@@ -474,13 +801,49 @@ impl DownloadService {
     }
 
     pub async fn is_downloaded(&self, game: &Game) -> bool {
-        let game_dir = self.get_download_dir(game);
-        if !game_dir.exists() {
-            return false;
-        }
+        self.is_release_downloaded(&game.release_name)
+    }
+
+    /// Whether the download folder of `release_name` holds a verified
+    /// download, an APK or an install.txt.
+    pub fn is_release_downloaded(&self, release_name: &str) -> bool {
+        holds_download(
+            &self
+                .download_dir
+                .join(CatalogService::game_name_to_hash(release_name)),
+        )
+    }
+}
+
+/// Hash folders of `download_dir` that hold a download, for checking many
+/// releases with one directory listing.
+pub fn downloaded_hashes(download_dir: &Path) -> HashSet<String> {
+    let Ok(entries) = std::fs::read_dir(download_dir) else {
+        return HashSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| holds_download(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
 
-        has_apk(&game_dir) || game_dir.join("install.txt").exists()
+fn holds_download(game_dir: &Path) -> bool {
+    if !game_dir.exists() {
+        return false;
     }
+
+    game_dir.join(VERIFIED_MARKER).exists() || has_apk(game_dir) || game_dir.join("install.txt").exists()
+}
+
+/// Space the items of `queue` with a status passing `counts`, other than
+/// `except`'s, still need.
+fn space_needed_by(queue: &[DownloadItem], except: &str, counts: impl Fn(&DownloadStatus) -> bool) -> u64 {
+    queue
+        .iter()
+        .filter(|item| item.game.package_name != except && counts(&item.status))
+        .map(|item| required_space(item.game.size_bytes, item.progress.bytes_transferred.max(0) as u64))
+        .fold(0, u64::saturating_add)
 }
 
 fn has_apk(dir: &Path) -> bool {
@@ -511,11 +874,265 @@ mod tests {
             game_name: "Sample".to_string(),
             release_name: format!("Release {package_name}"),
             package_name: package_name.to_string(),
-            version_code: "1".to_string(),
+            version_code: 1,
             ..Default::default()
         }
     }
 
+    /// Transport whose transfers run until the test finishes them. A
+    /// finished transfer writes the package's remote files that are missing
+    /// or of another size locally, like an rclone copy.
+    #[derive(Debug, Default)]
+    struct FakeTransport {
+        started: std::sync::Mutex<Vec<String>>,
+        /// Ends each running transfer, with an error or none
+        running: std::sync::Mutex<HashMap<String, tokio::sync::oneshot::Sender<Option<String>>>>,
+        /// Remote files of each package as (path, content), one volume by default
+        remote: std::sync::Mutex<HashMap<String, Vec<(String, String)>>>,
+        /// Files written with a flipped byte on their next transfer
+        corrupt: std::sync::Mutex<HashSet<String>>,
+        written: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl FakeTransport {
+        fn started(&self) -> Vec<String> {
+            self.started.lock().unwrap().clone()
+        }
+
+        fn remote_files(&self, package_name: &str) -> Vec<(String, String)> {
+            self.remote
+                .lock()
+                .unwrap()
+                .get(package_name)
+                .cloned()
+                .unwrap_or_else(|| vec![(format!("{package_name}.7z.001"), "data".to_string())])
+        }
+
+        fn write_files(&self, package_name: &str, dest: &Path) {
+            std::fs::create_dir_all(dest).unwrap();
+            for (path, content) in self.remote_files(package_name) {
+                let local = dest.join(&path);
+                if std::fs::metadata(&local).is_ok_and(|m| m.len() == content.len() as u64) {
+                    continue;
+                }
+                let content = if self.corrupt.lock().unwrap().remove(&path) {
+                    content.replacen(|_: char| true, "X", 1)
+                } else {
+                    content
+                };
+                std::fs::write(local, content).unwrap();
+                self.written.lock().unwrap().push(path);
+            }
+        }
+
+        /// End the transfer of `package_name`, if running.
+        fn finish(&self, package_name: &str, success: bool) -> bool {
+            self.end(package_name, (!success).then(|| "stopped".to_string()))
+        }
+
+        fn fail(&self, package_name: &str, error: &str) -> bool {
+            self.end(package_name, Some(error.to_string()))
+        }
+
+        fn end(&self, package_name: &str, error: Option<String>) -> bool {
+            let hash = CatalogService::game_name_to_hash(&format!("Release {package_name}"));
+            match self.running.lock().unwrap().remove(&hash) {
+                Some(done) => done.send(error).is_ok(),
+                None => false,
+            }
+        }
+    }
+
+    impl DownloadTransport for FakeTransport {
+        fn download<'a>(
+            &'a self,
+            game: &'a Game,
+            dest: &'a Path,
+            _bandwidth_limit_mbps: f64,
+            progress: mpsc::UnboundedSender<DownloadProgress>,
+        ) -> TransferFuture<'a> {
+            let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+            self.started.lock().unwrap().push(game.package_name.clone());
+            self.running
+                .lock()
+                .unwrap()
+                .insert(CatalogService::game_name_to_hash(&game.release_name), done_tx);
+            Box::pin(async move {
+                let _ = progress.send(DownloadProgress {
+                    percent: 50.0,
+                    ..Default::default()
+                });
+                let error = done_rx.await.unwrap_or_else(|_| Some("dropped".to_string()));
+                if error.is_none() {
+                    self.write_files(&game.package_name, dest);
+                }
+                Ok(RcloneResult {
+                    stdout: String::new(),
+                    returncode: if error.is_some() { 1 } else { 0 },
+                    stderr: error.unwrap_or_default(),
+                })
+            })
+        }
+
+        fn stop<'a>(&'a self, game_hash: &'a str) -> StopFuture<'a> {
+            if let Some(done) = self.running.lock().unwrap().remove(game_hash) {
+                let _ = done.send(Some("stopped".to_string()));
+            }
+            Box::pin(async { Ok(()) })
+        }
+
+        fn list<'a>(&'a self, game: &'a Game) -> ListFuture<'a> {
+            let files = self
+                .remote_files(&game.package_name)
+                .into_iter()
+                .map(|(path, content)| RemoteFile {
+                    path,
+                    size: content.len() as u64,
+                    md5: Some(format!("{:x}", md5::compute(&content))),
+                })
+                .collect();
+            Box::pin(async { Ok(files) })
+        }
+    }
+
+    fn fake_service(slots: usize) -> (DownloadService, Arc<FakeTransport>, tempfile::TempDir) {
+        let temp = tempdir().unwrap();
+        let transport = Arc::new(FakeTransport::default());
+        let service = DownloadService::with_transport(transport.clone(), temp.path().to_path_buf(), 0.0);
+        service.set_max_slots(slots);
+        // Failures are final unless a test turns retries on
+        service.set_retry_policy(RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        });
+        (service, transport, temp)
+    }
+
+    /// Wait until `check` holds for the queue.
+    async fn wait_for(service: &DownloadService, check: impl Fn(&[DownloadItem]) -> bool) {
+        for _ in 0..200 {
+            if check(&service.queue().await) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("queue never reached the expected state: {:?}", service.queue().await);
+    }
+
+    fn status_of(queue: &[DownloadItem], package_name: &str) -> Option<DownloadStatus> {
+        queue
+            .iter()
+            .find(|item| item.game.package_name == package_name)
+            .map(|item| item.status)
+    }
+
+    #[tokio::test]
+    async fn slots_fill_by_priority_then_queue_order() {
+        let (service, transport, _temp) = fake_service(2);
+        for package in ["com.one", "com.two", "com.three", "com.four"] {
+            service.add_to_queue(sample_game(package)).await;
+        }
+        assert!(service.set_priority("com.four", 5).await);
+        service.process_queue().await.unwrap();
+
+        wait_for(&service, |queue| {
+            queue.iter().filter(|i| i.status == DownloadStatus::Downloading).count() == 2
+        })
+        .await;
+        assert_eq!(transport.started(), vec!["com.four", "com.one"]);
+        let queue = service.queue().await;
+        let slots: Vec<_> = queue.iter().map(|item| item.slot).collect();
+        assert_eq!(slots, vec![Some(1), None, None, Some(0)]);
+
+        // The freed slot goes to the next in line
+        service.set_priority("com.three", 1).await;
+        assert!(transport.finish("com.four", true));
+        wait_for(&service, |queue| status_of(queue, "com.three") == Some(DownloadStatus::Downloading)).await;
+        let queue = service.queue().await;
+        assert_eq!(status_of(&queue, "com.four"), Some(DownloadStatus::Completed));
+        assert_eq!(queue[2].slot, Some(0));
+
+        for package in ["com.one", "com.three"] {
+            transport.finish(package, true);
+        }
+        wait_for(&service, |queue| status_of(queue, "com.two") == Some(DownloadStatus::Downloading)).await;
+        transport.finish("com.two", true);
+        wait_for(&service, |queue| queue.iter().all(|i| i.status == DownloadStatus::Completed)).await;
+        assert_eq!(transport.started(), vec!["com.four", "com.one", "com.three", "com.two"]);
+        for _ in 0..100 {
+            if !service.is_processing().await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!service.is_processing().await);
+    }
+
+    #[tokio::test]
+    async fn cancelling_one_slot_leaves_the_others_running() {
+        let (service, transport, _temp) = fake_service(3);
+        for package in ["com.one", "com.two", "com.three"] {
+            service.add_to_queue(sample_game(package)).await;
+        }
+        service.process_queue().await.unwrap();
+        wait_for(&service, |queue| queue.iter().all(|i| i.status == DownloadStatus::Downloading)).await;
+
+        assert!(service.cancel_item("com.two").await);
+        assert!(service.pause_item("com.three").await.unwrap());
+        assert!(!service.cancel_item("com.two").await);
+        wait_for(&service, |queue| queue.iter().filter(|i| i.slot.is_some()).count() == 1).await;
+
+        let queue = service.queue().await;
+        assert_eq!(status_of(&queue, "com.one"), Some(DownloadStatus::Downloading));
+        assert_eq!(status_of(&queue, "com.two"), Some(DownloadStatus::Cancelled));
+        assert_eq!(status_of(&queue, "com.three"), Some(DownloadStatus::Paused));
+
+        transport.finish("com.one", false);
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Failed)).await;
+        assert_eq!(service.queue().await[0].error, "stopped");
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported_for_every_slot() {
+        let (service, transport, _temp) = fake_service(2);
+        service.add_to_queue(sample_game("com.one")).await;
+        service.add_to_queue(sample_game("com.two")).await;
+
+        let updates = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = updates.clone();
+        service
+            .process_queue_with_callback(move |item| {
+                seen.lock().unwrap().push((item.game.package_name, item.status, item.progress.percent));
+                async {}
+            })
+            .await
+            .unwrap();
+
+        let progressed = |package: &str| {
+            updates.lock().unwrap().iter().any(|(p, status, percent)| {
+                p == package && *status == DownloadStatus::Downloading && *percent == 50.0
+            })
+        };
+        for _ in 0..200 {
+            if progressed("com.one") && progressed("com.two") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(progressed("com.one") && progressed("com.two"));
+
+        transport.finish("com.one", true);
+        transport.finish("com.two", true);
+        wait_for(&service, |queue| queue.iter().all(|i| i.status == DownloadStatus::Completed)).await;
+        for _ in 0..100 {
+            if updates.lock().unwrap().iter().filter(|(_, status, _)| *status == DownloadStatus::Completed).count() == 2 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("completions were not reported: {:?}", updates.lock().unwrap());
+    }
+
     #[tokio::test]
     async fn add_to_queue_rejects_duplicates() {
         let temp = tempdir().unwrap();
@@ -562,6 +1179,189 @@ mod tests {
         assert_eq!(order, vec!["com.three", "com.one", "com.two"]);
     }
 
+    #[tokio::test]
+    async fn transient_failures_retry_with_backoff() {
+        let (service, transport, _temp) = fake_service(1);
+        service.set_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(40),
+        });
+        service.add_to_queue(sample_game("com.one")).await;
+        service.add_to_queue(sample_game("com.two")).await;
+        service.process_queue().await.unwrap();
+
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Downloading)).await;
+        assert!(transport.fail("com.one", "read tcp: connection reset by peer"));
+        // The next item takes the slot while com.one waits
+        wait_for(&service, |queue| status_of(queue, "com.two") == Some(DownloadStatus::Downloading)).await;
+        let queue = service.queue().await;
+        assert_eq!(queue[0].status, DownloadStatus::Queued);
+        assert_eq!(queue[0].retry_count, 1);
+        assert_eq!(queue[0].failure, Some(DownloadFailure::Connection));
+        assert!(queue[0].next_retry_at.is_some());
+
+        transport.finish("com.two", true);
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Downloading)).await;
+        assert!(transport.fail("com.one", "HTTP error 503 (503 Service Unavailable) returned"));
+        wait_for(&service, |queue| queue[0].retry_count == 2 && queue[0].status == DownloadStatus::Downloading).await;
+        // Out of retries
+        assert!(transport.fail("com.one", "i/o timeout"));
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Failed)).await;
+        let queue = service.queue().await;
+        assert_eq!(queue[0].retry_count, 2);
+        assert_eq!(queue[0].failure, Some(DownloadFailure::Timeout));
+        assert_eq!(queue[0].next_retry_at, None);
+        assert_eq!(transport.started(), vec!["com.one", "com.two", "com.one", "com.one"]);
+
+        // Permanent failures aren't retried, and a resume starts the count over
+        assert!(service.resume_item("com.one").await.unwrap());
+        service.process_queue().await.unwrap();
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Downloading)).await;
+        assert_eq!(service.queue().await[0].retry_count, 0);
+        assert!(transport.fail("com.one", "HTTP error 404 (404 Not Found) returned"));
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Failed)).await;
+        let queue = service.queue().await;
+        assert_eq!(queue[0].retry_count, 0);
+        assert_eq!(queue[0].failure, Some(DownloadFailure::NotFound));
+    }
+
+    #[tokio::test]
+    async fn corrupt_parts_are_downloaded_again() {
+        let (service, transport, _temp) = fake_service(1);
+        let volumes = |names: &[&str]| -> Vec<(String, String)> {
+            names.iter().map(|name| (name.to_string(), format!("{name} data"))).collect()
+        };
+        transport
+            .remote
+            .lock()
+            .unwrap()
+            .insert("com.one".to_string(), volumes(&["one.7z.001", "one.7z.002", "one.7z.003"]));
+        transport
+            .remote
+            .lock()
+            .unwrap()
+            .insert("com.gap".to_string(), volumes(&["gap.7z.001", "gap.7z.003"]));
+        transport.corrupt.lock().unwrap().insert("one.7z.002".to_string());
+        service.add_to_queue(sample_game("com.one")).await;
+        service.add_to_queue(sample_game("com.gap")).await;
+
+        let statuses = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = statuses.clone();
+        service
+            .process_queue_with_callback(move |item| {
+                seen.lock().unwrap().push((item.game.package_name, item.status));
+                async {}
+            })
+            .await
+            .unwrap();
+
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Downloading)).await;
+        assert!(transport.finish("com.one", true));
+        // Verification finds the bad volume and downloads it again
+        for _ in 0..200 {
+            if transport.finish("com.one", true) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        wait_for(&service, |queue| status_of(queue, "com.one") == Some(DownloadStatus::Completed)).await;
+        assert_eq!(
+            *transport.written.lock().unwrap(),
+            vec!["one.7z.001", "one.7z.002", "one.7z.003", "one.7z.002"]
+        );
+        assert!(statuses
+            .lock()
+            .unwrap()
+            .contains(&("com.one".to_string(), DownloadStatus::Verifying)));
+        assert!(service.is_downloaded(&sample_game("com.one")).await);
+
+        // A gap in the remote volumes can't be fixed by downloading again
+        wait_for(&service, |queue| status_of(queue, "com.gap") == Some(DownloadStatus::Downloading)).await;
+        assert!(transport.finish("com.gap", true));
+        wait_for(&service, |queue| status_of(queue, "com.gap") == Some(DownloadStatus::Failed)).await;
+        let queue = service.queue().await;
+        assert_eq!(queue[1].error, "archive volumes not found on the mirror: gap.7z.002");
+        assert_eq!(queue[1].failure, Some(DownloadFailure::NotFound));
+        assert!(!service.is_downloaded(&sample_game("com.gap")).await);
+    }
+
+    #[tokio::test]
+    async fn downloads_that_cannot_fit_are_refused() {
+        let (service, transport, _temp) = fake_service(2);
+        let small = Game {
+            size_bytes: 1000,
+            ..sample_game("com.small")
+        };
+        let huge = Game {
+            size_bytes: u64::MAX / 2,
+            ..sample_game("com.huge")
+        };
+        service.add_to_queue(small.clone()).await;
+        assert_eq!(service.queued_space().await, 2000);
+        let check = service.space_to_queue(&huge).await.unwrap();
+        assert_eq!(check.reserved_bytes, 2000);
+        assert!(!check.fits_alone());
+        assert!(service.space_to_queue(&small).await.unwrap().fits());
+
+        service.add_to_queue(huge).await;
+        service.process_queue().await.unwrap();
+        wait_for(&service, |queue| status_of(queue, "com.huge") == Some(DownloadStatus::Failed)).await;
+        let queue = service.queue().await;
+        assert_eq!(queue[1].failure, Some(DownloadFailure::DiskFull));
+        assert!(queue[1].error.starts_with("not enough space on the download drive"));
+        // The small one still runs
+        wait_for(&service, |queue| status_of(queue, "com.small") == Some(DownloadStatus::Downloading)).await;
+        assert_eq!(transport.started(), vec!["com.small"]);
+        assert_eq!(service.queued_space().await, 2000);
+    }
+
+    #[tokio::test]
+    async fn restored_queue_resumes_interrupted_downloads() {
+        let (service, transport, _temp) = fake_service(1);
+        service.add_to_queue(sample_game("com.one")).await;
+        service.add_to_queue(sample_game("com.two")).await;
+        service.set_priority("com.two", 3).await;
+        service.process_queue().await.unwrap();
+        wait_for(&service, |queue| status_of(queue, "com.two") == Some(DownloadStatus::Downloading)).await;
+        wait_for(&service, |queue| queue[1].progress.percent == 50.0).await;
+
+        let mut saved = service.snapshot().await;
+        assert_eq!(saved[1].status, "downloading");
+        saved[0].status = "paused".to_string();
+        saved[0].error = "network".to_string();
+        saved[0].retry_count = 2;
+        saved.push(QueuedDownload {
+            package_name: "com.gone".to_string(),
+            status: "queued".to_string(),
+            ..QueuedDownload::default()
+        });
+        transport.finish("com.two", true);
+
+        // The next run, with a catalog that only lists com.one
+        let (restored, transport, _temp) = fake_service(1);
+        let count = restored
+            .restore(&saved, |package, release| {
+                (package == "com.one" && release == "Release com.one").then(|| sample_game(package))
+            })
+            .await;
+        assert_eq!(count, 2);
+        let queue = restored.queue().await;
+        assert_eq!(queue[0].status, DownloadStatus::Paused);
+        assert_eq!(queue[0].error, "network");
+        assert_eq!(queue[0].retry_count, 2);
+        // Rebuilt from the saved names
+        assert_eq!(queue[1].game.release_name, "Release com.two");
+        assert_eq!(queue[1].status, DownloadStatus::Queued);
+        assert_eq!(queue[1].priority, 3);
+        assert_eq!(queue[1].progress.percent, 50.0);
+        assert_eq!(restored.restore(&saved, |_, _| None).await, 0);
+
+        restored.process_queue().await.unwrap();
+        wait_for(&restored, |queue| status_of(queue, "com.two") == Some(DownloadStatus::Downloading)).await;
+        assert_eq!(transport.started(), vec!["com.two"]);
+    }
+
     #[tokio::test]
     async fn is_downloaded_detects_apk_or_install_txt() {
         let temp = tempdir().unwrap();
@@ -575,5 +1375,9 @@ mod tests {
 
         std::fs::write(dir.join("install.txt"), "adb shell echo hi").unwrap();
         assert!(service.is_downloaded(&game).await);
+        assert_eq!(
+            downloaded_hashes(temp.path()),
+            HashSet::from([CatalogService::game_name_to_hash(&game.release_name)])
+        );
     }
 }
@@ -0,0 +1,193 @@
+use regex::Regex;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+static STATUS_CODE_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// Why a download failed, as far as the rclone error tells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadFailure {
+    Timeout,
+    /// A 5xx answer from the mirror
+    ServerError,
+    /// Connection reset, refused or dropped
+    Connection,
+    NotFound,
+    /// Bad credentials or access denied
+    Unauthorized,
+    DiskFull,
+    Unknown,
+}
+
+impl DownloadFailure {
+    /// Read the failure from an rclone job error or a transport error.
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| error.contains(needle));
+        let status = STATUS_CODE_PATTERN
+            .get_or_init(|| Regex::new(r"(?:http|status|code)\D{0,8}\b([45]\d\d)\b").unwrap())
+            .captures_iter(&error)
+            .filter_map(|caps| caps[1].parse::<u16>().ok())
+            .find(|code| matches!(code, 401 | 403 | 404 | 408 | 429 | 500..=599));
+
+        if has(&[
+            "no space left",
+            "disk full",
+            "not enough space",
+            "quota exceeded",
+        ]) {
+            Self::DiskFull
+        } else if matches!(status, Some(401 | 403))
+            || has(&[
+                "unauthorized",
+                "forbidden",
+                "authentication",
+                "access denied",
+            ])
+        {
+            Self::Unauthorized
+        } else if status == Some(404)
+            || has(&[
+                "not found",
+                "no such file",
+                "doesn't exist",
+                "does not exist",
+            ])
+        {
+            Self::NotFound
+        } else if status == Some(408) || has(&["timeout", "timed out", "deadline exceeded"]) {
+            Self::Timeout
+        } else if matches!(status, Some(429 | 500..=599))
+            || has(&[
+                "internal server error",
+                "bad gateway",
+                "service unavailable",
+            ])
+        {
+            Self::ServerError
+        } else if has(&[
+            "connection reset",
+            "connection refused",
+            "broken pipe",
+            "unexpected eof",
+            "network is unreachable",
+            "no such host",
+        ]) {
+            Self::Connection
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Whether trying again may help. Unclassified failures are retried too;
+    /// the retry cap keeps that bounded.
+    pub fn is_transient(&self) -> bool {
+        !matches!(self, Self::NotFound | Self::Unauthorized | Self::DiskFull)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::ServerError => "server_error",
+            Self::Connection => "connection",
+            Self::NotFound => "not_found",
+            Self::Unauthorized => "unauthorized",
+            Self::DiskFull => "disk_full",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// How often and how soon failed downloads are tried again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 turns retrying off
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each one after
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry`, from 1, or `None` once the retries
+    /// are used up.
+    pub fn delay(&self, retry: u32) -> Option<Duration> {
+        if retry == 0 || retry > self.max_retries {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(retry - 1);
+        Some(self.base_delay.saturating_mul(factor).min(self.max_delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_rclone_errors() {
+        let cases = [
+            (
+                "Get \"https://mirror/x\": dial tcp: i/o timeout",
+                DownloadFailure::Timeout,
+            ),
+            (
+                "HTTP error 503 (503 Service Unavailable) returned",
+                DownloadFailure::ServerError,
+            ),
+            (
+                "read tcp 10.0.0.2:5123: connection reset by peer",
+                DownloadFailure::Connection,
+            ),
+            ("directory not found", DownloadFailure::NotFound),
+            (
+                "HTTP error 404 (404 Not Found) returned",
+                DownloadFailure::NotFound,
+            ),
+            (
+                "HTTP error 403 (403 Forbidden) returned",
+                DownloadFailure::Unauthorized,
+            ),
+            (
+                "write /data/x.7z.001: no space left on device",
+                DownloadFailure::DiskFull,
+            ),
+            ("something odd happened", DownloadFailure::Unknown),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(DownloadFailure::classify(error), expected, "{error}");
+        }
+        // Numbers outside an HTTP status are not status codes
+        assert_eq!(
+            DownloadFailure::classify("copied 404 files, then: connection refused"),
+            DownloadFailure::Connection
+        );
+        assert!(DownloadFailure::Timeout.is_transient());
+        assert!(!DownloadFailure::DiskFull.is_transient());
+    }
+
+    #[test]
+    fn delays_double_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60),
+        };
+        let delays: Vec<_> = (0..=6).map(|retry| policy.delay(retry)).collect();
+        let secs = |s| Some(Duration::from_secs(s));
+        assert_eq!(
+            delays,
+            vec![None, secs(10), secs(20), secs(40), secs(60), secs(60), None]
+        );
+    }
+}
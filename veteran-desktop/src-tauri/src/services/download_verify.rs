@@ -0,0 +1,147 @@
+use crate::services::rclone::RemoteFile;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static VOLUME_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// File left in a release folder once its download checked out against the
+/// remote, since the folder holds only archive volumes until extraction.
+pub const VERIFIED_MARKER: &str = ".verified";
+
+/// Outcome of checking a downloaded release folder against its listing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Verification {
+    /// Files missing locally, or with the wrong size or MD5
+    pub bad_files: Vec<String>,
+    /// Gaps in the `.7z.001` volume sequence of the listing itself, which no
+    /// download can fix
+    pub missing_volumes: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_ok(&self) -> bool {
+        self.bad_files.is_empty() && self.missing_volumes.is_empty()
+    }
+}
+
+/// Compare the files in `dir` with the `remote` listing: every listed file
+/// must exist with the listed size, and the listed MD5 where there is one.
+/// Reads every hashed file, so run it off the async runtime.
+pub fn verify_release(dir: &Path, remote: &[RemoteFile]) -> Verification {
+    let bad_files = remote
+        .iter()
+        .filter(|file| !file_matches(&dir.join(&file.path), file))
+        .map(|file| file.path.clone())
+        .collect();
+    Verification {
+        bad_files,
+        missing_volumes: missing_volumes(remote.iter().map(|file| file.path.as_str())),
+    }
+}
+
+fn file_matches(path: &Path, remote: &RemoteFile) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() || metadata.len() != remote.size {
+        return false;
+    }
+    match &remote.md5 {
+        Some(expected) => file_md5(path).is_ok_and(|actual| actual == *expected),
+        None => true,
+    }
+}
+
+fn file_md5(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buffer = vec![0; 1 << 20];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.consume(&buffer[..read]);
+    }
+    Ok(format!("{:x}", context.finalize()))
+}
+
+/// Volumes absent between `.7z.001` and the last volume of each archive
+/// among `paths`, e.g. `game.7z.002` when only 001 and 003 exist.
+pub fn missing_volumes<'a>(paths: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let pattern = VOLUME_PATTERN.get_or_init(|| Regex::new(r"(?i)^(.+\.7z)\.(\d{3})$").unwrap());
+    let mut archives: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    for path in paths {
+        if let Some(caps) = pattern.captures(path) {
+            if let Ok(number) = caps[2].parse() {
+                archives
+                    .entry(caps[1].to_string())
+                    .or_default()
+                    .push(number);
+            }
+        }
+    }
+
+    let mut missing = Vec::new();
+    for (archive, numbers) in archives {
+        let last = numbers.iter().copied().max().unwrap_or(0);
+        for number in 1..=last {
+            if !numbers.contains(&number) {
+                missing.push(format!("{archive}.{number:03}"));
+            }
+        }
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn remote(path: &str, content: &str, hashed: bool) -> RemoteFile {
+        RemoteFile {
+            path: path.to_string(),
+            size: content.len() as u64,
+            md5: hashed.then(|| format!("{:x}", md5::compute(content))),
+        }
+    }
+
+    #[test]
+    fn finds_missing_volumes() {
+        assert!(missing_volumes(["a.7z.001", "a.7z.002", "notes.txt"]).is_empty());
+        assert_eq!(
+            missing_volumes(["a.7z.001", "a.7z.004", "b/b.7z.002"]),
+            vec!["a.7z.002", "a.7z.003", "b/b.7z.001"]
+        );
+    }
+
+    #[test]
+    fn flags_missing_resized_and_corrupt_files() {
+        let temp = tempdir().unwrap();
+        std::fs::write(temp.path().join("a.7z.001"), "aaaa").unwrap();
+        std::fs::write(temp.path().join("a.7z.002"), "bbbX").unwrap();
+        std::fs::write(temp.path().join("a.7z.003"), "cc").unwrap();
+        std::fs::write(temp.path().join("a.7z.004"), "dddX").unwrap();
+
+        let listing = [
+            remote("a.7z.001", "aaaa", true),
+            remote("a.7z.002", "bbbb", true),
+            remote("a.7z.003", "ccc", false),
+            // Without a hash only the size is compared
+            remote("a.7z.004", "dddd", false),
+            remote("a.7z.005", "e", false),
+        ];
+        let verification = verify_release(temp.path(), &listing);
+        assert_eq!(
+            verification.bad_files,
+            vec!["a.7z.002", "a.7z.003", "a.7z.005"]
+        );
+        assert!(verification.missing_volumes.is_empty());
+        assert!(!verification.is_ok());
+        assert!(verify_release(temp.path(), &listing[..1]).is_ok());
+    }
+}
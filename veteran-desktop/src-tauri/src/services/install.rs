@@ -4,6 +4,7 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use zeroize::Zeroizing;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InstallResult {
@@ -50,7 +51,7 @@ impl InstallService {
         package_name: &str,
         release_name: &str,
         serial: Option<&str>,
-        password: Option<String>,
+        password: Option<Zeroizing<String>>,
         status_sender: Option<tokio::sync::mpsc::UnboundedSender<String>>,
     ) -> Result<InstallResult> {
         crate::logger::log(&format!(
@@ -88,7 +89,7 @@ impl InstallService {
 
         for archive in &archives {
             crate::logger::log(&format!("[INSTALL] Extracting: {} → {}", archive.display(), download_dir.display()));
-            if let Err(e) = crate::services::extract::ExtractService::extract_7z(archive, download_dir, password.as_deref()) {
+            if let Err(e) = crate::services::extract::ExtractService::extract_7z(archive, download_dir, password.as_deref().map(String::as_str)) {
                 crate::logger::log(&format!("[INSTALL] Extraction failed: {:?}", e));
                 return Err(e);
             }
@@ -126,13 +127,14 @@ impl InstallService {
     }
 
     /// Install from a game directory that contains the APK, OBB, and optionally install.txt.
-    /// This matches the Windows sideloader's install logic after extraction.
-    async fn install_from_game_dir(
+    /// This matches the Windows sideloader's install logic after extraction, and
+    /// is also how imported local entries are installed.
+    pub async fn install_from_game_dir(
         &self,
         game_dir: &Path,
         package_name: &str,
         serial: Option<&str>,
-        password: Option<String>,
+        password: Option<Zeroizing<String>>,
         status_sender: Option<tokio::sync::mpsc::UnboundedSender<String>>,
     ) -> Result<InstallResult> {
         crate::logger::log(&format!("[INSTALL] Installing from game dir: {}", game_dir.display()));
@@ -151,7 +153,7 @@ impl InstallService {
             for archive in &nested_archives {
                 crate::logger::log(&format!("[INSTALL] Extracting nested archive: {}", archive.display()));
                 let extract_dir = archive.parent().unwrap_or(game_dir);
-                let _ = crate::services::extract::ExtractService::extract_7z(archive, extract_dir, password.as_deref());
+                let _ = crate::services::extract::ExtractService::extract_7z(archive, extract_dir, password.as_deref().map(String::as_str));
             }
 
             let (success, message) = self
@@ -507,6 +509,11 @@ impl InstallService {
     }
 }
 
+/// Whether `install_from_game_dir` would find an install.txt or an APK in `game_dir`.
+pub fn is_installable_dir(game_dir: &Path) -> bool {
+    find_install_txt(game_dir).is_some() || find_first_apk(game_dir).is_some()
+}
+
 /// Find install.txt (case-insensitive) in the game directory.
 fn find_install_txt(game_dir: &Path) -> Option<PathBuf> {
     let lower = game_dir.join("install.txt");
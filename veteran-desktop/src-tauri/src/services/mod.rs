@@ -1,9 +1,22 @@
 pub mod adb;
 pub mod binary_paths;
 pub mod catalog;
+pub mod catalog_db;
+pub mod catalog_diff;
+pub mod catalog_export;
+pub mod catalog_sync;
 pub mod config;
+pub mod disk_space;
 pub mod download;
+pub mod download_retry;
+pub mod download_verify;
 pub mod extract;
 pub mod install;
+pub mod popularity;
+pub mod query;
 pub mod rclone;
+pub mod search;
+pub mod secret_store;
 pub mod settings;
+pub mod source_secrets;
+pub mod trailer;
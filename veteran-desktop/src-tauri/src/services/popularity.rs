@@ -0,0 +1,272 @@
+use crate::models::catalog_report::PopularityTrend;
+use crate::models::game::Game;
+use std::collections::{BTreeMap, HashMap};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Samples older than this many days are pruned, a little over the longest
+/// trend window.
+pub const HISTORY_DAYS: i64 = 45;
+
+/// Downloads and popularity rank of one package as of one sync.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopularitySample {
+    pub taken_at: i64,
+    pub downloads: f64,
+    /// 0 when the package had no downloads to rank by
+    pub rank: i32,
+}
+
+impl PopularitySample {
+    pub fn day(&self) -> i64 {
+        self.taken_at.div_euclid(SECONDS_PER_DAY)
+    }
+}
+
+/// Popularity samples per package, one per day, oldest first. A sync
+/// replaces the sample of the day it runs on, so trends don't depend on how
+/// often the catalog is synced.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PopularityHistory {
+    packages: HashMap<String, Vec<PopularitySample>>,
+}
+
+impl PopularityHistory {
+    pub fn from_samples(samples: impl IntoIterator<Item = (String, PopularitySample)>) -> Self {
+        let mut history = Self::default();
+        for (package, sample) in samples {
+            history.insert(package, sample);
+        }
+        history
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Add the samples of a sync and drop those older than [`HISTORY_DAYS`].
+    pub fn record(&mut self, current: &BTreeMap<String, PopularitySample>, now: i64) {
+        for (package, sample) in current {
+            self.insert(package.clone(), *sample);
+        }
+        let oldest_day = now.div_euclid(SECONDS_PER_DAY) - HISTORY_DAYS;
+        self.packages.retain(|_, samples| {
+            samples.retain(|sample| sample.day() >= oldest_day);
+            !samples.is_empty()
+        });
+    }
+
+    fn insert(&mut self, package: String, sample: PopularitySample) {
+        let samples = self.packages.entry(package).or_default();
+        match samples.binary_search_by_key(&sample.day(), PopularitySample::day) {
+            Ok(index) => samples[index] = sample,
+            Err(index) => samples.insert(index, sample),
+        }
+    }
+
+    /// Trends of the packages in `current`, the samples of the latest sync.
+    pub fn trends(
+        &self,
+        current: &BTreeMap<String, PopularitySample>,
+        now: i64,
+    ) -> HashMap<String, PopularityTrend> {
+        current
+            .iter()
+            .map(|(package, latest)| {
+                let samples = self.packages.get(package).map(Vec::as_slice);
+                (
+                    package.clone(),
+                    trend(samples.unwrap_or_default(), latest, now),
+                )
+            })
+            .collect()
+    }
+}
+
+fn trend(samples: &[PopularitySample], latest: &PopularitySample, now: i64) -> PopularityTrend {
+    let today = now.div_euclid(SECONDS_PER_DAY);
+    let baseline = |days: i64| {
+        // The newest sample at least a full window old, else the oldest one
+        samples
+            .iter()
+            .rev()
+            .find(|sample| sample.day() <= today - days)
+            .or_else(|| samples.first())
+            .filter(|sample| sample.day() < latest.day())
+    };
+    let growth = |days: i64| baseline(days).map(|from| latest.downloads - from.downloads);
+    let rank_change = |days: i64| {
+        baseline(days)
+            .filter(|from| from.rank > 0 && latest.rank > 0)
+            .map(|from| from.rank - latest.rank)
+    };
+
+    PopularityTrend {
+        downloads: latest.downloads,
+        rank: latest.rank,
+        growth_7d: growth(7),
+        growth_30d: growth(30),
+        rank_change_7d: rank_change(7),
+        rank_change_30d: rank_change(30),
+        tracked_days: samples
+            .first()
+            .map_or(0, |first| (latest.day() - first.day()).max(0) as u32),
+    }
+}
+
+/// One sample per package from the deduplicated catalog, whose ranks were
+/// assigned from the same downloads.
+pub fn current_samples(games: &[Game], now: i64) -> BTreeMap<String, PopularitySample> {
+    let mut samples: BTreeMap<String, PopularitySample> = BTreeMap::new();
+    for game in games.iter().filter(|game| !game.is_local()) {
+        let sample = samples
+            .entry(game.package_name.clone())
+            .or_insert(PopularitySample {
+                taken_at: now,
+                downloads: game.downloads,
+                rank: game.popularity_rank,
+            });
+        if game.downloads > sample.downloads {
+            sample.downloads = game.downloads;
+        }
+    }
+    samples
+}
+
+/// Order for the `trending` library sort, most trending first: downloads
+/// gained this week, then places climbed, then current rank. Packages without
+/// a trend yet go last.
+pub fn compare_trending(
+    a: Option<&PopularityTrend>,
+    b: Option<&PopularityTrend>,
+) -> std::cmp::Ordering {
+    let key = |trend: Option<&PopularityTrend>| {
+        trend.map(|trend| {
+            (
+                trend.growth_7d.unwrap_or(f64::NEG_INFINITY),
+                trend.rank_change_7d.unwrap_or(i32::MIN),
+                // Lower rank is more popular, unranked last
+                if trend.rank > 0 {
+                    -trend.rank
+                } else {
+                    i32::MIN
+                },
+            )
+        })
+    };
+    match (key(a), key(b)) {
+        (Some(a), Some(b)) => {
+            b.0.total_cmp(&a.0)
+                .then_with(|| b.1.cmp(&a.1))
+                .then_with(|| b.2.cmp(&a.2))
+        }
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn sync(history: &mut PopularityHistory, now: i64, packages: &[(&str, f64, i32)]) {
+        let current = packages
+            .iter()
+            .map(|(package, downloads, rank)| {
+                (
+                    package.to_string(),
+                    PopularitySample {
+                        taken_at: now,
+                        downloads: *downloads,
+                        rank: *rank,
+                    },
+                )
+            })
+            .collect();
+        history.record(&current, now);
+    }
+
+    fn trends(history: &PopularityHistory, now: i64) -> HashMap<String, PopularityTrend> {
+        let current = history
+            .packages
+            .iter()
+            .filter_map(|(package, samples)| {
+                samples
+                    .last()
+                    .filter(|sample| sample.day() == now / DAY)
+                    .map(|sample| (package.clone(), *sample))
+            })
+            .collect();
+        history.trends(&current, now)
+    }
+
+    #[test]
+    fn trends_measure_growth_and_rank_movement() {
+        let start = 100 * DAY;
+        let mut history = PopularityHistory::default();
+        sync(&mut history, start, &[("a", 100.0, 1), ("b", 50.0, 2)]);
+        sync(
+            &mut history,
+            start + 23 * DAY,
+            &[("a", 160.0, 1), ("b", 90.0, 2)],
+        );
+        // Two syncs on one day keep only the later sample
+        sync(
+            &mut history,
+            start + 30 * DAY,
+            &[("a", 170.0, 2), ("b", 1.0, 9)],
+        );
+        sync(
+            &mut history,
+            start + 30 * DAY + 60,
+            &[("a", 170.0, 2), ("b", 200.0, 1)],
+        );
+
+        let trends = trends(&history, start + 30 * DAY + 60);
+        assert_eq!(trends["a"].growth_7d, Some(10.0));
+        assert_eq!(trends["a"].growth_30d, Some(70.0));
+        assert_eq!(trends["a"].rank_change_7d, Some(-1));
+        assert_eq!(trends["b"].growth_7d, Some(110.0));
+        assert_eq!(trends["b"].rank_change_30d, Some(1));
+        assert_eq!(trends["b"].tracked_days, 30);
+        assert_eq!(history.packages["b"].len(), 3);
+
+        assert_eq!(
+            compare_trending(trends.get("b"), trends.get("a")),
+            std::cmp::Ordering::Less
+        );
+        assert_eq!(
+            compare_trending(trends.get("a"), None),
+            std::cmp::Ordering::Less
+        );
+    }
+
+    #[test]
+    fn short_history_measures_from_the_oldest_sample() {
+        let mut history = PopularityHistory::default();
+        sync(&mut history, 10 * DAY, &[("a", 10.0, 3)]);
+        assert_eq!(trends(&history, 10 * DAY)["a"].growth_7d, None);
+
+        sync(&mut history, 12 * DAY, &[("a", 25.0, 1), ("c", 5.0, 0)]);
+        let trends = trends(&history, 12 * DAY);
+        assert_eq!(trends["a"].growth_7d, Some(15.0));
+        assert_eq!(trends["a"].growth_30d, Some(15.0));
+        assert_eq!(trends["a"].rank_change_7d, Some(2));
+        assert_eq!(trends["a"].tracked_days, 2);
+        assert_eq!(trends["c"].growth_7d, None);
+    }
+
+    #[test]
+    fn old_samples_are_pruned() {
+        let mut history = PopularityHistory::default();
+        sync(&mut history, 0, &[("gone", 1.0, 1)]);
+        sync(&mut history, DAY, &[("a", 1.0, 1)]);
+        sync(&mut history, (HISTORY_DAYS + 1) * DAY, &[("a", 2.0, 1)]);
+
+        assert!(!history.packages.contains_key("gone"));
+        assert_eq!(history.packages["a"].len(), 2);
+    }
+}
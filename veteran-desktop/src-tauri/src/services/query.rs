@@ -0,0 +1,524 @@
+use crate::models::config::PRIMARY_SOURCE;
+use crate::models::game::Game;
+use crate::models::size::parse_catalog_size;
+use crate::models::tags::normalize_tag;
+use crate::services::search::tokenize;
+use chrono::NaiveDate;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Comparison operator in front of a filter value, e.g. the `<` in `size:<2GB`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn split(value: &str) -> (Self, &str) {
+        for (prefix, comparison) in [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (comparison, rest.trim());
+            }
+        }
+        (Comparison::Equal, value.trim())
+    }
+
+    fn matches<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Size in bytes
+    Size(Comparison, u64),
+    Updated(Comparison, NaiveDate),
+    Downloads(Comparison, f64),
+    Version(Comparison, i64),
+    Installed(bool),
+    Favorite(bool),
+    /// Catalog source name, lowercase
+    Source(String),
+    /// A word or phrase in the release notes, lowercase
+    Note(String),
+    /// Any of these normalized tags, from `tag:multiplayer,co-op`
+    Tag(Vec<String>),
+    /// A word or quoted phrase, only produced for exclusions like `-mod`
+    Term(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clause {
+    pub filter: Filter,
+    pub negated: bool,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum QueryError {
+    #[error("`{0}:` is missing a value")]
+    MissingValue(String),
+    #[error("`{key}:{value}` is invalid, expected {expected}")]
+    InvalidValue {
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+    #[error("`{0}:` does not take a comparison operator")]
+    UnexpectedOperator(String),
+    #[error("unterminated quote in query")]
+    UnterminatedQuote,
+}
+
+/// Device, user and notes state that some filters need (`installed:`,
+/// `favorite:`, `note:`).
+#[derive(Debug, Clone, Default)]
+pub struct FilterContext {
+    pub favorites: HashSet<String>,
+    /// Packages installed on the selected device, if it was queried
+    pub installed: Option<HashSet<String>>,
+    /// Packages whose notes match each `note:` term, see [`CatalogQuery::note_terms`]
+    pub notes: HashMap<String, HashSet<String>>,
+}
+
+/// A library query split into free text for the search index and filter clauses.
+///
+/// `size:<2GB updated:>2024-06-01 downloads:>500 installed:no favorite:yes note:multiplayer tag:rhythm -mod beat`
+/// keeps `beat` as text and turns everything else into clauses. Unknown
+/// `key:value` words stay in the text, so the `release:` and `pkg:` search
+/// prefixes keep working.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CatalogQuery {
+    pub text: String,
+    pub clauses: Vec<Clause>,
+}
+
+impl CatalogQuery {
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let mut text_words = Vec::new();
+        let mut clauses = Vec::new();
+
+        for word in split_words(input)? {
+            let (negated, body) = match word.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, word.as_str()),
+            };
+
+            if let Some(filter) = Self::parse_filter(body)? {
+                clauses.push(Clause { filter, negated });
+            } else if negated {
+                clauses.push(Clause {
+                    filter: Filter::Term(body.to_lowercase()),
+                    negated: true,
+                });
+            } else {
+                text_words.push(word);
+            }
+        }
+
+        Ok(Self {
+            text: text_words.join(" "),
+            clauses,
+        })
+    }
+
+    fn parse_filter(word: &str) -> Result<Option<Filter>, QueryError> {
+        let Some((key, value)) = word.split_once(':') else {
+            return Ok(None);
+        };
+        let key = key.to_lowercase();
+        let filter_key = match key.as_str() {
+            "size" | "updated" | "date" | "downloads" | "version" | "installed" | "favorite"
+            | "fav" | "source" | "note" | "notes" | "tag" | "tags" => key.as_str(),
+            _ => return Ok(None),
+        };
+
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(QueryError::MissingValue(key));
+        }
+        let (comparison, operand) = Comparison::split(value);
+        let invalid = |expected| QueryError::InvalidValue {
+            key: key.clone(),
+            value: value.to_string(),
+            expected,
+        };
+
+        let filter = match filter_key {
+            "size" => Filter::Size(
+                comparison,
+                parse_catalog_size(operand).ok_or_else(|| invalid("a size like 2GB or 500MB"))?,
+            ),
+            "updated" | "date" => Filter::Updated(
+                comparison,
+                NaiveDate::parse_from_str(operand, "%Y-%m-%d")
+                    .map_err(|_| invalid("a date like 2024-06-01"))?,
+            ),
+            "downloads" => Filter::Downloads(
+                comparison,
+                operand.parse::<f64>().map_err(|_| invalid("a number"))?,
+            ),
+            "version" => Filter::Version(
+                comparison,
+                operand
+                    .parse::<i64>()
+                    .map_err(|_| invalid("an integer version code"))?,
+            ),
+            _ => {
+                if operand != value {
+                    return Err(QueryError::UnexpectedOperator(key));
+                }
+                match filter_key {
+                    "source" => return Ok(Some(Filter::Source(value.to_lowercase()))),
+                    "note" | "notes" => return Ok(Some(Filter::Note(value.to_lowercase()))),
+                    "tag" | "tags" => {
+                        let tags: Vec<String> = value
+                            .split(',')
+                            .map(normalize_tag)
+                            .filter(|tag| !tag.is_empty())
+                            .collect();
+                        if tags.is_empty() {
+                            return Err(QueryError::MissingValue(key));
+                        }
+                        return Ok(Some(Filter::Tag(tags)));
+                    }
+                    _ => {}
+                }
+                let flag = parse_flag(value).ok_or_else(|| invalid("yes or no"))?;
+                if filter_key == "installed" {
+                    Filter::Installed(flag)
+                } else {
+                    Filter::Favorite(flag)
+                }
+            }
+        };
+
+        Ok(Some(filter))
+    }
+
+    pub fn has_clauses(&self) -> bool {
+        !self.clauses.is_empty()
+    }
+
+    /// Whether evaluating the query requires the device's installed packages.
+    pub fn needs_installed(&self) -> bool {
+        self.clauses
+            .iter()
+            .any(|clause| matches!(clause.filter, Filter::Installed(_)))
+    }
+
+    /// Terms of the `note:` clauses, to look up in the notes index before matching.
+    pub fn note_terms(&self) -> Vec<&str> {
+        self.clauses
+            .iter()
+            .filter_map(|clause| match &clause.filter {
+                Filter::Note(term) => Some(term.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Tags every result must carry (one of, for `tag:a,b`), for highlighting
+    /// the active filter chips.
+    pub fn required_tags(&self) -> Vec<&str> {
+        self.clauses
+            .iter()
+            .filter(|clause| !clause.negated)
+            .filter_map(|clause| match &clause.filter {
+                Filter::Tag(tags) => Some(tags.iter().map(String::as_str)),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn matches(&self, game: &Game, context: &FilterContext) -> bool {
+        self.clauses
+            .iter()
+            .all(|clause| Self::evaluate(&clause.filter, game, context) != clause.negated)
+    }
+
+    fn evaluate(filter: &Filter, game: &Game, context: &FilterContext) -> bool {
+        match filter {
+            Filter::Size(comparison, bytes) => {
+                game.size_bytes > 0 && comparison.matches(game.size_bytes, *bytes)
+            }
+            Filter::Updated(comparison, date) => game
+                .last_updated
+                .is_some_and(|updated| comparison.matches(updated, *date)),
+            Filter::Downloads(comparison, downloads) => {
+                comparison.matches(game.downloads, *downloads)
+            }
+            Filter::Version(comparison, version) => {
+                game.version_code > 0 && comparison.matches(game.version_code, *version)
+            }
+            Filter::Installed(flag) => {
+                let installed = context
+                    .installed
+                    .as_ref()
+                    .is_some_and(|packages| packages.contains(&game.package_name));
+                installed == *flag
+            }
+            Filter::Favorite(flag) => context.favorites.contains(&game.package_name) == *flag,
+            Filter::Source(source) => {
+                let game_source = if game.source.is_empty() {
+                    PRIMARY_SOURCE
+                } else {
+                    game.source.as_str()
+                };
+                game_source.eq_ignore_ascii_case(source)
+            }
+            Filter::Note(term) => context
+                .notes
+                .get(term)
+                .is_some_and(|packages| packages.contains(&game.package_name)),
+            Filter::Tag(tags) => tags.iter().any(|tag| game.tags.contains(tag)),
+            Filter::Term(term) => {
+                let fields = [&game.game_name, &game.release_name, &game.package_name];
+                let term_tokens = tokenize(term);
+                if term_tokens.len() == 1 {
+                    fields
+                        .iter()
+                        .any(|field| tokenize(field).contains(&term_tokens[0]))
+                } else {
+                    fields
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(term.as_str()))
+                }
+            }
+        }
+    }
+}
+
+/// Split on whitespace, keeping double-quoted runs together (quotes are dropped).
+fn split_words(input: &str) -> Result<Vec<String>, QueryError> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(QueryError::UnterminatedQuote);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Some(true),
+        "no" | "n" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(name: &str, size: &str, updated: &str, downloads: &str) -> Game {
+        let row = format!(
+            "{name};{name} v1+1.0;com.test.{};10;{updated};{size};{downloads}",
+            name.to_lowercase().replace(' ', "")
+        );
+        let fields: Vec<&str> = row.split(';').collect();
+        Game::from_csv_row(&fields).unwrap()
+    }
+
+    #[test]
+    fn parse_splits_text_and_clauses() {
+        let query = CatalogQuery::parse(
+            "beat size:<2GB updated:>2024-06-01 downloads:>500 installed:no favorite:yes -mod",
+        )
+        .unwrap();
+
+        assert_eq!(query.text, "beat");
+        assert_eq!(query.clauses.len(), 6);
+        assert_eq!(
+            query.clauses[0].filter,
+            Filter::Size(Comparison::Less, 2 * 1024 * 1024 * 1024)
+        );
+        assert_eq!(
+            query.clauses[1].filter,
+            Filter::Updated(
+                Comparison::Greater,
+                NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()
+            )
+        );
+        assert_eq!(query.clauses[3].filter, Filter::Installed(false));
+        assert_eq!(
+            query.clauses[5],
+            Clause {
+                filter: Filter::Term("mod".to_string()),
+                negated: true
+            }
+        );
+        assert!(query.needs_installed());
+    }
+
+    #[test]
+    fn parse_keeps_search_prefixes_as_text() {
+        let query = CatalogQuery::parse("size:>=1GB release:Beat Saber").unwrap();
+        assert_eq!(query.text, "release:Beat Saber");
+        assert_eq!(query.clauses.len(), 1);
+    }
+
+    #[test]
+    fn parse_reports_bad_values() {
+        assert_eq!(
+            CatalogQuery::parse("size:<huge").unwrap_err().to_string(),
+            "`size:<huge` is invalid, expected a size like 2GB or 500MB"
+        );
+        assert_eq!(
+            CatalogQuery::parse("updated:>06/01/2024").unwrap_err(),
+            QueryError::InvalidValue {
+                key: "updated".to_string(),
+                value: ">06/01/2024".to_string(),
+                expected: "a date like 2024-06-01",
+            }
+        );
+        assert_eq!(
+            CatalogQuery::parse("installed:").unwrap_err(),
+            QueryError::MissingValue("installed".to_string())
+        );
+        assert_eq!(
+            CatalogQuery::parse("favorite:>yes").unwrap_err(),
+            QueryError::UnexpectedOperator("favorite".to_string())
+        );
+        assert_eq!(
+            CatalogQuery::parse("-\"walking dead").unwrap_err(),
+            QueryError::UnterminatedQuote
+        );
+    }
+
+    #[test]
+    fn matches_numeric_and_date_filters() {
+        let small = game("Small", "500 MB", "2024-07-01", "900");
+        let large = game("Large", "3 GB", "2024-01-01", "100");
+        let context = FilterContext::default();
+
+        let query = CatalogQuery::parse("size:<2GB").unwrap();
+        assert!(query.matches(&small, &context));
+        assert!(!query.matches(&large, &context));
+
+        let query = CatalogQuery::parse("updated:>2024-06-01 downloads:>500").unwrap();
+        assert!(query.matches(&small, &context));
+        assert!(!query.matches(&large, &context));
+
+        let query = CatalogQuery::parse("-size:<2GB").unwrap();
+        assert!(!query.matches(&small, &context));
+        assert!(query.matches(&large, &context));
+    }
+
+    #[test]
+    fn matches_context_filters() {
+        let game = game("Beat Saber", "500 MB", "2024-07-01", "900");
+        let mut context = FilterContext::default();
+
+        let favorite = CatalogQuery::parse("favorite:yes").unwrap();
+        assert!(!favorite.matches(&game, &context));
+        context.favorites.insert(game.package_name.clone());
+        assert!(favorite.matches(&game, &context));
+
+        let not_installed = CatalogQuery::parse("installed:no").unwrap();
+        assert!(not_installed.matches(&game, &context));
+        context.installed = Some(HashSet::from([game.package_name.clone()]));
+        assert!(!not_installed.matches(&game, &context));
+
+        let multiplayer = CatalogQuery::parse("note:Multiplayer").unwrap();
+        assert_eq!(multiplayer.note_terms(), vec!["multiplayer"]);
+        assert!(!multiplayer.matches(&game, &context));
+        context.notes.insert(
+            "multiplayer".to_string(),
+            HashSet::from([game.package_name.clone()]),
+        );
+        assert!(multiplayer.matches(&game, &context));
+        assert!(!CatalogQuery::parse("-note:multiplayer")
+            .unwrap()
+            .matches(&game, &context));
+
+        // Releases without a source belong to the public one
+        assert!(CatalogQuery::parse("source:VRP")
+            .unwrap()
+            .matches(&game, &context));
+        let mirrored = Game {
+            source: "mirror".to_string(),
+            ..game
+        };
+        assert!(CatalogQuery::parse("source:mirror")
+            .unwrap()
+            .matches(&mirrored, &context));
+        assert!(CatalogQuery::parse("-source:vrp")
+            .unwrap()
+            .matches(&mirrored, &context));
+    }
+
+    #[test]
+    fn matches_tags() {
+        let tagged = Game {
+            tags: vec!["mixed-reality".to_string(), "rhythm".to_string()],
+            ..game("Beat Saber", "500 MB", "2024-07-01", "900")
+        };
+        let context = FilterContext::default();
+
+        let query = CatalogQuery::parse("tag:Rhythm tag:fitness,mixed_reality").unwrap();
+        assert_eq!(
+            query.required_tags(),
+            vec!["rhythm", "fitness", "mixed-reality"]
+        );
+        assert!(query.matches(&tagged, &context));
+        assert!(!CatalogQuery::parse("tag:rhythm tag:horror")
+            .unwrap()
+            .matches(&tagged, &context));
+        assert!(!CatalogQuery::parse("-tag:rhythm")
+            .unwrap()
+            .matches(&tagged, &context));
+        assert_eq!(
+            CatalogQuery::parse("tag:,").unwrap_err(),
+            QueryError::MissingValue("tag".to_string())
+        );
+    }
+
+    #[test]
+    fn excludes_whole_words_only() {
+        let modded = game("Beat Saber", "500 MB", "2024-07-01", "900");
+        let modded = Game {
+            release_name: "Beat Saber v1+1.0 -mod".to_string(),
+            ..modded
+        };
+        let modern = game("Modern Warfare", "500 MB", "2024-07-01", "900");
+        let context = FilterContext::default();
+
+        let query = CatalogQuery::parse("-mod").unwrap();
+        assert!(!query.matches(&modded, &context));
+        assert!(query.matches(&modern, &context));
+    }
+}
@@ -1,14 +1,22 @@
 use crate::logger;
-use crate::models::config::PublicConfig;
+use crate::models::config::{usable_sources, CatalogSource, CatalogSourceKind, PublicConfig, PRIMARY_SOURCE};
+use crate::models::size::{parse_size, UnitBase};
+use crate::services::catalog::CatalogService;
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::TcpListener;
 use std::path::Path;
 use std::process::Stdio;
 use std::sync::RwLock;
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
+use zeroize::Zeroizing;
+
+/// `active_jobs` key prefix for `meta.7z` copies, so cancelling downloads
+/// leaves a running catalog sync alone.
+const META_JOB_PREFIX: &str = "meta.7z:";
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct DownloadProgress {
@@ -29,6 +37,70 @@ impl DownloadProgress {
     }
 }
 
+/// A file on a remote as reported by `operations/stat`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteFileStat {
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+impl RemoteFileStat {
+    fn from_rc_item(item: &Value) -> Option<Self> {
+        if item.get("IsDir").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return None;
+        }
+        let size = item.get("Size")?.as_i64()?;
+        let modified = item
+            .get("ModTime")
+            .and_then(|v| v.as_str())
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|time| time.with_timezone(&Utc));
+        Some(Self {
+            size: size.max(0) as u64,
+            modified,
+        })
+    }
+
+    /// Whether the local file at `path` is this file: same size, and a modtime
+    /// within a second, since rclone keeps the remote modtime on copies.
+    pub fn matches_file(&self, path: &Path) -> bool {
+        let Some(modified) = self.modified else {
+            return false;
+        };
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return false;
+        };
+        let Ok(local_modified) = metadata.modified() else {
+            return false;
+        };
+        let local_modified = DateTime::<Utc>::from(local_modified);
+        metadata.len() == self.size && (local_modified - modified).num_milliseconds().abs() < 1000
+    }
+}
+
+/// A file of a release folder on its source, as listed by `operations/list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteFile {
+    /// Path relative to the release folder
+    pub path: String,
+    pub size: u64,
+    /// MD5 from `operations/hashsum`, when the remote has one
+    pub md5: Option<String>,
+}
+
+impl RemoteFile {
+    fn from_rc_item(item: &Value) -> Option<Self> {
+        if item.get("IsDir").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return None;
+        }
+        Some(Self {
+            path: item.get("Path")?.as_str()?.to_string(),
+            size: item.get("Size")?.as_i64()?.max(0) as u64,
+            md5: None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RcloneResult {
     pub stdout: String,
@@ -45,8 +117,10 @@ impl RcloneResult {
 #[derive(Debug)]
 pub struct RcloneService {
     rclone_path: String,
-    password: RwLock<String>,
+    password: RwLock<Zeroizing<String>>,
     base_uri: RwLock<String>,
+    sources: RwLock<Vec<CatalogSource>>,
+    configured_remotes: RwLock<HashSet<String>>,
     daemon_process: Mutex<Option<Child>>,
     rc_port: RwLock<Option<u16>>,
     http_client: reqwest::Client,
@@ -63,8 +137,10 @@ impl RcloneService {
     pub fn new(rclone_path: Option<String>) -> Self {
         Self {
             rclone_path: rclone_path.unwrap_or_else(|| "rclone".to_string()),
-            password: RwLock::new(String::new()),
+            password: RwLock::new(Zeroizing::new(String::new())),
             base_uri: RwLock::new(String::new()),
+            sources: RwLock::new(Vec::new()),
+            configured_remotes: RwLock::new(HashSet::new()),
             daemon_process: Mutex::new(None),
             rc_port: RwLock::new(None),
             http_client: reqwest::Client::builder()
@@ -86,6 +162,53 @@ impl RcloneService {
         self.base_uri.read().unwrap().clone()
     }
 
+    /// Register the additional catalog sources; invalid or disabled ones are dropped.
+    /// Remotes of sources that changed or went away are configured again on next use.
+    pub fn set_catalog_sources(&self, sources: &[CatalogSource]) {
+        let sources = usable_sources(sources);
+        let previous = std::mem::replace(&mut *self.sources.write().unwrap(), sources.clone());
+        let mut configured = self.configured_remotes.write().unwrap();
+        for source in previous.iter().filter(|source| !sources.contains(source)) {
+            configured.remove(source.remote_name());
+        }
+    }
+
+    pub fn catalog_sources(&self) -> Vec<CatalogSource> {
+        self.sources.read().unwrap().clone()
+    }
+
+    fn catalog_source(&self, name: &str) -> Option<CatalogSource> {
+        self.sources
+            .read()
+            .unwrap()
+            .iter()
+            .find(|source| source.name == name)
+            .cloned()
+    }
+
+    /// rclone path of a release's folder in the source it was listed by.
+    /// Releases without a source come from the public one.
+    pub fn release_path(&self, source: &str, release_name: &str) -> Result<String> {
+        if source.is_empty() || source == PRIMARY_SOURCE {
+            let hash = CatalogService::game_name_to_hash(release_name);
+            return Ok(format!("{}:{}/", PRIMARY_SOURCE, hash));
+        }
+        let source = self
+            .catalog_source(source)
+            .with_context(|| format!("catalog source '{}' is not configured", source))?;
+        Ok(match source.kind {
+            CatalogSourceKind::Remote => {
+                let hash = CatalogService::game_name_to_hash(release_name);
+                format!("{}:{}/", source.remote_name(), hash)
+            }
+            CatalogSourceKind::Local => Path::new(&source.base_uri)
+                .join(release_name)
+                .join("")
+                .to_string_lossy()
+                .to_string(),
+        })
+    }
+
     fn find_free_port() -> Result<u16> {
         let listener = TcpListener::bind("127.0.0.1:0")
             .context("Failed to bind to find free port")?;
@@ -185,7 +308,7 @@ impl RcloneService {
         .stderr(Stdio::piped());
 
         if !password.is_empty() {
-            cmd.env("RCLONE_CONFIG_PASS", password);
+            cmd.env("RCLONE_CONFIG_PASS", password.as_str());
             logger::log("[RCLONE] Using password from config");
         }
 
@@ -215,13 +338,27 @@ impl RcloneService {
     }
 
     async fn create_remote_config(&self, port: u16) -> Result<()> {
+        self.configured_remotes.write().unwrap().clear();
         let base_uri = self.base_uri.read().unwrap().clone();
+        self.create_remote(port, PRIMARY_SOURCE, &base_uri).await?;
+
+        for source in self.catalog_sources() {
+            if source.kind == CatalogSourceKind::Remote {
+                if let Err(e) = self.create_remote(port, source.remote_name(), &source.base_uri).await {
+                    logger::log(&format!("[RCLONE] WARNING: Failed to create remote '{}': {}", source.remote_name(), e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn create_remote(&self, port: u16, name: &str, base_uri: &str) -> Result<()> {
         let base_uri_trimmed = base_uri.trim_end_matches('/');
         
-        logger::log(&format!("[RCLONE] Creating remote config 'vrp' with URL: {}", base_uri_trimmed));
+        logger::log(&format!("[RCLONE] Creating remote config '{}' with URL: {}", name, base_uri_trimmed));
         
         let body = serde_json::json!({
-            "name": "vrp",
+            "name": name,
             "type": "http",
             "parameters": {
                 "url": base_uri_trimmed
@@ -230,10 +367,24 @@ impl RcloneService {
         
         let response = self.rc_post(port, "config/create", body).await?;
         logger::log(&format!("[RCLONE] Config created: {}", response));
+        self.configured_remotes.write().unwrap().insert(name.to_string());
         
         Ok(())
     }
 
+    /// Make sure the remote of `source` exists, for sources added after the daemon started.
+    async fn ensure_source_remote(&self, port: u16, source: &str) -> Result<()> {
+        let Some(source) = self.catalog_source(source) else {
+            return Ok(());
+        };
+        if source.kind != CatalogSourceKind::Remote
+            || self.configured_remotes.read().unwrap().contains(source.remote_name())
+        {
+            return Ok(());
+        }
+        self.create_remote(port, source.remote_name(), &source.base_uri).await
+    }
+
     pub async fn shutdown(&self) -> Result<()> {
         // Kill any local rclone processes first to be thorough
         #[cfg(target_os = "macos")]
@@ -258,11 +409,55 @@ impl RcloneService {
         Ok(())
     }
 
-    pub async fn sync_metadata(&self, download_dir: &Path) -> Result<RcloneResult> {
+    pub async fn sync_metadata(
+        &self,
+        download_dir: &Path,
+        progress_sender: Option<tokio::sync::mpsc::UnboundedSender<DownloadProgress>>,
+    ) -> Result<RcloneResult> {
+        self.sync_source_metadata(PRIMARY_SOURCE, download_dir, progress_sender).await
+    }
+
+    fn source_remote(&self, source: &str) -> Result<String> {
+        if source.is_empty() || source == PRIMARY_SOURCE {
+            return Ok(PRIMARY_SOURCE.to_string());
+        }
+        let source = self
+            .catalog_source(source)
+            .with_context(|| format!("catalog source '{}' is not configured", source))?;
+        Ok(source.remote_name().to_string())
+    }
+
+    /// Size and modtime of `meta.7z` on the remote catalog source `source`,
+    /// `None` when the remote has no such file.
+    pub async fn stat_source_metadata(&self, source: &str) -> Result<Option<RemoteFileStat>> {
+        let port = self.ensure_daemon().await?;
+        self.ensure_source_remote(port, source).await?;
+        let remote = self.source_remote(source)?;
+
+        let body = serde_json::json!({
+            "fs": format!("{}:", remote),
+            "remote": "meta.7z"
+        });
+        let response = self.rc_post(port, "operations/stat", body).await?;
+        let stat = response.get("item").and_then(RemoteFileStat::from_rc_item);
+        logger::log(&format!("[RCLONE] Remote '{}' meta.7z stat: {:?}", remote, stat));
+        Ok(stat)
+    }
+
+    /// Copy `meta.7z` of the remote catalog source `source` into `download_dir`,
+    /// sending transfer stats to `progress_sender` while the copy runs.
+    pub async fn sync_source_metadata(
+        &self,
+        source: &str,
+        download_dir: &Path,
+        progress_sender: Option<tokio::sync::mpsc::UnboundedSender<DownloadProgress>>,
+    ) -> Result<RcloneResult> {
         let port = self.ensure_daemon().await?;
+        self.ensure_source_remote(port, source).await?;
+        let remote = self.source_remote(source)?;
         
-        // Use the named remote "vrp" which was configured in ensure_daemon
-        let src_fs = "vrp:meta.7z".to_string();
+        // Use the named remote which was configured in ensure_daemon
+        let src_fs = format!("{}:meta.7z", remote);
         logger::log(&format!("[RCLONE] sync_metadata from '{}'", src_fs));
         logger::log(&format!("[RCLONE] download_dir: {}", download_dir.display()));
         
@@ -271,30 +466,10 @@ impl RcloneService {
             .context(format!("Failed to create download directory: {}", download_dir.display()))?;
         logger::log(&format!("[RCLONE] Ensured download directory exists"));
 
-        // Verify the remote is accessible by listing the root
-        logger::log(&format!("[RCLONE] Verifying remote 'vrp' is accessible..."));
-        let list_body = serde_json::json!({
-            "fs": "vrp:",
-            "remote": ""
-        });
-        match self.rc_post(port, "operations/list", list_body).await {
-            Ok(list_response) => {
-                let file_count = list_response.get("list")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| arr.len())
-                    .unwrap_or(0);
-                logger::log(&format!("[RCLONE] Remote 'vrp' accessible, found {} items in root", file_count));
-            }
-            Err(e) => {
-                logger::log(&format!("[RCLONE] WARNING: Could not list remote 'vrp': {}", e));
-                // Continue anyway, as the file might still be accessible
-            }
-        }
-
         // Synchronous completion is handled by passing _async: false or omitting it
         // and we ensure we call operations/copyfile which is better for single files
         let body = serde_json::json!({
-            "srcFs": "vrp:",
+            "srcFs": format!("{}:", remote),
             "srcRemote": "meta.7z",
             "dstFs": download_dir.to_string_lossy().to_string(),
             "dstRemote": "meta.7z",
@@ -323,15 +498,17 @@ impl RcloneService {
         // Check if we got a jobid (async operation)
         if let Some(job_id) = response.get("jobid").and_then(|v| v.as_u64()) {
             logger::log(&format!("[RCLONE] Metadata sync started as job {}", job_id));
+            let job_key = format!("{}{}", META_JOB_PREFIX, source);
+            self.active_jobs.lock().await.insert(job_key.clone(), job_id);
             
             // Poll for completion with a 5-minute timeout
             let start_time = std::time::Instant::now();
             let timeout = std::time::Duration::from_secs(300);
             
-            loop {
+            let result = loop {
                 if start_time.elapsed() > timeout {
                     logger::log(&format!("[RCLONE] ERROR: Job {} timed out after 5 minutes", job_id));
-                    return Ok(RcloneResult {
+                    break Ok(RcloneResult {
                         stdout: String::new(),
                         stderr: "Metadata sync timed out".to_string(),
                         returncode: 1,
@@ -339,6 +516,15 @@ impl RcloneService {
                 }
                 
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+                if let Some(sender) = progress_sender.as_ref() {
+                    let stats_body = serde_json::json!({ "group": format!("job/{}", job_id) });
+                    if let Ok(stats) = self.rc_post(port, "core/stats", stats_body).await {
+                        if let Some(progress) = Self::parse_rc_stats(&stats) {
+                            let _ = sender.send(progress);
+                        }
+                    }
+                }
                 
                 let status_body = serde_json::json!({ "jobid": job_id });
                 match self.rc_post(port, "job/status", status_body).await {
@@ -352,14 +538,14 @@ impl RcloneService {
                             
                             if success {
                                 logger::log(&format!("[RCLONE] Metadata sync job {} completed successfully", job_id));
-                                return Ok(RcloneResult {
+                                break Ok(RcloneResult {
                                     stdout: status.to_string(),
                                     stderr: String::new(),
                                     returncode: 0,
                                 });
                             } else {
                                 logger::log(&format!("[RCLONE] Metadata sync job {} failed: {}", job_id, error));
-                                return Ok(RcloneResult {
+                                break Ok(RcloneResult {
                                     stdout: String::new(),
                                     stderr: error.to_string(),
                                     returncode: 1,
@@ -372,7 +558,10 @@ impl RcloneService {
                         // Continue polling even if one status check fails
                     }
                 }
-            }
+            };
+
+            self.active_jobs.lock().await.remove(&job_key);
+            return result;
         }
 
         // Synchronous completion
@@ -383,16 +572,69 @@ impl RcloneService {
         })
     }
 
+    /// Files of a release folder on the catalog source that listed it, with
+    /// MD5 sums where the remote supports them.
+    pub async fn list_release(&self, source: &str, release_name: &str) -> Result<Vec<RemoteFile>> {
+        let port = self.ensure_daemon().await?;
+        self.ensure_source_remote(port, source).await?;
+        let fs = self.release_path(source, release_name)?;
+
+        let body = serde_json::json!({
+            "fs": fs,
+            "remote": "",
+            "opt": { "recurse": true, "filesOnly": true }
+        });
+        let response = self.rc_post(port, "operations/list", body).await?;
+        let mut files: Vec<RemoteFile> = response
+            .get("list")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(RemoteFile::from_rc_item).collect())
+            .unwrap_or_default();
+
+        // Not every remote has hashes; only sizes are compared then
+        let body = serde_json::json!({ "fs": fs, "hashType": "md5" });
+        match self.rc_post(port, "operations/hashsum", body).await {
+            Ok(response) => {
+                let sums = Self::parse_hashsum(&response);
+                for file in &mut files {
+                    file.md5 = sums.get(&file.path).cloned();
+                }
+            }
+            Err(e) => logger::log(&format!("[RCLONE] No hashes for {}: {}", fs, e)),
+        }
+        logger::log(&format!("[RCLONE] Listed {} files in {}", files.len(), fs));
+        Ok(files)
+    }
+
+    /// Sums by path from an `operations/hashsum` response, whose lines read
+    /// `<hash>  <path>`; remotes without the hash leave it blank.
+    fn parse_hashsum(response: &Value) -> HashMap<String, String> {
+        response
+            .get("hashsum")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|line| line.as_str()?.split_once("  "))
+            .filter(|(hash, _)| !hash.trim().is_empty())
+            .map(|(hash, path)| (path.to_string(), hash.trim().to_lowercase()))
+            .collect()
+    }
+
+    /// Download a release from the catalog source that listed it.
     pub async fn download_game(
         &self,
-        game_hash: &str,
+        source: &str,
+        release_name: &str,
         download_dir: &Path,
         bandwidth_limit_mbps: f64,
         progress_sender: Option<tokio::sync::mpsc::UnboundedSender<DownloadProgress>>,
     ) -> Result<RcloneResult> {
-        logger::log(&format!("[RCLONE] Starting download for game_hash: {}", game_hash));
+        let game_hash = CatalogService::game_name_to_hash(release_name);
+        let game_hash = game_hash.as_str();
+        logger::log(&format!("[RCLONE] Starting download for game_hash: {} (source: {})", game_hash, source));
         
         let port = self.ensure_daemon().await?;
+        self.ensure_source_remote(port, source).await?;
         let base_uri = self.base_uri.read().unwrap().clone();
         
         logger::log(&format!("[RCLONE] base_uri: {}, port: {}", base_uri, port));
@@ -408,9 +650,9 @@ impl RcloneService {
         logger::log(&format!("[RCLONE] Bandwidth limit set: {} Mbps", bandwidth_limit_mbps));
 
         // Start async copy job
-        // Use the named remote "vrp" which was configured in ensure_daemon
-        // The format is "vrp:<path>" where path is the game_hash directory
-        let src_fs = format!("vrp:{}/", game_hash);
+        // Remote sources use their named remote configured in ensure_daemon, in the
+        // form "<remote>:<game_hash>/"; local sources are plain folder paths
+        let src_fs = self.release_path(source, release_name)?;
         let dst_fs = download_dir.to_string_lossy().to_string();
         logger::log(&format!("[RCLONE] Starting sync/copy from '{}' to '{}'", src_fs, dst_fs));
         
@@ -551,7 +793,7 @@ impl RcloneService {
         let jobs = self.active_jobs.lock().await.clone();
         let port = self.ensure_daemon().await?;
 
-        for (_, job_id) in jobs {
+        for (_, job_id) in jobs.iter().filter(|(key, _)| !key.starts_with(META_JOB_PREFIX)) {
             let body = serde_json::json!({ "jobid": job_id });
             let _ = self.rc_post(port, "job/stop", body).await;
         }
@@ -559,6 +801,25 @@ impl RcloneService {
         Ok(())
     }
 
+    /// Stop any running `meta.7z` copy, e.g. when a catalog sync is cancelled.
+    pub async fn stop_metadata_sync(&self) -> Result<()> {
+        let jobs: Vec<u64> = self.active_jobs.lock().await
+            .iter()
+            .filter(|(key, _)| key.starts_with(META_JOB_PREFIX))
+            .map(|(_, job_id)| *job_id)
+            .collect();
+        if jobs.is_empty() {
+            return Ok(());
+        }
+        let port = self.ensure_daemon().await?;
+        for job_id in jobs {
+            logger::log(&format!("[RCLONE] Stopping metadata sync job {}", job_id));
+            let body = serde_json::json!({ "jobid": job_id });
+            let _ = self.rc_post(port, "job/stop", body).await;
+        }
+        Ok(())
+    }
+
     pub async fn stop_download(&self, game_hash: &str) -> Result<()> {
         let jobs = self.active_jobs.lock().await;
         let job_id = jobs.get(game_hash);
@@ -605,49 +866,7 @@ impl RcloneService {
     }
 
     pub fn parse_bytes(size_str: &str) -> i64 {
-        let normalized = size_str.trim().to_uppercase();
-        let parse_value = |suffix: &str, scale: f64| -> Option<i64> {
-            let value = normalized.strip_suffix(suffix)?.trim().parse::<f64>().ok()?;
-            Some((value * scale) as i64)
-        };
-
-        if let Some(value) = parse_value("PIB", 1024_f64.powi(5)) {
-            return value;
-        }
-        if let Some(value) = parse_value("TIB", 1024_f64.powi(4)) {
-            return value;
-        }
-        if let Some(value) = parse_value("GIB", 1024_f64.powi(3)) {
-            return value;
-        }
-        if let Some(value) = parse_value("MIB", 1024_f64.powi(2)) {
-            return value;
-        }
-        if let Some(value) = parse_value("KIB", 1024_f64) {
-            return value;
-        }
-
-        if let Some(value) = parse_value("PB", 1000_f64.powi(5)) {
-            return value;
-        }
-        if let Some(value) = parse_value("TB", 1000_f64.powi(4)) {
-            return value;
-        }
-        if let Some(value) = parse_value("GB", 1000_f64.powi(3)) {
-            return value;
-        }
-        if let Some(value) = parse_value("MB", 1000_f64.powi(2)) {
-            return value;
-        }
-        if let Some(value) = parse_value("KB", 1000_f64) {
-            return value;
-        }
-
-        if let Some(value) = parse_value("B", 1.0) {
-            return value;
-        }
-
-        normalized.parse::<f64>().map(|value| value as i64).unwrap_or(0)
+        parse_size(size_str, UnitBase::Decimal, 1.0).map_or(0, |bytes| bytes as i64)
     }
 }
 
@@ -669,6 +888,23 @@ mod tests {
         assert_eq!(RcloneService::parse_bytes("3 GB"), 3_000_000_000);
     }
 
+    #[test]
+    fn parse_hashsum_skips_blank_hashes() {
+        let response = serde_json::json!({
+            "hashType": "md5",
+            "hashsum": [
+                "D41D8CD98F00B204E9800998ECF8427E  abc.7z.001",
+                "                                  abc.7z.002"
+            ]
+        });
+        let sums = RcloneService::parse_hashsum(&response);
+        assert_eq!(sums.len(), 1);
+        assert_eq!(sums["abc.7z.001"], "d41d8cd98f00b204e9800998ecf8427e");
+
+        let item = serde_json::json!({ "Path": "abc.7z.001", "Size": 42, "IsDir": false });
+        assert_eq!(RemoteFile::from_rc_item(&item).unwrap().size, 42);
+    }
+
     #[test]
     fn parse_rc_stats_extracts_fields() {
         let stats = serde_json::json!({
@@ -686,6 +922,105 @@ mod tests {
         assert_eq!(progress.eta, "40s");
     }
 
+    #[test]
+    fn remote_stat_matches_local_copy_by_size_and_modtime() {
+        let item = serde_json::json!({
+            "Path": "meta.7z",
+            "Name": "meta.7z",
+            "Size": 5,
+            "ModTime": "2024-06-01T12:00:00.250000000Z",
+            "IsDir": false
+        });
+        let stat = RemoteFileStat::from_rc_item(&item).expect("must parse stat");
+        assert_eq!(stat.size, 5);
+        assert!(RemoteFileStat::from_rc_item(&serde_json::json!({ "IsDir": true, "Size": -1 })).is_none());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("meta.7z");
+        assert!(!stat.matches_file(&path));
+
+        std::fs::write(&path, b"12345").unwrap();
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        let modtime = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap();
+        file.set_modified(modtime.with_timezone(&Utc).into()).unwrap();
+        assert!(stat.matches_file(&path));
+
+        let resized = RemoteFileStat { size: 6, ..stat.clone() };
+        assert!(!resized.matches_file(&path));
+        let newer = RemoteFileStat {
+            modified: Some(Utc::now()),
+            ..stat
+        };
+        assert!(!newer.matches_file(&path));
+    }
+
+    #[test]
+    fn release_path_routes_by_source() {
+        let rclone = RcloneService::new(None);
+        rclone.set_catalog_sources(&[
+            CatalogSource {
+                name: "mirror".to_string(),
+                remote_name: "team".to_string(),
+                base_uri: "https://mirror.example.com".to_string(),
+                enabled: true,
+                ..Default::default()
+            },
+            CatalogSource {
+                name: "builds".to_string(),
+                kind: CatalogSourceKind::Local,
+                base_uri: "/srv/builds".to_string(),
+                enabled: true,
+                ..Default::default()
+            },
+        ]);
+        let hash = CatalogService::game_name_to_hash("Game v1");
+
+        assert_eq!(rclone.release_path("", "Game v1").unwrap(), format!("vrp:{hash}/"));
+        assert_eq!(rclone.release_path("vrp", "Game v1").unwrap(), format!("vrp:{hash}/"));
+        assert_eq!(rclone.release_path("mirror", "Game v1").unwrap(), format!("team:{hash}/"));
+        assert_eq!(
+            Path::new(&rclone.release_path("builds", "Game v1").unwrap()),
+            Path::new("/srv/builds/Game v1/")
+        );
+        assert!(rclone.release_path("removed", "Game v1").is_err());
+        assert_eq!(rclone.source_remote("mirror").unwrap(), "team");
+        assert_eq!(rclone.source_remote("vrp").unwrap(), "vrp");
+        assert!(rclone.source_remote("removed").is_err());
+    }
+
+    #[test]
+    fn changed_sources_are_reconfigured() {
+        let rclone = RcloneService::new(None);
+        let mirror = CatalogSource {
+            name: "mirror".to_string(),
+            base_uri: "https://mirror.example.com".to_string(),
+            enabled: true,
+            ..Default::default()
+        };
+        let other = CatalogSource {
+            name: "other".to_string(),
+            base_uri: "https://other.example.com".to_string(),
+            enabled: true,
+            ..Default::default()
+        };
+        rclone.set_catalog_sources(&[mirror.clone(), other.clone()]);
+        rclone
+            .configured_remotes
+            .write()
+            .unwrap()
+            .extend(["vrp".to_string(), "mirror".to_string(), "other".to_string()]);
+
+        let moved = CatalogSource {
+            base_uri: "https://new.example.com".to_string(),
+            ..mirror
+        };
+        rclone.set_catalog_sources(&[moved, other]);
+        let configured = rclone.configured_remotes.read().unwrap();
+        assert!(!configured.contains("mirror"));
+        assert!(configured.contains("other"));
+        assert!(configured.contains("vrp"));
+    }
+
     #[test]
     fn format_speed_formats_correctly() {
         assert_eq!(RcloneService::format_speed(100.0), "100 B/s");
@@ -0,0 +1,477 @@
+use crate::models::game::Game;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+// Score for a query token, before the field weight is applied.
+const TOKEN_EXACT: f64 = 1.0;
+const TOKEN_PREFIX_MIN: f64 = 0.5;
+const TOKEN_PREFIX_MAX: f64 = 0.8;
+const TOKEN_FUZZY: f64 = 0.5;
+const TOKEN_FUZZY_PREFIX: f64 = 0.4;
+
+// Score for the whole query appearing verbatim in a field.
+const PHRASE_EXACT: f64 = 4.0;
+const PHRASE_PREFIX: f64 = 3.0;
+const PHRASE_CONTAINED: f64 = 2.0;
+
+/// The `Game` fields covered by the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    GameName,
+    ReleaseName,
+    PackageName,
+}
+
+impl SearchField {
+    pub const ALL: [SearchField; 3] = [
+        SearchField::GameName,
+        SearchField::ReleaseName,
+        SearchField::PackageName,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            SearchField::GameName => 0,
+            SearchField::ReleaseName => 1,
+            SearchField::PackageName => 2,
+        }
+    }
+
+    /// A hit in the game name outranks the same hit in the release or package name.
+    fn weight(self) -> f64 {
+        match self {
+            SearchField::GameName => 1.0,
+            SearchField::ReleaseName => 0.7,
+            SearchField::PackageName => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    doc: u32,
+    field: SearchField,
+}
+
+/// Token index over a list of games, used by `CatalogService::search`.
+///
+/// Queries are split into tokens that may match in any order. Each token must
+/// match some indexed term exactly, as a prefix, or within a small edit
+/// distance. Results are document indices into the slice the index was built
+/// from, best match first.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    postings: HashMap<String, Vec<Posting>>,
+    /// Sorted vocabulary, so prefix lookups are a binary search.
+    terms: Vec<String>,
+    /// Lowercased fields per document, for whole-query substring matches.
+    documents: Vec<[String; 3]>,
+}
+
+impl SearchIndex {
+    pub fn build(games: &[Game]) -> Self {
+        let mut postings: HashMap<String, Vec<Posting>> = HashMap::new();
+        let mut documents = Vec::with_capacity(games.len());
+
+        for (doc, game) in games.iter().enumerate() {
+            let fields = [
+                game.game_name.to_lowercase(),
+                game.release_name.to_lowercase(),
+                game.package_name.to_lowercase(),
+            ];
+
+            for field in SearchField::ALL {
+                for token in tokenize(&fields[field.index()]) {
+                    let list = postings.entry(token).or_default();
+                    let duplicate = list
+                        .last()
+                        .is_some_and(|p| p.doc == doc as u32 && p.field == field);
+                    if !duplicate {
+                        list.push(Posting {
+                            doc: doc as u32,
+                            field,
+                        });
+                    }
+                }
+            }
+
+            documents.push(fields);
+        }
+
+        let mut terms: Vec<String> = postings.keys().cloned().collect();
+        terms.sort();
+
+        Self {
+            postings,
+            terms,
+            documents,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Search the game, release and package names.
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        self.search_fields(query, &SearchField::ALL)
+    }
+
+    /// Search a single field, as used by the `release:` and `pkg:` prefixes.
+    pub fn search_field(&self, query: &str, field: SearchField) -> Vec<usize> {
+        self.search_fields(query, &[field])
+    }
+
+    fn search_fields(&self, query: &str, fields: &[SearchField]) -> Vec<usize> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return (0..self.documents.len()).collect();
+        }
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+
+        // Every query token has to match somewhere in the document, in any order.
+        let query_tokens = tokenize(&query);
+        if !query_tokens.is_empty() {
+            let mut token_hits: HashMap<usize, Vec<f64>> = HashMap::new();
+            for (position, token) in query_tokens.iter().enumerate() {
+                for (term, quality) in self.matching_terms(token) {
+                    let Some(list) = self.postings.get(term) else {
+                        continue;
+                    };
+                    for posting in list.iter().filter(|p| fields.contains(&p.field)) {
+                        let hits = token_hits
+                            .entry(posting.doc as usize)
+                            .or_insert_with(|| vec![0.0; query_tokens.len()]);
+                        let score = quality * posting.field.weight();
+                        if score > hits[position] {
+                            hits[position] = score;
+                        }
+                    }
+                }
+            }
+
+            for (doc, hits) in token_hits {
+                if hits.iter().all(|score| *score > 0.0) {
+                    *scores.entry(doc).or_default() += hits.iter().sum::<f64>();
+                }
+            }
+        }
+
+        // The whole query as a substring keeps the old `contains` results and
+        // ranks phrase matches above scattered token matches.
+        for (doc, text) in self.documents.iter().enumerate() {
+            let best = fields
+                .iter()
+                .map(|field| phrase_score(&text[field.index()], &query) * field.weight())
+                .fold(0.0, f64::max);
+            if best > 0.0 {
+                *scores.entry(doc).or_default() += best;
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+        ranked.into_iter().map(|(doc, _)| doc).collect()
+    }
+
+    /// Indexed terms matching a query token, with a match quality in `(0, 1]`.
+    fn matching_terms<'a>(&'a self, token: &str) -> Vec<(&'a str, f64)> {
+        let mut matches: Vec<(&str, f64)> = Vec::new();
+        let token_chars: Vec<char> = token.chars().collect();
+
+        let start = self.terms.partition_point(|term| term.as_str() < token);
+        for term in self.terms[start..]
+            .iter()
+            .take_while(|term| term.starts_with(token))
+        {
+            let quality = if term == token {
+                TOKEN_EXACT
+            } else {
+                let ratio = token_chars.len() as f64 / term.chars().count() as f64;
+                TOKEN_PREFIX_MIN + (TOKEN_PREFIX_MAX - TOKEN_PREFIX_MIN) * ratio
+            };
+            matches.push((term.as_str(), quality));
+        }
+
+        let budget = fuzzy_budget(token_chars.len());
+        if budget == 0 {
+            return matches;
+        }
+
+        for term in &self.terms {
+            if term.starts_with(token) {
+                continue;
+            }
+            let term_chars: Vec<char> = term.chars().collect();
+
+            if term_chars.len().abs_diff(token_chars.len()) <= budget {
+                if let Some(distance) = bounded_levenshtein(&token_chars, &term_chars, budget) {
+                    matches.push((term.as_str(), TOKEN_FUZZY / distance as f64));
+                    continue;
+                }
+            }
+
+            // A misspelled, partially typed word: compare against the term's prefix.
+            if term_chars.len() > token_chars.len() {
+                let prefix = &term_chars[..token_chars.len()];
+                if let Some(distance) = bounded_levenshtein(&token_chars, prefix, budget) {
+                    matches.push((term.as_str(), TOKEN_FUZZY_PREFIX / distance as f64));
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+/// Word index over release notes, keyed by package, for `note:` filters.
+///
+/// A single word matches whole words only, so `note:mod` doesn't match
+/// "modern"; several words (`note:"online play"`) must appear in sequence.
+#[derive(Debug, Clone, Default)]
+pub struct NotesIndex {
+    postings: HashMap<String, HashSet<String>>,
+    /// Note tokens per package joined by single spaces, padded on both ends.
+    documents: HashMap<String, String>,
+}
+
+impl NotesIndex {
+    pub fn insert(&mut self, package_name: &str, note: &str) {
+        let tokens = tokenize(note);
+        for token in &tokens {
+            self.postings
+                .entry(token.clone())
+                .or_default()
+                .insert(package_name.to_string());
+        }
+        self.documents
+            .insert(package_name.to_string(), format!(" {} ", tokens.join(" ")));
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Packages whose notes contain `term`.
+    pub fn matching_packages(&self, term: &str) -> HashSet<String> {
+        let tokens = tokenize(term);
+        let Some((first, rest)) = tokens.split_first() else {
+            return HashSet::new();
+        };
+        let Some(candidates) = self.postings.get(first) else {
+            return HashSet::new();
+        };
+        if rest.is_empty() {
+            return candidates.clone();
+        }
+
+        let phrase = format!(" {} ", tokens.join(" "));
+        candidates
+            .iter()
+            .filter(|package| {
+                self.documents
+                    .get(package.as_str())
+                    .is_some_and(|document| document.contains(&phrase))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Lowercase alphanumeric runs of `text`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+fn phrase_score(text: &str, query: &str) -> f64 {
+    if text == query {
+        PHRASE_EXACT
+    } else if text.starts_with(query) {
+        PHRASE_PREFIX
+    } else if text.contains(query) {
+        PHRASE_CONTAINED
+    } else {
+        0.0
+    }
+}
+
+/// Edits tolerated for a query token of `len` characters.
+fn fuzzy_budget(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, or `None` if it exceeds `max`.
+fn bounded_levenshtein(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let distance = previous[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(name: &str, release: &str, package: &str) -> Game {
+        Game {
+            game_name: name.to_string(),
+            release_name: release.to_string(),
+            package_name: package.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sample_index() -> (Vec<Game>, SearchIndex) {
+        let games = vec![
+            game("Beat Saber", "Beat Saber v1+1.40", "com.beatgames.beatsaber"),
+            game(
+                "The Walking Dead: Saints & Sinners",
+                "The Walking Dead Saints & Sinners v500+1.0",
+                "com.skydance.tws",
+            ),
+            game("Saber Duel", "Saber Duel v3+0.3", "com.example.saberduel"),
+            game("Pistol Whip", "Pistol Whip v90+2.1", "com.cloudheadgames.pistolwhip"),
+        ];
+        let index = SearchIndex::build(&games);
+        (games, index)
+    }
+
+    fn names(games: &[Game], hits: Vec<usize>) -> Vec<String> {
+        hits.into_iter().map(|i| games[i].game_name.clone()).collect()
+    }
+
+    #[test]
+    fn tokenize_splits_on_punctuation() {
+        assert_eq!(
+            tokenize("The Walking Dead: Saints & Sinners"),
+            vec!["the", "walking", "dead", "saints", "sinners"]
+        );
+        assert_eq!(tokenize("com.beatgames.beatsaber"), vec!["com", "beatgames", "beatsaber"]);
+    }
+
+    #[test]
+    fn empty_query_returns_everything_in_order() {
+        let (_, index) = sample_index();
+        assert_eq!(index.search("  "), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn matches_words_in_any_order() {
+        let (games, index) = sample_index();
+        let hits = names(&games, index.search("walking dead saints"));
+        assert_eq!(hits, vec!["The Walking Dead: Saints & Sinners"]);
+
+        let hits = names(&games, index.search("saints walking"));
+        assert_eq!(hits, vec!["The Walking Dead: Saints & Sinners"]);
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        let (games, index) = sample_index();
+        let hits = names(&games, index.search("beat sabr"));
+        assert_eq!(hits.first().map(String::as_str), Some("Beat Saber"));
+
+        let hits = names(&games, index.search("pistl whip"));
+        assert_eq!(hits, vec!["Pistol Whip"]);
+    }
+
+    #[test]
+    fn matches_prefixes_while_typing() {
+        let (games, index) = sample_index();
+        let hits = names(&games, index.search("pist"));
+        assert_eq!(hits, vec!["Pistol Whip"]);
+    }
+
+    #[test]
+    fn ranks_name_matches_above_other_fields() {
+        let (games, index) = sample_index();
+        let hits = names(&games, index.search("saber"));
+        assert_eq!(hits[0], "Saber Duel");
+        assert_eq!(hits[1], "Beat Saber");
+    }
+
+    #[test]
+    fn keeps_substring_matches() {
+        let (games, index) = sample_index();
+        // "games" only occurs inside a package token.
+        let hits = names(&games, index.search_field("games", SearchField::PackageName));
+        assert_eq!(hits, vec!["Beat Saber", "Pistol Whip"]);
+    }
+
+    #[test]
+    fn field_search_ignores_other_fields() {
+        let (_, index) = sample_index();
+        assert!(index.search_field("beat", SearchField::GameName).contains(&0));
+        assert!(index.search_field("skydance", SearchField::GameName).is_empty());
+        assert_eq!(index.search_field("skydance", SearchField::PackageName), vec![1]);
+    }
+
+    #[test]
+    fn bounded_levenshtein_respects_limit() {
+        let a: Vec<char> = "sabr".chars().collect();
+        let b: Vec<char> = "saber".chars().collect();
+        assert_eq!(bounded_levenshtein(&a, &b, 1), Some(1));
+        let c: Vec<char> = "sword".chars().collect();
+        assert_eq!(bounded_levenshtein(&a, &c, 1), None);
+    }
+
+    #[test]
+    fn notes_index_matches_words_and_phrases() {
+        let mut index = NotesIndex::default();
+        index.insert("pkg.a", "Supports online multiplayer.\nModern visuals");
+        index.insert("pkg.b", "Single player only, no online play");
+
+        assert_eq!(
+            index.matching_packages("Multiplayer"),
+            HashSet::from(["pkg.a".to_string()])
+        );
+        assert_eq!(index.matching_packages("online").len(), 2);
+        assert_eq!(
+            index.matching_packages("online play"),
+            HashSet::from(["pkg.b".to_string()])
+        );
+        assert!(index.matching_packages("mod").is_empty());
+        assert!(index.matching_packages("play online").is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Encrypts small files at rest with a random per-install AES-256-GCM key. The
+/// key lives in its own file, readable only by the current user, so copying
+/// the cache directory doesn't copy the secrets in it.
+pub struct SecretStore {
+    key_path: PathBuf,
+}
+
+impl SecretStore {
+    pub fn new(key_path: PathBuf) -> Self {
+        Self { key_path }
+    }
+
+    pub fn key_path(&self) -> &Path {
+        &self.key_path
+    }
+
+    /// Encrypt `plaintext` as nonce followed by ciphertext, creating the key on
+    /// first use.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = self.cipher(true)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("encryption failed"))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn decrypt(&self, sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if sealed.len() < NONCE_LEN {
+            bail!("encrypted data is truncated");
+        }
+        let cipher = self.cipher(false)?;
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map(Zeroizing::new)
+            .map_err(|_| anyhow!("decryption failed, the data or key file was changed"))
+    }
+
+    fn cipher(&self, create: bool) -> Result<Aes256Gcm> {
+        let key = match std::fs::read(&self.key_path) {
+            Ok(bytes) => Zeroizing::new(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && create => self.create_key()?,
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("failed to read key file {}", self.key_path.display())
+                })
+            }
+        };
+        if key.len() != KEY_LEN {
+            bail!("key file {} is not a valid key", self.key_path.display());
+        }
+        restrict_permissions(&self.key_path)?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+
+    fn create_key(&self) -> Result<Zeroizing<Vec<u8>>> {
+        if let Some(parent) = self.key_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let key = Zeroizing::new(Aes256Gcm::generate_key(&mut OsRng).to_vec());
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        match options.open(&self.key_path) {
+            Ok(mut file) => {
+                file.write_all(&key)?;
+                file.sync_all()?;
+                crate::logger::log(&format!(
+                    "[SECRETS] Created key file {}",
+                    self.key_path.display()
+                ));
+                Ok(key)
+            }
+            // Another caller created it first
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                Ok(Zeroizing::new(std::fs::read(&self.key_path)?))
+            }
+            Err(e) => Err(e)
+                .with_context(|| format!("failed to create key file {}", self.key_path.display())),
+        }
+    }
+}
+
+/// Make the key file readable and writable by its owner only.
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to restrict {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn round_trips_and_detects_tampering() {
+        let temp_dir = tempdir().unwrap();
+        let store = SecretStore::new(temp_dir.path().join("keys").join("config.key"));
+
+        let sealed = store.encrypt(b"secret_password").unwrap();
+        assert!(!sealed
+            .windows(b"secret_password".len())
+            .any(|window| window == b"secret_password"));
+        assert_eq!(
+            store.decrypt(&sealed).unwrap().as_slice(),
+            b"secret_password"
+        );
+
+        // The key is reused, not regenerated
+        let again = SecretStore::new(store.key_path().to_path_buf());
+        assert_eq!(
+            again.decrypt(&sealed).unwrap().as_slice(),
+            b"secret_password"
+        );
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(store.decrypt(&tampered).is_err());
+        assert!(store.decrypt(&sealed[..4]).is_err());
+
+        let other = SecretStore::new(temp_dir.path().join("other.key"));
+        assert!(other.decrypt(&sealed).is_err());
+        // Decrypting never creates a key
+        assert!(!other.key_path().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn key_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let store = SecretStore::new(temp_dir.path().join("config.key"));
+        store.encrypt(b"x").unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(store.key_path()), 0o600);
+
+        std::fs::set_permissions(store.key_path(), std::fs::Permissions::from_mode(0o644)).unwrap();
+        store.encrypt(b"x").unwrap();
+        assert_eq!(mode(store.key_path()), 0o600);
+    }
+}
@@ -1,13 +1,17 @@
+use crate::models::config::CatalogSource;
 use crate::models::settings::Settings;
+use crate::services::source_secrets::SourceSecrets;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use zeroize::Zeroizing;
 
 #[derive(Clone)]
 pub struct SettingsService {
     settings: Arc<RwLock<Settings>>,
     config_path: PathBuf,
+    secrets: SourceSecrets,
 }
 
 impl SettingsService {
@@ -16,7 +20,7 @@ impl SettingsService {
             .map(|p| p.join(".veteran").join("settings.json"))
             .unwrap_or_else(|| PathBuf::from("settings.json"));
 
-        let settings = if config_path.exists() {
+        let mut settings: Settings = if config_path.exists() {
             std::fs::read_to_string(&config_path)
                 .ok()
                 .and_then(|content| serde_json::from_str(&content).ok())
@@ -25,6 +29,24 @@ impl SettingsService {
             Settings::default()
         };
 
+        // Move source passwords saved in plaintext by older versions into the secrets
+        let secrets = SourceSecrets::beside(&config_path);
+        if settings
+            .catalog_sources
+            .iter()
+            .any(|source| !source.password.is_empty())
+        {
+            match migrate_source_passwords(&mut settings, &secrets, &config_path) {
+                Ok(()) => crate::logger::log(
+                    "[SETTINGS] Moved catalog source passwords into the secret store",
+                ),
+                Err(e) => crate::logger::log(&format!(
+                    "[SETTINGS] Failed to move catalog source passwords: {:#}",
+                    e
+                )),
+            }
+        }
+
         // Ensure download directory exists
         if !settings.download_dir.is_empty() {
             let download_path = PathBuf::from(&settings.download_dir);
@@ -36,6 +58,7 @@ impl SettingsService {
         Self {
             settings: Arc::new(RwLock::new(settings)),
             config_path,
+            secrets,
         }
     }
 
@@ -44,11 +67,22 @@ impl SettingsService {
     pub fn from_settings(settings: Settings, config_path: PathBuf) -> Self {
         Self {
             settings: Arc::new(RwLock::new(settings)),
+            secrets: SourceSecrets::beside(&config_path),
             config_path,
         }
     }
 }
 
+fn migrate_source_passwords(
+    settings: &mut Settings,
+    secrets: &SourceSecrets,
+    config_path: &Path,
+) -> Result<()> {
+    secrets.seal(&mut settings.catalog_sources, &[])?;
+    std::fs::write(config_path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
 impl Default for SettingsService {
     fn default() -> Self {
         Self::new()
@@ -66,7 +100,11 @@ impl SettingsService {
 
     pub async fn save(&self) -> Result<()> {
         let settings = self.settings.read().await;
-        let json = serde_json::to_string_pretty(&*settings)?;
+        self.write_file(&settings).await
+    }
+
+    async fn write_file(&self, settings: &Settings) -> Result<()> {
+        let json = serde_json::to_string_pretty(settings)?;
 
         if let Some(parent) = self.config_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
@@ -76,6 +114,28 @@ impl SettingsService {
         Ok(())
     }
 
+    /// Password of a catalog source, kept out of the settings file.
+    pub fn source_password(&self, source: &CatalogSource) -> Result<Option<Zeroizing<String>>> {
+        self.secrets.password(source)
+    }
+
+    /// Change the current settings with `update` and save them, holding the
+    /// write lock throughout so concurrent writers don't undo each other's
+    /// changes. Nothing is saved when `update` fails or changes nothing.
+    pub async fn update_with<T>(&self, update: impl FnOnce(&mut Settings) -> Result<T>) -> Result<T> {
+        let mut settings = self.settings.write().await;
+        let mut new_settings = settings.clone();
+        let result = update(&mut new_settings)?;
+        if new_settings == *settings {
+            return Ok(result);
+        }
+        self.secrets
+            .seal(&mut new_settings.catalog_sources, &settings.catalog_sources)?;
+        self.write_file(&new_settings).await?;
+        *settings = new_settings;
+        Ok(result)
+    }
+
     pub async fn update_settings(&self, new_settings: Settings) -> Result<()> {
         // Ensure download directory exists if it changed
         if !new_settings.download_dir.is_empty() {
@@ -85,31 +145,32 @@ impl SettingsService {
             }
         }
 
-        let mut settings = self.settings.write().await;
-        *settings = new_settings;
-        drop(settings); // Release lock
-        self.save().await
+        self.update_with(|settings| {
+            *settings = new_settings;
+            Ok(())
+        })
+        .await
     }
 
     // Patch settings supports partial updates from a JSON value
     pub async fn patch_settings(&self, patch: serde_json::Value) -> Result<Settings> {
-        let mut current_settings = self.settings.write().await;
-        let mut current_json = serde_json::to_value(&*current_settings)?;
-
-        if let serde_json::Value::Object(map) = patch {
-            if let serde_json::Value::Object(ref mut current_map) = current_json {
-                for (k, v) in map {
-                    current_map.insert(k, v);
+        self.update_with(|settings| {
+            let mut current_json = serde_json::to_value(&*settings)?;
+
+            if let serde_json::Value::Object(map) = patch {
+                if let serde_json::Value::Object(ref mut current_map) = current_json {
+                    for (k, v) in map {
+                        current_map.insert(k, v);
+                    }
                 }
             }
-        }
 
-        let new_settings: Settings = serde_json::from_value(current_json)?;
-        *current_settings = new_settings.clone();
-        drop(current_settings); // Release lock
-
-        self.save().await?;
-        Ok(new_settings)
+            *settings = serde_json::from_value(current_json)?;
+            Ok(())
+        })
+        .await?;
+        // Read back once sealed, so no plaintext password is returned
+        Ok(self.get_settings().await)
     }
 }
 
@@ -127,6 +188,7 @@ mod tests {
         let service = SettingsService {
             settings: Arc::new(RwLock::new(Settings::default())),
             config_path: config_path.clone(),
+            secrets: SourceSecrets::beside(&config_path),
         };
 
         // Modify settings
@@ -140,4 +202,41 @@ mod tests {
         let saved_settings: Settings = serde_json::from_str(&content).unwrap();
         assert_eq!(saved_settings.window_width, 1234);
     }
+
+    #[tokio::test]
+    async fn concurrent_updates_keep_each_change() {
+        let temp_dir = tempdir().unwrap();
+        let service =
+            SettingsService::from_settings(Settings::default(), temp_dir.path().join("settings.json"));
+
+        let updates = (0..20).map(|i| {
+            let service = service.clone();
+            tokio::spawn(async move {
+                service
+                    .update_with(|settings| {
+                        settings.favorited_games.push(format!("pkg.{i}"));
+                        Ok(())
+                    })
+                    .await
+            })
+        });
+        for update in updates.collect::<Vec<_>>() {
+            update.await.unwrap().unwrap();
+        }
+        assert_eq!(service.get_settings().await.favorited_games.len(), 20);
+
+        // A failed update changes nothing
+        let result = service
+            .update_with(|settings| -> Result<()> {
+                settings.favorited_games.clear();
+                anyhow::bail!("rejected")
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(service.get_settings().await.favorited_games.len(), 20);
+
+        let content = std::fs::read_to_string(temp_dir.path().join("settings.json")).unwrap();
+        let saved: Settings = serde_json::from_str(&content).unwrap();
+        assert_eq!(saved.favorited_games.len(), 20);
+    }
 }
@@ -0,0 +1,180 @@
+use crate::models::config::CatalogSource;
+use crate::services::secret_store::SecretStore;
+use anyhow::{bail, Result};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zeroize::{Zeroize, Zeroizing};
+
+const SECRETS_VERSION: u32 = 1;
+
+type Secrets = HashMap<String, Zeroizing<String>>;
+
+/// On-disk form of the source passwords: their JSON sealed with the per-install key.
+#[derive(Serialize, Deserialize)]
+struct EncryptedSecrets {
+    version: u32,
+    /// Base64 of nonce and ciphertext
+    sealed: String,
+}
+
+/// Passwords of the additional catalog sources. Settings only keep a
+/// reference to each one, the passwords themselves are sealed with the same
+/// key as the cached public config.
+#[derive(Debug, Clone)]
+pub struct SourceSecrets {
+    path: PathBuf,
+    key_path: PathBuf,
+}
+
+impl SourceSecrets {
+    pub fn new(path: PathBuf, key_path: PathBuf) -> Self {
+        Self { path, key_path }
+    }
+
+    /// Secrets kept next to `settings_path`, sharing `config.key` with the
+    /// config cache.
+    pub fn beside(settings_path: &Path) -> Self {
+        Self::new(
+            settings_path.with_file_name("source-secrets.json"),
+            settings_path.with_file_name("config.key"),
+        )
+    }
+
+    /// Password of `source`, if it has one.
+    pub fn password(&self, source: &CatalogSource) -> Result<Option<Zeroizing<String>>> {
+        if source.password_ref.is_empty() {
+            return Ok(None);
+        }
+        let mut secrets = self.load()?;
+        match secrets.remove(&source.password_ref) {
+            Some(password) => Ok(Some(password)),
+            None => bail!("password of source '{}' is missing", source.name),
+        }
+    }
+
+    /// Move the plaintext passwords of `sources` into the store, leaving a
+    /// reference in their place, and forget passwords of sources in `previous`
+    /// that no longer refer to them.
+    pub fn seal(&self, sources: &mut [CatalogSource], previous: &[CatalogSource]) -> Result<()> {
+        let has_plaintext = sources.iter().any(|source| !source.password.is_empty());
+        let dropped = previous.iter().any(|old| {
+            !old.password_ref.is_empty()
+                && !sources
+                    .iter()
+                    .any(|source| source.password_ref == old.password_ref)
+        });
+        if !has_plaintext && !dropped {
+            return Ok(());
+        }
+
+        let mut secrets = self.load()?;
+        for source in sources
+            .iter_mut()
+            .filter(|source| !source.password.is_empty())
+        {
+            if source.password_ref.is_empty() {
+                source.password_ref = Uuid::new_v4().to_string();
+            }
+            secrets.insert(
+                source.password_ref.clone(),
+                Zeroizing::new(source.password.clone()),
+            );
+            source.password.zeroize();
+        }
+        secrets.retain(|reference, _| {
+            sources
+                .iter()
+                .any(|source| &source.password_ref == reference)
+        });
+        self.save(&secrets)
+    }
+
+    fn load(&self) -> Result<Secrets> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Secrets::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let secrets: EncryptedSecrets = serde_json::from_str(&content)?;
+        if secrets.version != SECRETS_VERSION {
+            bail!("unsupported source secrets version {}", secrets.version);
+        }
+        let sealed = general_purpose::STANDARD.decode(secrets.sealed)?;
+        let json = SecretStore::new(self.key_path.clone()).decrypt(&sealed)?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+
+    fn save(&self, secrets: &Secrets) -> Result<()> {
+        let json = Zeroizing::new(serde_json::to_vec(secrets)?);
+        let sealed = SecretStore::new(self.key_path.clone()).encrypt(&json)?;
+        let secrets = EncryptedSecrets {
+            version: SECRETS_VERSION,
+            sealed: general_purpose::STANDARD.encode(sealed),
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&secrets)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn source(name: &str, password: &str) -> CatalogSource {
+        CatalogSource {
+            name: name.to_string(),
+            base_uri: "https://mirror.example.com/".to_string(),
+            password: password.to_string(),
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn passwords_are_sealed_and_forgotten_with_their_source() {
+        let temp_dir = tempdir().unwrap();
+        let secrets = SourceSecrets::beside(&temp_dir.path().join("settings.json"));
+
+        // Nothing to seal writes nothing
+        let mut sources = vec![source("open", "")];
+        secrets.seal(&mut sources, &[]).unwrap();
+        assert!(!secrets.path.exists());
+        assert!(!secrets.key_path.exists());
+
+        let mut sources = vec![source("mirror", "hunter2"), source("open", "")];
+        secrets.seal(&mut sources, &[]).unwrap();
+        assert!(sources[0].password.is_empty());
+        assert!(!sources[0].password_ref.is_empty());
+        assert!(sources[1].password_ref.is_empty());
+        assert!(!std::fs::read_to_string(&secrets.path)
+            .unwrap()
+            .contains("hunter2"));
+        assert_eq!(
+            secrets.password(&sources[0]).unwrap().unwrap().as_str(),
+            "hunter2"
+        );
+        assert!(secrets.password(&sources[1]).unwrap().is_none());
+
+        // A new password replaces the old one under the same reference
+        let mut changed = sources.clone();
+        changed[0].password = "correct horse".to_string();
+        secrets.seal(&mut changed, &sources).unwrap();
+        assert_eq!(changed[0].password_ref, sources[0].password_ref);
+        assert_eq!(
+            secrets.password(&changed[0]).unwrap().unwrap().as_str(),
+            "correct horse"
+        );
+
+        // Removing the source forgets its password
+        let mut remaining = vec![changed[1].clone()];
+        secrets.seal(&mut remaining, &changed).unwrap();
+        assert!(secrets.password(&changed[0]).is_err());
+    }
+}
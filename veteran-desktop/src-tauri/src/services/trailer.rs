@@ -0,0 +1,491 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::OnceLock;
+use tokio::sync::Mutex;
+
+pub const YOUTUBE_URL: &str = "https://www.youtube.com";
+
+/// User file next to the cache directory mapping package names to a video id
+/// or URL; `null` hides a package's trailer:
+///
+/// ```json
+/// { "com.beatgames.beatsaber": "https://youtu.be/vL39Sg2AqWg", "com.example.app": null }
+/// ```
+pub const OVERRIDE_FILE: &str = "trailers.json";
+
+const CACHE_FILE: &str = "trailer_cache.json";
+const CACHE_VERSION: u32 = 1;
+
+/// Seconds a found trailer is used before it is looked up again.
+pub const FOUND_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+/// Seconds a lookup that found nothing is remembered, so games without a
+/// trailer aren't searched every time their details are opened.
+pub const MISSING_TTL_SECS: i64 = 24 * 60 * 60;
+
+static VIDEO_LINK_PATTERN: OnceLock<Regex> = OnceLock::new();
+static VIDEO_ID_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+fn video_link_pattern() -> &'static Regex {
+    VIDEO_LINK_PATTERN
+        .get_or_init(|| Regex::new(r"(?:watch\?v=|youtu\.be/|embed/)([a-zA-Z0-9_-]{11})").unwrap())
+}
+
+/// The video id of a bare id or a YouTube URL.
+pub fn video_id_from(text: &str) -> Option<String> {
+    let text = text.trim();
+    let bare = VIDEO_ID_PATTERN.get_or_init(|| Regex::new(r"^[a-zA-Z0-9_-]{11}$").unwrap());
+    if bare.is_match(text) {
+        return Some(text.to_string());
+    }
+    video_link_pattern()
+        .captures(text)
+        .map(|caps| caps[1].to_string())
+}
+
+pub type LookupFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<String>>> + Send + 'a>>;
+
+/// Somewhere to look up the trailer of a game.
+pub trait TrailerProvider: Send + Sync {
+    /// Recorded with cached results
+    fn name(&self) -> &'static str;
+
+    /// The video id of `game_name`'s trailer, `None` when there is none. An
+    /// error means the provider couldn't be asked and nothing is cached.
+    fn lookup<'a>(&'a self, game_name: &'a str) -> LookupFuture<'a>;
+}
+
+/// Takes the first video of a YouTube search for `<game name> VR trailer`.
+pub struct YoutubeScraper {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl YoutubeScraper {
+    pub fn new() -> Self {
+        Self::with_base_url(YOUTUBE_URL)
+    }
+
+    /// Search `base_url` instead of YouTube, e.g. a stand-in server in tests.
+    pub fn with_base_url(base_url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
+            .timeout(std::time::Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn search(&self, game_name: &str) -> Result<Option<String>> {
+        let query = format!("{} VR trailer", game_name);
+        let encoded_query =
+            url::form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>();
+        let url = format!("{}/results?search_query={}", self.base_url, encoded_query);
+
+        let text = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("trailer search for {game_name} failed"))?
+            .text()
+            .await?;
+        Ok(video_link_pattern()
+            .captures(&text)
+            .map(|caps| caps[1].to_string()))
+    }
+}
+
+impl Default for YoutubeScraper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrailerProvider for YoutubeScraper {
+    fn name(&self) -> &'static str {
+        "youtube"
+    }
+
+    fn lookup<'a>(&'a self, game_name: &'a str) -> LookupFuture<'a> {
+        Box::pin(self.search(game_name))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CachedTrailer {
+    /// `None` caches that the provider found nothing
+    video_id: Option<String>,
+    fetched_at: i64,
+    provider: String,
+}
+
+impl CachedTrailer {
+    fn is_fresh(&self, now: i64) -> bool {
+        let ttl = if self.video_id.is_some() {
+            FOUND_TTL_SECS
+        } else {
+            MISSING_TTL_SECS
+        };
+        now - self.fetched_at < ttl
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: HashMap<String, CachedTrailer>,
+}
+
+fn cache_key(game_name: &str) -> String {
+    game_name.trim().to_lowercase()
+}
+
+/// Trailer lookups: the override file first, then the on-disk cache, then
+/// the provider. When the provider fails, an expired cache entry is still
+/// used.
+pub struct TrailerService {
+    provider: Box<dyn TrailerProvider>,
+    cache_path: PathBuf,
+    override_path: PathBuf,
+    /// Loaded from `cache_path` on first use
+    cache: Mutex<Option<HashMap<String, CachedTrailer>>>,
+}
+
+impl TrailerService {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self::with_provider(cache_dir, Box::new(YoutubeScraper::new()))
+    }
+
+    pub fn with_provider(cache_dir: &Path, provider: Box<dyn TrailerProvider>) -> Self {
+        Self {
+            provider,
+            cache_path: cache_dir.join(CACHE_FILE),
+            override_path: cache_dir.parent().unwrap_or(cache_dir).join(OVERRIDE_FILE),
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub fn override_path(&self) -> &Path {
+        &self.override_path
+    }
+
+    pub async fn lookup(
+        &self,
+        game_name: &str,
+        package_name: Option<&str>,
+    ) -> Result<Option<String>> {
+        self.lookup_at(game_name, package_name, Utc::now().timestamp())
+            .await
+    }
+
+    pub async fn lookup_at(
+        &self,
+        game_name: &str,
+        package_name: Option<&str>,
+        now: i64,
+    ) -> Result<Option<String>> {
+        if let Some(video_id) = package_name.and_then(|package| self.override_for(package)) {
+            return Ok(video_id);
+        }
+
+        let key = cache_key(game_name);
+        let cached = {
+            let mut cache = self.cache.lock().await;
+            cache
+                .get_or_insert_with(|| load_cache(&self.cache_path))
+                .get(&key)
+                .cloned()
+        };
+        if let Some(entry) = cached.as_ref().filter(|entry| entry.is_fresh(now)) {
+            return Ok(entry.video_id.clone());
+        }
+
+        let video_id = match self.provider.lookup(game_name.trim()).await {
+            Ok(video_id) => video_id,
+            Err(e) => {
+                let Some(entry) = cached else {
+                    return Err(e);
+                };
+                crate::logger::log(&format!("[TRAILER] {:#}; using the expired cache entry", e));
+                return Ok(entry.video_id);
+            }
+        };
+
+        let mut cache = self.cache.lock().await;
+        let entries = cache.get_or_insert_with(HashMap::new);
+        entries.insert(
+            key,
+            CachedTrailer {
+                video_id: video_id.clone(),
+                fetched_at: now,
+                provider: self.provider.name().to_string(),
+            },
+        );
+        if let Err(e) = save_cache(&self.cache_path, entries) {
+            crate::logger::log(&format!(
+                "[TRAILER] Failed to save the trailer cache: {:#}",
+                e
+            ));
+        }
+        Ok(video_id)
+    }
+
+    /// The override for `package`: `Some(None)` hides its trailer. An invalid
+    /// file or video id is logged and ignored.
+    fn override_for(&self, package: &str) -> Option<Option<String>> {
+        let text = std::fs::read_to_string(&self.override_path).ok()?;
+        let mut overrides: HashMap<String, Option<String>> = match serde_json::from_str(&text) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                crate::logger::log(&format!(
+                    "[TRAILER] Ignoring invalid {}: {}",
+                    self.override_path.display(),
+                    e
+                ));
+                return None;
+            }
+        };
+        match overrides.remove(package)? {
+            None => Some(None),
+            Some(text) => match video_id_from(&text) {
+                Some(video_id) => Some(Some(video_id)),
+                None => {
+                    crate::logger::log(&format!(
+                        "[TRAILER] Ignoring override for {package}: '{text}' is not a video id"
+                    ));
+                    None
+                }
+            },
+        }
+    }
+}
+
+fn load_cache(path: &Path) -> HashMap<String, CachedTrailer> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    match serde_json::from_str::<CacheFile>(&text) {
+        Ok(file) if file.version == CACHE_VERSION => file.entries,
+        Ok(file) => {
+            crate::logger::log(&format!(
+                "[TRAILER] Discarding trailer cache version {}",
+                file.version
+            ));
+            HashMap::new()
+        }
+        Err(e) => {
+            crate::logger::log(&format!(
+                "[TRAILER] Discarding invalid trailer cache: {}",
+                e
+            ));
+            HashMap::new()
+        }
+    }
+}
+
+fn save_cache(path: &Path, entries: &HashMap<String, CachedTrailer>) -> Result<()> {
+    #[derive(Serialize)]
+    struct CacheFileRef<'a> {
+        version: u32,
+        entries: &'a HashMap<String, CachedTrailer>,
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&CacheFileRef {
+        version: CACHE_VERSION,
+        entries,
+    })?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// A stand-in for the YouTube results page. Searches for Beat Saber find a
+    /// video, others find nothing; while `failing` is set every request gets
+    /// a 500. Returns the base URL and the request paths seen so far.
+    async fn results_server(
+        failing: Arc<AtomicBool>,
+    ) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buffer).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or("").to_string();
+                seen.lock().unwrap().push(path.clone());
+
+                let (status, body) = if failing.load(Ordering::SeqCst) {
+                    ("500 Internal Server Error", String::new())
+                } else if path.contains("Beat+Saber") {
+                    (
+                        "200 OK",
+                        r#"<a href="/watch?v=vL39Sg2AqWg">x</a><a href="/watch?v=zzzzzzzzzzz">"#
+                            .to_string(),
+                    )
+                } else {
+                    ("200 OK", "<html>No results</html>".to_string())
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (base_url, requests)
+    }
+
+    #[tokio::test]
+    async fn scraper_takes_the_first_result() {
+        let failing = Arc::new(AtomicBool::new(false));
+        let (base_url, requests) = results_server(failing.clone()).await;
+        let scraper = YoutubeScraper::with_base_url(&base_url);
+
+        assert_eq!(
+            scraper.lookup("Beat Saber").await.unwrap(),
+            Some("vL39Sg2AqWg".to_string())
+        );
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            "/results?search_query=Beat+Saber+VR+trailer"
+        );
+        assert_eq!(scraper.lookup("Unknown Game").await.unwrap(), None);
+
+        failing.store(true, Ordering::SeqCst);
+        assert!(scraper.lookup("Beat Saber").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn results_are_cached_on_disk_until_they_expire() {
+        let failing = Arc::new(AtomicBool::new(false));
+        let (base_url, requests) = results_server(failing.clone()).await;
+        let dir = tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+        let service = |url: &str| {
+            TrailerService::with_provider(&cache_dir, Box::new(YoutubeScraper::with_base_url(url)))
+        };
+        let now = 1_700_000_000;
+
+        let first = service(&base_url);
+        assert!(first
+            .lookup_at("Beat Saber", None, now)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(first.lookup_at("Other", None, now).await.unwrap(), None);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // A restart reads both the hit and the miss back from disk
+        let second = service(&base_url);
+        assert!(second
+            .lookup_at(" beat saber ", None, now + 60)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            second.lookup_at("Other", None, now + 60).await.unwrap(),
+            None
+        );
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // Misses expire sooner than hits
+        second
+            .lookup_at("Other", None, now + MISSING_TTL_SECS)
+            .await
+            .unwrap();
+        second
+            .lookup_at("Beat Saber", None, now + MISSING_TTL_SECS)
+            .await
+            .unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // An expired entry is still used when the provider fails
+        failing.store(true, Ordering::SeqCst);
+        assert_eq!(
+            second
+                .lookup_at("Beat Saber", None, now + FOUND_TTL_SECS)
+                .await
+                .unwrap(),
+            Some("vL39Sg2AqWg".to_string())
+        );
+        assert!(second.lookup_at("Never Seen", None, now).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn overrides_win_over_the_provider() {
+        let failing = Arc::new(AtomicBool::new(false));
+        let (base_url, requests) = results_server(failing).await;
+        let dir = tempdir().unwrap();
+        let service = TrailerService::with_provider(
+            &dir.path().join("cache"),
+            Box::new(YoutubeScraper::with_base_url(&base_url)),
+        );
+        std::fs::write(
+            service.override_path(),
+            r#"{"com.beat": "https://www.youtube.com/watch?v=abcdefghijk&t=5", "com.hidden": null, "com.bad": "nope"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            service
+                .lookup("Beat Saber", Some("com.beat"))
+                .await
+                .unwrap(),
+            Some("abcdefghijk".to_string())
+        );
+        assert_eq!(
+            service
+                .lookup("Beat Saber", Some("com.hidden"))
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(requests.lock().unwrap().is_empty());
+
+        // An invalid override falls back to the provider
+        assert_eq!(
+            service.lookup("Beat Saber", Some("com.bad")).await.unwrap(),
+            Some("vL39Sg2AqWg".to_string())
+        );
+        assert_eq!(
+            video_id_from("https://youtu.be/vL39Sg2AqWg"),
+            Some("vL39Sg2AqWg".to_string())
+        );
+        assert_eq!(
+            video_id_from("vL39Sg2AqWg"),
+            Some("vL39Sg2AqWg".to_string())
+        );
+    }
+}
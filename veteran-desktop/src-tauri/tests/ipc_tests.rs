@@ -4,6 +4,7 @@
 //! These tests use the Tauri mock builder to test command handlers.
 
 use veteran_desktop::ipc::commands::AppState;
+use chrono::NaiveDate;
 use veteran_desktop::models::game::Game;
 
 /// Helper function to create a sample game for testing
@@ -12,12 +13,12 @@ fn _sample_game(package_name: &str) -> Game {
         game_name: format!("Test Game {}", package_name),
         release_name: format!("Release {}", package_name),
         package_name: package_name.to_string(),
-        version_code: "1".to_string(),
+        version_code: 1,
         release_apk_path: String::new(),
         version_name: "1.0.0".to_string(),
-        downloads: "100".to_string(),
-        size: "100 MB".to_string(),
-        last_updated: "2024-01-01".to_string(),
+        downloads: 100.0,
+        size_bytes: 100 * 1024 * 1024,
+        last_updated: NaiveDate::from_ymd_opt(2024, 1, 1),
         thumbnail_path: String::new(),
         thumbnail_exists: false,
         note_path: String::new(),
@@ -25,6 +26,8 @@ fn _sample_game(package_name: &str) -> Game {
         note_exists: false,
         popularity_rank: 1,
         is_new: false,
+        source: String::new(),
+        tags: Vec::new(),
     }
 }
 
@@ -353,6 +356,27 @@ async fn test_appstate_event_extra_field() {
     assert!(extra.as_object().unwrap().is_empty(), "Extra should be empty by default");
 }
 
+#[tokio::test]
+async fn test_appstate_notice_event_carries_extra() {
+    let state = AppState::new_for_test();
+
+    state
+        .push_notice_event(
+            "watchlist.release",
+            "op-1",
+            "watchlist",
+            "New release",
+            serde_json::json!({"to_version_code": 12}),
+        )
+        .await;
+
+    let events = state.get_events().await;
+    let event = &events[0];
+    assert_eq!(event["extra"]["to_version_code"], 12);
+    assert_eq!(event["operation"]["state"], "succeeded");
+    assert_eq!(event["operation"]["terminal"], true);
+}
+
 #[tokio::test]
 async fn test_appstate_state_history_structure() {
     let state = AppState::new_for_test();
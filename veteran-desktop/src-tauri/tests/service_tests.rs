@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use veteran_desktop::models::game::Game;
 use veteran_desktop::models::device::RawDeviceInfo;
 use veteran_desktop::services::download::{DownloadService, DownloadStatus, DownloadItem};
@@ -11,12 +12,12 @@ fn sample_game(package_name: &str) -> Game {
         game_name: "Sample Game".to_string(),
         release_name: format!("Sample Release {}", package_name),
         package_name: package_name.to_string(),
-        version_code: "1".to_string(),
+        version_code: 1,
         release_apk_path: String::new(),
         version_name: "1.0.0".to_string(),
-        downloads: "1000".to_string(),
-        size: "500 MB".to_string(),
-        last_updated: "2024-01-01".to_string(),
+        downloads: 1000.0,
+        size_bytes: 500 * 1024 * 1024,
+        last_updated: NaiveDate::from_ymd_opt(2024, 1, 1),
         thumbnail_path: String::new(),
         thumbnail_exists: false,
         note_path: String::new(),
@@ -24,6 +25,8 @@ fn sample_game(package_name: &str) -> Game {
         note_exists: false,
         popularity_rank: 1,
         is_new: false,
+        source: String::new(),
+        tags: Vec::new(),
     }
 }
 
@@ -32,12 +35,12 @@ fn sample_game_with_release(package_name: &str, release_name: &str) -> Game {
         game_name: "Sample Game".to_string(),
         release_name: release_name.to_string(),
         package_name: package_name.to_string(),
-        version_code: "1".to_string(),
+        version_code: 1,
         release_apk_path: String::new(),
         version_name: "1.0.0".to_string(),
-        downloads: "1000".to_string(),
-        size: "500 MB".to_string(),
-        last_updated: "2024-01-01".to_string(),
+        downloads: 1000.0,
+        size_bytes: 500 * 1024 * 1024,
+        last_updated: NaiveDate::from_ymd_opt(2024, 1, 1),
         thumbnail_path: String::new(),
         thumbnail_exists: false,
         note_path: String::new(),
@@ -45,6 +48,8 @@ fn sample_game_with_release(package_name: &str, release_name: &str) -> Game {
         note_exists: false,
         popularity_rank: 1,
         is_new: false,
+        source: String::new(),
+        tags: Vec::new(),
     }
 }
 